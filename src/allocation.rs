@@ -0,0 +1,142 @@
+use crate::cards::escape_html;
+use crate::config::EventConfig;
+use crate::output::csv_field;
+use crate::timefmt::{format_clock, ClockStyle};
+use crate::{window_clock_ranges, Competitor, SavedDraw};
+
+// every window's competitors, alphabetically, case aside: the allocation is
+// published before the start times, and must read the same whatever order
+// the shuffle left them in
+fn alphabetical(saved: &SavedDraw) -> Vec<Vec<&Competitor>> {
+    saved
+        .windows
+        .iter()
+        .map(|window| {
+            let mut competitors: Vec<&Competitor> = window.competitors.iter().collect();
+            competitors.sort_by_cached_key(|c| (c.name.to_lowercase(), c.name.clone()));
+            competitors
+        })
+        .collect()
+}
+
+// a pinned start's clock time is fixed before the draw, so it gives nothing away
+fn pinned_clock(competitor: &Competitor, config: &EventConfig) -> Option<String> {
+    let style = ClockStyle {
+        unit: config.time_unit,
+        ..ClockStyle::default()
+    };
+    competitor
+        .fixed_offset
+        .map(|offset| format_clock(config.start_time, offset, style))
+}
+
+/// Lists each window's competitors alphabetically under a header with the
+/// window's clock range and how many start in it, a pinned start with its
+/// time and a competitor with a club with it.
+pub fn allocation_text(saved: &SavedDraw, config: &EventConfig) -> String {
+    let mut text = String::new();
+    for (i, (competitors, (from, to))) in alphabetical(saved)
+        .into_iter()
+        .zip(window_clock_ranges(&saved.windows, config))
+        .enumerate()
+    {
+        text.push_str(&format!(
+            "Window {}: {}–{}, {} competitors\n",
+            i + 1,
+            from,
+            to,
+            competitors.len()
+        ));
+        for competitor in competitors {
+            text.push_str(&format!("  {}", competitor.name));
+            if let Some(club) = &competitor.club {
+                text.push_str(&format!(" ({})", club));
+            }
+            if let Some(clock) = pinned_clock(competitor, config) {
+                text.push_str(&format!(", starts {}", clock));
+            }
+            text.push('\n');
+        }
+    }
+    text
+}
+
+/// Writes the allocation as a windows CSV that `input::windows_from_csv`
+/// reads back for the finalize phase: `window_id,from,to,count,duration,
+/// competitor_name,club,locked,pinned_offset`, one row per competitor in
+/// alphabetical order, or one without a name for an empty window. Every
+/// window is locked, so drawing the file again shuffles each window but
+/// moves nobody out of the one they were published in.
+pub fn allocation_csv(saved: &SavedDraw, config: &EventConfig) -> String {
+    let mut csv = String::from(
+        "window_id,from,to,count,duration,competitor_name,club,locked,pinned_offset\n",
+    );
+    for (i, ((competitors, (from, to)), window)) in alphabetical(saved)
+        .into_iter()
+        .zip(window_clock_ranges(&saved.windows, config))
+        .zip(&saved.windows)
+        .enumerate()
+    {
+        let row = |competitor: Option<&Competitor>| {
+            format!(
+                "{},{},{},{},{},{},{},true,{}\n",
+                i + 1,
+                from,
+                to,
+                competitors.len(),
+                window.duration,
+                csv_field(competitor.map_or("", |c| c.name.as_str())),
+                csv_field(competitor.and_then(|c| c.club.as_deref()).unwrap_or("")),
+                competitor
+                    .and_then(|c| c.fixed_offset)
+                    .map_or(String::new(), |offset| offset.to_string())
+            )
+        };
+        if competitors.is_empty() {
+            csv.push_str(&row(None));
+        }
+        for &competitor in &competitors {
+            csv.push_str(&row(Some(competitor)));
+        }
+    }
+    csv
+}
+
+/// Renders the allocation as an HTML page to publish: a section per window
+/// with its clock range, its count and its competitors alphabetically.
+pub fn allocation_html(saved: &SavedDraw, config: &EventConfig) -> String {
+    let mut html = String::from(
+        "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n\
+         <title>Start groups</title>\n</head>\n<body>\n<h1>Start groups</h1>\n",
+    );
+    for (i, (competitors, (from, to))) in alphabetical(saved)
+        .into_iter()
+        .zip(window_clock_ranges(&saved.windows, config))
+        .enumerate()
+    {
+        html.push_str(&format!(
+            "<section class=\"window\">\n<h2>Window {}: {}–{}</h2>\n\
+             <p class=\"count\">{} competitors</p>\n<ul>\n",
+            i + 1,
+            from,
+            to,
+            competitors.len()
+        ));
+        for competitor in competitors {
+            html.push_str(&format!("<li>{}", escape_html(&competitor.name)));
+            if let Some(club) = &competitor.club {
+                html.push_str(&format!(
+                    " <span class=\"club\">{}</span>",
+                    escape_html(club)
+                ));
+            }
+            if let Some(clock) = pinned_clock(competitor, config) {
+                html.push_str(&format!(" <span class=\"pinned\">{}</span>", clock));
+            }
+            html.push_str("</li>\n");
+        }
+        html.push_str("</ul>\n</section>\n");
+    }
+    html.push_str("</body>\n</html>\n");
+    html
+}
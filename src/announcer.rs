@@ -0,0 +1,112 @@
+use chrono::NaiveTime;
+
+use crate::config::{expected_finish, FinishEstimate};
+use crate::timefmt::{format_clock, ClockStyle, TimeUnit};
+use crate::{CompetitorWithOffset, Minutes};
+
+pub struct AnnouncerOptions {
+    pub lead_text: String,
+    pub call_next: bool,
+    pub time_unit: TimeUnit, // the unit the offsets count
+}
+
+impl Default for AnnouncerOptions {
+    fn default() -> Self {
+        AnnouncerOptions {
+            lead_text: "to the start".to_string(),
+            call_next: false,
+            time_unit: TimeUnit::Minutes,
+        }
+    }
+}
+
+/// Renders one line per start in clock order, e.g. "At 09:03, Jane Doe to the start."
+/// With `call_next` the following starter is called up on the same line.
+pub fn announcer_script(
+    competitors: &[CompetitorWithOffset],
+    start_time: NaiveTime,
+    options: &AnnouncerOptions,
+) -> String {
+    let mut ordered: Vec<&CompetitorWithOffset> = competitors.iter().collect();
+    ordered.sort_by_key(|c| c.offset);
+    let style = ClockStyle {
+        unit: options.time_unit,
+        ..ClockStyle::default()
+    };
+
+    let mut script = String::new();
+    for (i, competitor_with_offset) in ordered.iter().enumerate() {
+        script.push_str(&format!(
+            "At {}, {} {}.",
+            format_clock(start_time, competitor_with_offset.offset, style),
+            competitor_with_offset.competitor.name,
+            options.lead_text
+        ));
+        if options.call_next {
+            if let Some(next) = ordered.get(i + 1) {
+                script.push_str(&format!(" Next: {}.", next.competitor.name));
+            }
+        }
+        script.push('\n');
+    }
+    script
+}
+
+/// Renders the speaker's finish sheet: one line per start in order of
+/// expected finish by the class `estimates`, e.g.
+/// "09:47–09:53 Jane Doe, W21, OK Linné, started 09:02". Starts whose class
+/// has no estimate follow under "No finish estimate:" in clock order.
+pub fn commentary_sheet(
+    competitors: &[CompetitorWithOffset],
+    start_time: NaiveTime,
+    unit: TimeUnit,
+    estimates: &[FinishEstimate],
+) -> String {
+    let style = ClockStyle {
+        unit,
+        ..ClockStyle::default()
+    };
+    let describe = |start: &CompetitorWithOffset| {
+        let competitor = &start.competitor;
+        let mut line = competitor.name.clone();
+        for detail in [&competitor.category, &competitor.club]
+            .into_iter()
+            .flatten()
+        {
+            line.push_str(&format!(", {}", detail));
+        }
+        line.push_str(&format!(
+            ", started {}",
+            format_clock(start_time, start.offset, style)
+        ));
+        line
+    };
+
+    let mut expected: Vec<((Minutes, Minutes), &CompetitorWithOffset)> = Vec::new();
+    let mut unexpected: Vec<&CompetitorWithOffset> = Vec::new();
+    for start in competitors {
+        match expected_finish(estimates, start) {
+            Some(finish) => expected.push((finish, start)),
+            None => unexpected.push(start),
+        }
+    }
+    expected.sort_by_key(|&(finish, start)| (finish, start.offset));
+    unexpected.sort_by_key(|c| c.offset);
+
+    let mut sheet = String::new();
+    for ((from, to), start) in expected {
+        sheet.push_str(&format!(
+            "{}–{} {}\n",
+            format_clock(start_time, from, style),
+            format_clock(start_time, to, style),
+            describe(start)
+        ));
+    }
+    if !unexpected.is_empty() {
+        sheet.push_str("No finish estimate:\n");
+        for start in unexpected {
+            sheet.push_str(&format!("  {}\n", describe(start)));
+        }
+    }
+    sheet
+}
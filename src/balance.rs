@@ -0,0 +1,107 @@
+use std::fmt;
+
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+
+use crate::config::{ConfigError, EventConfig};
+use crate::{stabilize_windows, Competitor, Minutes, Window};
+
+/// What stabilization would do to windows with the given entry counts.
+pub struct BalanceResult {
+    pub counts_before: Vec<usize>,
+    pub counts_after: Vec<usize>,
+    pub spacings_before: Vec<f64>,
+    pub spacings_after: Vec<f64>,
+    // net competitors moved across the boundary after each window except the
+    // last; positive moved into the later window, negative into the earlier
+    pub boundary_moves: Vec<isize>,
+}
+
+#[derive(Debug)]
+pub enum BalanceError {
+    // every window needs both a count and a duration
+    LengthMismatch { counts: usize, durations: usize },
+    Config(ConfigError),
+}
+
+impl From<ConfigError> for BalanceError {
+    fn from(error: ConfigError) -> Self {
+        BalanceError::Config(error)
+    }
+}
+
+impl fmt::Display for BalanceError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            BalanceError::LengthMismatch { counts, durations } => write!(
+                f,
+                "{} counts but {} durations; every window needs both",
+                counts, durations
+            ),
+            BalanceError::Config(error) => error.fmt(f),
+        }
+    }
+}
+
+impl std::error::Error for BalanceError {}
+
+/// Runs stabilization on anonymous competitors, for planning before any names
+/// are known. `counts` and `durations` are per window; lists of different
+/// lengths are an error.
+pub fn balance_counts(
+    counts: &[usize],
+    durations: &[Minutes],
+    spacing_threshold: Minutes,
+) -> Result<BalanceResult, BalanceError> {
+    if counts.len() != durations.len() {
+        return Err(BalanceError::LengthMismatch {
+            counts: counts.len(),
+            durations: durations.len(),
+        });
+    }
+    // min_spacing plays no part in stabilization
+    let config = EventConfig::builder()
+        .spacing_threshold(spacing_threshold)
+        .min_spacing(1)
+        .build()?;
+
+    let mut windows: Vec<Window> = counts
+        .iter()
+        .zip(durations)
+        .map(|(&count, &duration)| {
+            // String::new() does not allocate, so anonymous competitors stay cheap
+            Window::new(duration, (0..count).map(|_| Competitor::new(String::new())))
+        })
+        .collect();
+    let spacings_before = windows
+        .iter()
+        .map(|w| w.calculate_spacing(w.duration))
+        .collect();
+    // the default displacement selection never draws from the rng
+    stabilize_windows(&mut windows, &config, &mut StdRng::seed_from_u64(0));
+
+    let counts_after: Vec<usize> = windows.iter().map(|w| w.competitors.len()).collect();
+    // competitors only ever move one window at a time, so whatever a prefix of
+    // windows lost went across the boundary right after it
+    let mut boundary_moves = Vec::with_capacity(counts.len().saturating_sub(1));
+    let mut flow = 0;
+    for (&before, &after) in counts
+        .iter()
+        .zip(&counts_after)
+        .take(counts.len().saturating_sub(1))
+    {
+        flow += before as isize - after as isize;
+        boundary_moves.push(flow);
+    }
+
+    Ok(BalanceResult {
+        counts_before: counts.to_vec(),
+        spacings_after: windows
+            .iter()
+            .map(|w| w.calculate_spacing(w.duration))
+            .collect(),
+        counts_after,
+        spacings_before,
+        boundary_moves,
+    })
+}
@@ -0,0 +1,124 @@
+use chrono::NaiveTime;
+
+use crate::output::verification_code;
+use crate::timefmt::{format_clock, ClockStyle, TimeUnit};
+use crate::{CompetitorWithOffset, Minutes};
+
+pub struct CardOptions {
+    // how long before the start competitors are called up, in time_unit;
+    // three minutes when not given
+    pub call_up: Option<Minutes>,
+    pub only_window: Option<usize>,
+    pub time_unit: TimeUnit,    // the unit the offsets count
+    pub map_scale: Option<u32>, // the denominator, e.g. 10000 for 1:10000
+}
+
+impl Default for CardOptions {
+    fn default() -> Self {
+        CardOptions {
+            call_up: None,
+            only_window: None,
+            time_unit: TimeUnit::Minutes,
+            map_scale: None,
+        }
+    }
+}
+
+const STYLE: &str = "\
+@page { size: A4; margin: 10mm; }
+body { margin: 0; font-family: sans-serif; }
+.sheet { display: grid; grid-template-columns: repeat(2, 1fr); grid-template-rows: repeat(4, 68mm); \
+gap: 2mm; page-break-after: always; }
+.card { border: 1px dashed #888; padding: 4mm; overflow: hidden; }
+.name { font-weight: bold; white-space: nowrap; font-size: min(7mm, calc(150mm / var(--chars))); }
+.start { font-size: 12mm; margin-top: 6mm; }
+.call-up { color: #444; }
+.class, .bib, .map-scale { display: inline-block; margin-right: 6mm; }
+";
+
+const CARDS_PER_SHEET: usize = 8;
+
+/// Renders a print-ready HTML page with one start card per competitor, eight
+/// to an A4 sheet, in start-time order so the cut cards come out sorted. A
+/// card shows the competitor's class when they have a category, their bib
+/// and the map scale when the options give one. Bibs run from 1 in clock
+/// order over the whole list, as in the other exports, even when only one
+/// window is printed.
+pub fn start_cards_html(
+    competitors: &[CompetitorWithOffset],
+    start_time: NaiveTime,
+    options: &CardOptions,
+) -> String {
+    let mut ordered: Vec<&CompetitorWithOffset> = competitors.iter().collect();
+    ordered.sort_by_key(|c| c.offset);
+    let ordered: Vec<(usize, &CompetitorWithOffset)> = ordered
+        .into_iter()
+        .enumerate()
+        .map(|(i, c)| (i + 1, c))
+        .filter(|(_, c)| options.only_window.is_none_or(|window| c.window == window))
+        .collect();
+    let style = ClockStyle {
+        unit: options.time_unit,
+        ..ClockStyle::default()
+    };
+    let call_up = options
+        .call_up
+        .unwrap_or_else(|| options.time_unit.from_minutes(3));
+
+    let mut html = format!(
+        "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n\
+         <meta name=\"verification-code\" content=\"{}\">\n<title>Start cards</title>\n\
+         <style>\n{}</style>\n</head>\n<body>\n",
+        verification_code(competitors),
+        STYLE
+    );
+    for sheet in ordered.chunks(CARDS_PER_SHEET) {
+        html.push_str("<div class=\"sheet\">\n");
+        for (bib, competitor_with_offset) in sheet {
+            let offset = competitor_with_offset.offset;
+            let name = &competitor_with_offset.competitor.name;
+            let mut details = String::new();
+            if let Some(class) = &competitor_with_offset.competitor.category {
+                details.push_str(&format!(
+                    "<span class=\"class\">{}</span>",
+                    escape_html(class)
+                ));
+            }
+            details.push_str(&format!("<span class=\"bib\">Bib {}</span>", bib));
+            if let Some(map_scale) = options.map_scale {
+                details.push_str(&format!(
+                    "<span class=\"map-scale\">Map 1:{}</span>",
+                    map_scale
+                ));
+            }
+            html.push_str(&format!(
+                "<div class=\"card\"><div class=\"name\" style=\"--chars: {}\">{}</div>\
+<div class=\"details\">{}</div>\
+<div class=\"start\">{}</div><div class=\"call-up\">Call-up {}</div></div>\n",
+                name.chars().count().max(1),
+                escape_html(name),
+                details,
+                format_clock(start_time, offset, style),
+                format_clock(start_time, offset - call_up, style)
+            ));
+        }
+        html.push_str("</div>\n");
+    }
+    html.push_str("</body>\n</html>\n");
+    html
+}
+
+pub(crate) fn escape_html(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&#39;"),
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
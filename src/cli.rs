@@ -0,0 +1,1056 @@
+use std::str::FromStr;
+
+use chrono::{NaiveDate, NaiveTime};
+
+use start_list_generator::announcer::AnnouncerOptions;
+use start_list_generator::cards::CardOptions;
+use start_list_generator::config::{
+    ConstraintKind, DisplacementSelection, OffsetPattern, RollingCap, Taper,
+};
+use start_list_generator::demo::DemoSize;
+use start_list_generator::provenance::Embedding;
+use start_list_generator::sync::SyncPolicy;
+use start_list_generator::timefmt::{parse_clock, parse_offset, TimeUnit};
+use start_list_generator::Minutes;
+
+pub const USAGE: &str =
+    "usage: [ENTRIES [--max-skipped N] [--reuse-windows]] [--demo [small|medium|large]] [--time-unit minutes|seconds] \
+     [--min-spacing N] [--spacing-threshold N] [--announcer [--lead-text TEXT] [--call-next]] \
+     [--rolling-cap STARTS/N] [--block HH:MM-HH:MM]... [--taper N*FACTOR] \
+     [--alternate-categories] [--same-club-spacing N] [--max-per-club N] [--spacing-divisor N] \
+     [--first-start-offset N|H:MM[:SS]] [--lock WINDOW]... \
+     [--no-shuffle] [--reject-empty] [--shuffle-alarm THRESHOLD] [--displacement deque-ends|random|volunteer] \
+     [--cards [--call-up N] [--only-window WINDOW] [--map-scale N]] [--adaptive-threshold] [--random-tie-break] \
+     [--ceremonial NAME@HH:MM]... [--pin NAME@HH:MM[~N]]... [--sweeper NAME@WINDOW]... [--require-sweeper] [--seed N] [--input EVENT.toml] \
+     [--shuttle HH:MM+N]... [--shuttle-margin N] [--repair-shuttles] [--repair-duty-shifts] \
+     [--allowed-offsets even|odd|every:N:PHASE|only:M,M,...] [--avoid LIST.csv [--clearance N]] \
+     [--relax rolling-cap|allowed-offsets|blocked[,...]] \
+     [--format text|csv|iof-xml|html|commentary [--date YYYY-MM-DD] [--no-js]] [--output FILE] [--save-draw FILE] [--allocation FILE.csv|.html|.txt] [--witness FILE] \
+     [--emit text|csv|iof-xml|html|commentary|cards|announcer[,OPTION=VALUE]...=FILE]... \
+     [--select EXPRESSION [--renumber]] [--provenance basic|full] [--variant draft|final [--force]]\n\
+     ENTRIES is a windows CSV (window_id,duration,competitor_name,...), or with --input \
+     a window,name[,club] CSV of entries for the event file's windows; rows it cannot read are \
+     skipped with a warning, and --max-skipped N gives up after N. ENTRIES may also be a \
+     start list CSV this tool wrote, e.g. last year's, with --input: its start times are \
+     dropped and its competitors dealt out over the event's windows, or with \
+     --reuse-windows put back in the windows they started in. An --allocation CSV is \
+     itself a windows CSV with every window locked: drawing it again fixes the times without \
+     moving anyone out of their published window. \
+     --select keeps only the starts an expression matches in every output, e.g. \
+     'club=OK Linné & time<10:00 | flag=pinned': terms club=, class=, window=N[,N...], \
+     time<, <=, >, >= HH:MM and flag=ceremonial|sweeper|pinned, & binding tighter than |, \
+     with parentheses. The text and CSV outputs keep each start's position in the full \
+     list unless --renumber is given. \
+     The html format is one self-contained page with a box to search names and clubs; \
+     --no-js, or the emit option no-js, leaves out its script and keeps the plain table. \
+     The event file's [[class]] sections estimate each class's winning time and spread: \
+     the csv and html formats then add every start's expected finish, blank for a class \
+     without an estimate, and the commentary format lists the starts by expected finish. \
+     Every file written, and the list printed, starts with its provenance: the version, the \
+     seed, a hash of the files read and the time, from SOURCE_DATE_EPOCH if set; \
+     --provenance full adds the host and user. \
+     --variant final checks the list against min_spacing, the pinned starts and the locked \
+     windows before writing anything, and refuses with the findings unless --force is given; \
+     the default draft only warns. A --select export is never final. \
+     Every N counts the event's time unit: minutes, or seconds with --time-unit seconds or \
+     time_unit = \"seconds\" in the event file. Clock times are HH:MM.";
+
+pub const BALANCE_USAGE: &str =
+    "usage: balance --counts N,N,... --durations N,N,... [--spacing-threshold N]\n\
+     The durations and the threshold count the same time unit, whichever it is.";
+
+pub const RETIME_USAGE: &str =
+    "usage: retime [--min-spacing N] [--spacing-threshold N] [--seed N] DRAW.toml\n\
+     The spacings default to the draw file's, then to 2 and 3, and count its time unit.";
+
+pub const SYNC_USAGE: &str =
+    "usage: sync --entries ENTRIES.csv [--redraw] [--seed N] [--output FILE] DRAW.toml\n\
+     Applies the updated window,name[,club] entries to a saved draw and writes it back, or \
+     to --output. A window the entries oversubscribe is an error, or with --redraw is drawn \
+     again together with its neighbours.";
+
+pub const VERIFY_USAGE: &str = "usage: verify --against-constraints DRAW.toml LIST.csv\n\
+     Checks a start list CSV, e.g. one the timing company edited, against the constraints \
+     the saved draw records, and lists every violation.";
+
+pub const PROVENANCE_USAGE: &str = "usage: provenance FILE\n\
+     Prints the provenance a start list, card sheet, saved draw or report of this tool \
+     carries: its version and algorithm version, the seed, the hash of the files it read, \
+     when it was made and, with --provenance full, on which host by whom.";
+
+pub const AB_TEST_USAGE: &str =
+    "usage: ab-test [--entries ENTRIES] [--seed N] [--json] A.toml B.toml\n\
+     Draws the same entries with the settings of two event files, both from the same seed, \
+     and reports the last start, the gaps, the competitors moved out of their window, the \
+     soft constraints broken and every start time that differs. The entries are ENTRIES, \
+     with the first file's windows unless it is a windows CSV, or else the first file's own.";
+
+/// The name and version, and the cargo features the binary was built with.
+pub fn version() -> String {
+    let features: Vec<&str> = [("no-entropy", cfg!(feature = "no-entropy"))]
+        .into_iter()
+        .filter(|&(_, enabled)| enabled)
+        .map(|(name, _)| name)
+        .collect();
+    format!(
+        "{} {} (features: {})",
+        env!("CARGO_PKG_NAME"),
+        env!("CARGO_PKG_VERSION"),
+        if features.is_empty() {
+            "none".to_string()
+        } else {
+            features.join(", ")
+        }
+    )
+}
+
+#[derive(Clone, Copy, Default, PartialEq, Eq)]
+pub enum OutputFormat {
+    #[default]
+    Text,
+    Csv,
+    IofXml,
+    Html,
+    Commentary,
+}
+
+impl FromStr for OutputFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "text" => Ok(OutputFormat::Text),
+            "csv" => Ok(OutputFormat::Csv),
+            "iof-xml" => Ok(OutputFormat::IofXml),
+            "html" => Ok(OutputFormat::Html),
+            "commentary" => Ok(OutputFormat::Commentary),
+            _ => Err(format!(
+                "unknown format '{}', expected text, csv, iof-xml, html or commentary",
+                s
+            )),
+        }
+    }
+}
+
+/// One artifact to write from the generated list, e.g.
+/// `--emit cards,call-up=5=web/cards.html`.
+pub struct Emit {
+    pub render: Render,
+    pub path: String,
+}
+
+pub enum Render {
+    Text,
+    Csv,
+    IofXml { date: Option<NaiveDate> }, // today if not given
+    Html { search: bool },              // the search box's inline script
+    Commentary,
+    Cards(CardOptions),
+    Announcer(AnnouncerOptions),
+}
+
+impl Render {
+    /// How the rendered artifact carries its provenance.
+    pub fn embedding(&self) -> Embedding {
+        match self {
+            Render::IofXml { .. } => Embedding::Xml,
+            Render::Html { .. } | Render::Cards(_) => Embedding::Html,
+            Render::Text | Render::Csv | Render::Commentary | Render::Announcer(_) => {
+                Embedding::Comments
+            }
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Render::Text => "text",
+            Render::Csv => "csv",
+            Render::IofXml { .. } => "iof-xml",
+            Render::Html { .. } => "html",
+            Render::Commentary => "commentary",
+            Render::Cards(_) => "cards",
+            Render::Announcer(_) => "announcer",
+        }
+    }
+}
+
+#[derive(Default)]
+pub struct Options {
+    pub demo: Option<DemoSize>,
+    pub announcer: Option<AnnouncerOptions>,
+    pub rolling_cap: Option<RollingCap>,
+    pub blocked: Vec<(NaiveTime, NaiveTime)>,
+    pub taper: Option<Taper>,
+    pub alternate_categories: bool,
+    pub same_club_min_spacing: Option<Minutes>, // in the time unit, like min_spacing
+    pub max_per_club_per_window: Option<usize>,
+    pub spacing_divisor: Option<Minutes>,
+    pub first_start_offset: Option<String>, // read once the time unit is known
+    pub locked_windows: Vec<usize>,         // 1-based, as printed in competitor names
+    pub no_shuffle: bool,
+    pub reject_empty: bool,
+    pub shuffle_alarm_threshold: Option<f64>,
+    pub displacement_selection: Option<DisplacementSelection>,
+    pub cards: Option<CardOptions>,
+    pub adaptive_threshold: bool,
+    pub random_tie_break: bool,
+    pub seed: Option<u64>,
+    pub input: Option<String>,
+    pub entries: Option<String>, // read by the first importer that detects it
+    pub max_skipped: Option<usize>,
+    pub time_unit: Option<TimeUnit>,
+    // every span below counts the time unit, like the window durations
+    pub min_spacing: Option<Minutes>,
+    pub spacing_threshold: Option<Minutes>,
+    pub allowed_offsets: Option<OffsetPattern>,
+    // dropped in this order, as few as needed, when starts overrun their window
+    pub relaxation_order: Vec<ConstraintKind>,
+    pub avoid: Option<String>, // another start list whose minutes to keep clear of
+    pub clearance: Minutes,
+    pub ceremonial_starts: Vec<(String, NaiveTime)>,
+    // entered competitors who must start at that time, with nobody else
+    // starting within the exclusive radius
+    pub pinned: Vec<(String, NaiveTime, Minutes)>,
+    pub sweepers: Vec<(String, usize)>, // window is 1-based
+    pub require_sweeper: bool,
+    pub shuttles: Vec<(NaiveTime, Minutes)>, // departure and travel time
+    pub shuttle_margin: Minutes,
+    pub repair_shuttles: bool,
+    // swap starts out of their club's duty shift from the event file
+    pub repair_duty_shifts: bool,
+    pub format: OutputFormat,
+    pub output: Option<String>,
+    pub date: Option<NaiveDate>, // for IOF XML, today if not given
+    pub no_js: bool,             // HTML without the search script
+    pub save_draw: Option<String>,
+    // the published start groups: .csv, .html, or text otherwise
+    pub allocation: Option<String>,
+    pub witness: Option<String>, // where to write the draw witness
+    pub emits: Vec<Emit>,
+    // a start list read back as entries keeps its windows
+    pub reuse_windows: bool,
+    pub select: Option<String>, // parsed once the start time is known
+    pub renumber: bool,
+    pub full_provenance: bool, // the host and user as well
+    // checked before anything is written; a draft only warns
+    pub final_variant: bool,
+    pub force: bool, // write a final list its checks fail
+}
+
+pub fn parse_args(args: impl IntoIterator<Item = String>) -> Result<Options, String> {
+    let mut options = Options::default();
+    let mut args = args.into_iter().peekable();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--demo" => {
+                let size = match args.next_if(|next| !next.starts_with("--")) {
+                    Some(size) => size.parse()?,
+                    None => DemoSize::Small,
+                };
+                options.demo = Some(size);
+            }
+            "--announcer" => {
+                options
+                    .announcer
+                    .get_or_insert_with(AnnouncerOptions::default);
+            }
+            "--lead-text" => {
+                let lead_text = args
+                    .next()
+                    .ok_or_else(|| "--lead-text needs a value".to_string())?;
+                options
+                    .announcer
+                    .get_or_insert_with(AnnouncerOptions::default)
+                    .lead_text = lead_text;
+            }
+            "--call-next" => {
+                options
+                    .announcer
+                    .get_or_insert_with(AnnouncerOptions::default)
+                    .call_next = true;
+            }
+            "--rolling-cap" => {
+                let value = args
+                    .next()
+                    .ok_or_else(|| "--rolling-cap needs a value".to_string())?;
+                options.rolling_cap = Some(parse_rolling_cap(&value)?);
+            }
+            "--block" => {
+                let value = args
+                    .next()
+                    .ok_or_else(|| "--block needs a value".to_string())?;
+                options.blocked.push(parse_clock_range(&value)?);
+            }
+            "--taper" => {
+                let value = args
+                    .next()
+                    .ok_or_else(|| "--taper needs a value".to_string())?;
+                options.taper = Some(parse_taper(&value)?);
+            }
+            "--alternate-categories" => options.alternate_categories = true,
+            "--same-club-spacing" => {
+                let value = args
+                    .next()
+                    .ok_or_else(|| "--same-club-spacing needs a value".to_string())?;
+                match value.parse() {
+                    Ok(spacing) if spacing >= 0 => options.same_club_min_spacing = Some(spacing),
+                    _ => return Err(format!("invalid same-club spacing '{}'", value)),
+                }
+            }
+            "--max-per-club" => {
+                let value = args
+                    .next()
+                    .ok_or_else(|| "--max-per-club needs a value".to_string())?;
+                match value.parse() {
+                    Ok(cap) if cap > 0 => options.max_per_club_per_window = Some(cap),
+                    _ => return Err(format!("invalid club cap '{}'", value)),
+                }
+            }
+            "--spacing-divisor" => {
+                let value = args
+                    .next()
+                    .ok_or_else(|| "--spacing-divisor needs a value".to_string())?;
+                match value.parse() {
+                    Ok(divisor) if divisor > 0 => options.spacing_divisor = Some(divisor),
+                    _ => return Err(format!("invalid spacing divisor '{}'", value)),
+                }
+            }
+            "--first-start-offset" => {
+                let value = args
+                    .next()
+                    .ok_or_else(|| "--first-start-offset needs a value".to_string())?;
+                // any offset is a whole number of seconds; the event's unit
+                // is checked once it is known
+                parse_offset(&value, TimeUnit::Seconds)?;
+                options.first_start_offset = Some(value);
+            }
+            "--lock" => {
+                let value = args
+                    .next()
+                    .ok_or_else(|| "--lock needs a window number".to_string())?;
+                let window = value
+                    .parse()
+                    .map_err(|_| format!("invalid window number '{}'", value))?;
+                options.locked_windows.push(window);
+            }
+            "--no-shuffle" => options.no_shuffle = true,
+            "--reject-empty" => options.reject_empty = true,
+            "--shuffle-alarm" => {
+                let value = args
+                    .next()
+                    .ok_or_else(|| "--shuffle-alarm needs a value".to_string())?;
+                let threshold = value
+                    .parse()
+                    .map_err(|_| format!("invalid shuffle alarm threshold '{}'", value))?;
+                options.shuffle_alarm_threshold = Some(threshold);
+            }
+            "--displacement" => {
+                let value = args
+                    .next()
+                    .ok_or_else(|| "--displacement needs a value".to_string())?;
+                let selection = match value.as_str() {
+                    "deque-ends" => DisplacementSelection::DequeEnds,
+                    "random" => DisplacementSelection::Random,
+                    "volunteer" => DisplacementSelection::Volunteer,
+                    _ => return Err(format!("unknown displacement selection '{}'", value)),
+                };
+                options.displacement_selection = Some(selection);
+            }
+            "--cards" => {
+                options.cards.get_or_insert_with(CardOptions::default);
+            }
+            "--call-up" => {
+                let value = args
+                    .next()
+                    .ok_or_else(|| "--call-up needs a value".to_string())?;
+                options
+                    .cards
+                    .get_or_insert_with(CardOptions::default)
+                    .call_up = Some(
+                    value
+                        .parse()
+                        .map_err(|_| format!("invalid call-up '{}'", value))?,
+                );
+            }
+            "--only-window" => {
+                let value = args
+                    .next()
+                    .ok_or_else(|| "--only-window needs a window number".to_string())?;
+                let window: usize = value
+                    .parse()
+                    .ok()
+                    .filter(|&window| window > 0)
+                    .ok_or_else(|| format!("invalid window number '{}'", value))?;
+                options
+                    .cards
+                    .get_or_insert_with(CardOptions::default)
+                    .only_window = Some(window - 1);
+            }
+            "--map-scale" => {
+                let value = args
+                    .next()
+                    .ok_or_else(|| "--map-scale needs a value".to_string())?;
+                options
+                    .cards
+                    .get_or_insert_with(CardOptions::default)
+                    .map_scale = Some(
+                    value
+                        .parse()
+                        .map_err(|_| format!("invalid map scale '{}'", value))?,
+                );
+            }
+            "--adaptive-threshold" => options.adaptive_threshold = true,
+            "--random-tie-break" => options.random_tie_break = true,
+            "--relax" => {
+                let value = args
+                    .next()
+                    .ok_or_else(|| "--relax needs a value".to_string())?;
+                for kind in value.split(',') {
+                    options.relaxation_order.push(match kind.trim() {
+                        "rolling-cap" => ConstraintKind::RollingCap,
+                        "allowed-offsets" => ConstraintKind::AllowedOffsets,
+                        "blocked" => ConstraintKind::BlockedIntervals,
+                        _ => {
+                            return Err(format!(
+                                "unknown constraint '{}', expected rolling-cap, \
+                                 allowed-offsets or blocked",
+                                kind
+                            ))
+                        }
+                    });
+                }
+            }
+            "--ceremonial" => {
+                let value = args
+                    .next()
+                    .ok_or_else(|| "--ceremonial needs a value".to_string())?;
+                let invalid =
+                    || format!("invalid ceremonial start '{}', expected NAME@HH:MM", value);
+                let (name, clock) = value.rsplit_once('@').ok_or_else(invalid)?;
+                options
+                    .ceremonial_starts
+                    .push((name.to_string(), parse_clock(clock)?));
+            }
+            "--pin" => {
+                let value = args
+                    .next()
+                    .ok_or_else(|| "--pin needs a value".to_string())?;
+                let invalid =
+                    || format!("invalid pinned start '{}', expected NAME@HH:MM[~N]", value);
+                let (name, clock) = value.rsplit_once('@').ok_or_else(invalid)?;
+                let (clock, radius) = match clock.split_once('~') {
+                    Some((clock, radius)) => (
+                        clock,
+                        radius
+                            .parse()
+                            .ok()
+                            .filter(|&radius| radius >= 0)
+                            .ok_or_else(invalid)?,
+                    ),
+                    None => (clock, 0),
+                };
+                options
+                    .pinned
+                    .push((name.to_string(), parse_clock(clock)?, radius));
+            }
+            "--sweeper" => {
+                let value = args
+                    .next()
+                    .ok_or_else(|| "--sweeper needs a value".to_string())?;
+                let invalid = || format!("invalid sweeper '{}', expected NAME@WINDOW", value);
+                let (name, window) = value.rsplit_once('@').ok_or_else(invalid)?;
+                let window = window
+                    .parse()
+                    .ok()
+                    .filter(|&window| window > 0)
+                    .ok_or_else(invalid)?;
+                options.sweepers.push((name.to_string(), window));
+            }
+            "--require-sweeper" => options.require_sweeper = true,
+            "--shuttle" => {
+                let value = args
+                    .next()
+                    .ok_or_else(|| "--shuttle needs a value".to_string())?;
+                options.shuttles.push(parse_shuttle(&value)?);
+            }
+            "--shuttle-margin" => {
+                let value = args
+                    .next()
+                    .ok_or_else(|| "--shuttle-margin needs a value".to_string())?;
+                options.shuttle_margin = value
+                    .parse()
+                    .map_err(|_| format!("invalid shuttle margin '{}'", value))?;
+            }
+            "--repair-shuttles" => options.repair_shuttles = true,
+            "--repair-duty-shifts" => options.repair_duty_shifts = true,
+            "--allowed-offsets" => {
+                let value = args
+                    .next()
+                    .ok_or_else(|| "--allowed-offsets needs a value".to_string())?;
+                options.allowed_offsets = Some(parse_offset_pattern(&value)?);
+            }
+            "--avoid" => {
+                let path = args
+                    .next()
+                    .ok_or_else(|| "--avoid needs a file path".to_string())?;
+                options.avoid = Some(path);
+            }
+            "--clearance" => {
+                let value = args
+                    .next()
+                    .ok_or_else(|| "--clearance needs a value".to_string())?;
+                options.clearance = value
+                    .parse()
+                    .ok()
+                    .filter(|&clearance| clearance >= 0)
+                    .ok_or_else(|| format!("invalid clearance '{}'", value))?;
+            }
+            "--format" => {
+                let value = args
+                    .next()
+                    .ok_or_else(|| "--format needs a value".to_string())?;
+                options.format = value.parse()?;
+            }
+            "--no-js" => options.no_js = true,
+            "--time-unit" => {
+                let value = args
+                    .next()
+                    .ok_or_else(|| "--time-unit needs a value".to_string())?;
+                options.time_unit = Some(value.parse()?);
+            }
+            "--min-spacing" => {
+                let value = args
+                    .next()
+                    .ok_or_else(|| "--min-spacing needs a value".to_string())?;
+                let min_spacing = value
+                    .parse()
+                    .map_err(|_| format!("invalid min spacing '{}'", value))?;
+                options.min_spacing = Some(min_spacing);
+            }
+            "--spacing-threshold" => {
+                let value = args
+                    .next()
+                    .ok_or_else(|| "--spacing-threshold needs a value".to_string())?;
+                let spacing_threshold = value
+                    .parse()
+                    .map_err(|_| format!("invalid spacing threshold '{}'", value))?;
+                options.spacing_threshold = Some(spacing_threshold);
+            }
+            "--emit" => {
+                let value = args
+                    .next()
+                    .ok_or_else(|| "--emit needs a value".to_string())?;
+                options.emits.push(parse_emit(&value)?);
+            }
+            "--witness" => {
+                let path = args
+                    .next()
+                    .ok_or_else(|| "--witness needs a file".to_string())?;
+                options.witness = Some(path);
+            }
+            "--allocation" => {
+                let path = args
+                    .next()
+                    .ok_or_else(|| "--allocation needs a file path".to_string())?;
+                options.allocation = Some(path);
+            }
+            "--select" => {
+                let value = args
+                    .next()
+                    .ok_or_else(|| "--select needs an expression".to_string())?;
+                options.select = Some(value);
+            }
+            "--renumber" => options.renumber = true,
+            "--provenance" => {
+                let value = args
+                    .next()
+                    .ok_or_else(|| "--provenance needs a value".to_string())?;
+                options.full_provenance = match value.as_str() {
+                    "basic" => false,
+                    "full" => true,
+                    _ => {
+                        return Err(format!(
+                            "unknown provenance '{}', expected basic or full",
+                            value
+                        ))
+                    }
+                };
+            }
+            "--variant" => {
+                let value = args
+                    .next()
+                    .ok_or_else(|| "--variant needs a value".to_string())?;
+                options.final_variant = match value.as_str() {
+                    "draft" => false,
+                    "final" => true,
+                    _ => {
+                        return Err(format!(
+                            "unknown variant '{}', expected draft or final",
+                            value
+                        ))
+                    }
+                };
+            }
+            "--force" => options.force = true,
+            "--reuse-windows" => options.reuse_windows = true,
+            "--save-draw" => {
+                let path = args
+                    .next()
+                    .ok_or_else(|| "--save-draw needs a file path".to_string())?;
+                options.save_draw = Some(path);
+            }
+            "--output" => {
+                let path = args
+                    .next()
+                    .ok_or_else(|| "--output needs a file path".to_string())?;
+                options.output = Some(path);
+            }
+            "--date" => {
+                let value = args
+                    .next()
+                    .ok_or_else(|| "--date needs a value".to_string())?;
+                let date = NaiveDate::parse_from_str(&value, "%Y-%m-%d")
+                    .map_err(|_| format!("invalid date '{}', expected YYYY-MM-DD", value))?;
+                options.date = Some(date);
+            }
+            "--input" => {
+                let path = args
+                    .next()
+                    .ok_or_else(|| "--input needs a file path".to_string())?;
+                options.input = Some(path);
+            }
+            "--max-skipped" => {
+                let value = args
+                    .next()
+                    .ok_or_else(|| "--max-skipped needs a value".to_string())?;
+                let max_skipped = value
+                    .parse()
+                    .map_err(|_| format!("invalid row count '{}'", value))?;
+                options.max_skipped = Some(max_skipped);
+            }
+            "--seed" => {
+                let value = args
+                    .next()
+                    .ok_or_else(|| "--seed needs a value".to_string())?;
+                let seed = value
+                    .parse()
+                    .map_err(|_| format!("invalid seed '{}'", value))?;
+                options.seed = Some(seed);
+            }
+            _ if !arg.starts_with("--") && options.entries.is_none() => options.entries = Some(arg),
+            _ => return Err(format!("unknown argument '{}'", arg)),
+        }
+    }
+    if options.allowed_offsets.is_some() && options.avoid.is_some() {
+        return Err(
+            "--allowed-offsets and --avoid both set the allowed minutes, pick one".to_string(),
+        );
+    }
+    if options.format != OutputFormat::Text
+        && (options.cards.is_some() || options.announcer.is_some())
+    {
+        return Err("--format does not apply to --cards or --announcer".to_string());
+    }
+    if options.no_js && options.format != OutputFormat::Html {
+        return Err("--no-js applies to --format html".to_string());
+    }
+    if options.force && !options.final_variant {
+        return Err("--force applies to --variant final".to_string());
+    }
+    if options.final_variant && options.select.is_some() {
+        return Err("--select makes a partial list, which is never --variant final".to_string());
+    }
+    // entries may fill the windows of an event file, but not the demo's
+    if options.demo.is_some() && (options.input.is_some() || options.entries.is_some()) {
+        return Err("--demo provides the windows and entries, drop the others".to_string());
+    }
+    Ok(options)
+}
+
+pub struct BalanceOptions {
+    pub counts: Vec<usize>,
+    pub durations: Vec<Minutes>,
+    pub spacing_threshold: Minutes,
+}
+
+pub fn parse_balance_args(
+    args: impl IntoIterator<Item = String>,
+) -> Result<BalanceOptions, String> {
+    let mut counts = None;
+    let mut durations = None;
+    let mut spacing_threshold = 3;
+    let mut args = args.into_iter();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--counts" => {
+                let value = args
+                    .next()
+                    .ok_or_else(|| "--counts needs a value".to_string())?;
+                counts = Some(parse_list(&value, "count")?);
+            }
+            "--durations" => {
+                let value = args
+                    .next()
+                    .ok_or_else(|| "--durations needs a value".to_string())?;
+                durations = Some(parse_list(&value, "duration")?);
+            }
+            "--spacing-threshold" => {
+                let value = args
+                    .next()
+                    .ok_or_else(|| "--spacing-threshold needs a value".to_string())?;
+                spacing_threshold = value
+                    .parse()
+                    .map_err(|_| format!("invalid spacing threshold '{}'", value))?;
+            }
+            _ => return Err(format!("unknown argument '{}'", arg)),
+        }
+    }
+    let counts: Vec<usize> = counts.ok_or_else(|| "--counts is required".to_string())?;
+    let durations: Vec<Minutes> = durations.ok_or_else(|| "--durations is required".to_string())?;
+    if counts.len() != durations.len() {
+        return Err(format!(
+            "{} counts but {} durations, expected one of each per window",
+            counts.len(),
+            durations.len()
+        ));
+    }
+    if let Some(duration) = durations.iter().find(|&&duration| duration <= 0) {
+        return Err(format!(
+            "window duration must be positive, got {}",
+            duration
+        ));
+    }
+    Ok(BalanceOptions {
+        counts,
+        durations,
+        spacing_threshold,
+    })
+}
+
+pub struct RetimeOptions {
+    pub draw: String,
+    // override the spacings the draw file records
+    pub min_spacing: Option<Minutes>,
+    pub spacing_threshold: Option<Minutes>,
+    // spreads the leftover minutes; required in a no-entropy build
+    pub seed: Option<u64>,
+}
+
+pub fn parse_retime_args(args: impl IntoIterator<Item = String>) -> Result<RetimeOptions, String> {
+    let mut draw = None;
+    let mut min_spacing = None;
+    let mut spacing_threshold = None;
+    let mut seed = None;
+    let mut args = args.into_iter();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--seed" => {
+                let value = args
+                    .next()
+                    .ok_or_else(|| "--seed needs a value".to_string())?;
+                seed = Some(
+                    value
+                        .parse()
+                        .map_err(|_| format!("invalid seed '{}'", value))?,
+                );
+            }
+            "--min-spacing" => {
+                let value = args
+                    .next()
+                    .ok_or_else(|| "--min-spacing needs a value".to_string())?;
+                min_spacing = Some(
+                    value
+                        .parse()
+                        .map_err(|_| format!("invalid min spacing '{}'", value))?,
+                );
+            }
+            "--spacing-threshold" => {
+                let value = args
+                    .next()
+                    .ok_or_else(|| "--spacing-threshold needs a value".to_string())?;
+                spacing_threshold = Some(
+                    value
+                        .parse()
+                        .map_err(|_| format!("invalid spacing threshold '{}'", value))?,
+                );
+            }
+            _ if !arg.starts_with("--") && draw.is_none() => draw = Some(arg),
+            _ => return Err(format!("unknown argument '{}'", arg)),
+        }
+    }
+    Ok(RetimeOptions {
+        draw: draw.ok_or_else(|| "the saved draw file is required".to_string())?,
+        min_spacing,
+        spacing_threshold,
+        seed,
+    })
+}
+
+pub struct SyncOptions {
+    pub draw: String,
+    pub entries: String,
+    pub policy: SyncPolicy,
+    // where the updated draw goes, instead of over the draw file
+    pub output: Option<String>,
+    pub seed: Option<u64>,
+}
+
+pub fn parse_sync_args(args: impl IntoIterator<Item = String>) -> Result<SyncOptions, String> {
+    let mut draw = None;
+    let mut entries = None;
+    let mut policy = SyncPolicy::Error;
+    let mut output = None;
+    let mut seed = None;
+    let mut args = args.into_iter();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--entries" => {
+                entries = Some(
+                    args.next()
+                        .ok_or_else(|| "--entries needs a file".to_string())?,
+                );
+            }
+            "--redraw" => policy = SyncPolicy::Redraw,
+            "--output" => {
+                output = Some(
+                    args.next()
+                        .ok_or_else(|| "--output needs a file".to_string())?,
+                );
+            }
+            "--seed" => {
+                let value = args
+                    .next()
+                    .ok_or_else(|| "--seed needs a value".to_string())?;
+                seed = Some(
+                    value
+                        .parse()
+                        .map_err(|_| format!("invalid seed '{}'", value))?,
+                );
+            }
+            _ if !arg.starts_with("--") && draw.is_none() => draw = Some(arg),
+            _ => return Err(format!("unknown argument '{}'", arg)),
+        }
+    }
+    Ok(SyncOptions {
+        draw: draw.ok_or_else(|| "the saved draw file is required".to_string())?,
+        entries: entries.ok_or_else(|| "--entries is required".to_string())?,
+        policy,
+        output,
+        seed,
+    })
+}
+
+pub struct VerifyOptions {
+    pub draw: String,
+    pub list: String,
+}
+
+pub fn parse_verify_args(args: impl IntoIterator<Item = String>) -> Result<VerifyOptions, String> {
+    let mut draw = None;
+    let mut list = None;
+    let mut args = args.into_iter();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--against-constraints" => {
+                draw = Some(
+                    args.next()
+                        .ok_or_else(|| "--against-constraints needs a draw file".to_string())?,
+                );
+            }
+            _ if !arg.starts_with("--") && list.is_none() => list = Some(arg),
+            _ => return Err(format!("unknown argument '{}'", arg)),
+        }
+    }
+    Ok(VerifyOptions {
+        draw: draw.ok_or_else(|| "--against-constraints is required".to_string())?,
+        list: list.ok_or_else(|| "the start list CSV is required".to_string())?,
+    })
+}
+
+pub fn parse_provenance_args(args: impl IntoIterator<Item = String>) -> Result<String, String> {
+    let mut path = None;
+    for arg in args {
+        match arg {
+            _ if !arg.starts_with("--") && path.is_none() => path = Some(arg),
+            _ => return Err(format!("unknown argument '{}'", arg)),
+        }
+    }
+    path.ok_or_else(|| "the file is required".to_string())
+}
+
+pub struct AbTestOptions {
+    pub a: String,
+    pub b: String,
+    pub entries: Option<String>,
+    pub seed: Option<u64>,
+    pub json: bool,
+}
+
+pub fn parse_ab_test_args(args: impl IntoIterator<Item = String>) -> Result<AbTestOptions, String> {
+    let mut files = Vec::new();
+    let mut entries = None;
+    let mut seed = None;
+    let mut json = false;
+    let mut args = args.into_iter();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--entries" => {
+                entries = Some(
+                    args.next()
+                        .ok_or_else(|| "--entries needs a file".to_string())?,
+                );
+            }
+            "--seed" => {
+                let value = args
+                    .next()
+                    .ok_or_else(|| "--seed needs a value".to_string())?;
+                seed = Some(
+                    value
+                        .parse()
+                        .map_err(|_| format!("invalid seed '{}'", value))?,
+                );
+            }
+            "--json" => json = true,
+            _ if !arg.starts_with("--") && files.len() < 2 => files.push(arg),
+            _ => return Err(format!("unknown argument '{}'", arg)),
+        }
+    }
+    let mut files = files.into_iter();
+    match (files.next(), files.next()) {
+        (Some(a), Some(b)) => Ok(AbTestOptions {
+            a,
+            b,
+            entries,
+            seed,
+            json,
+        }),
+        _ => Err("two event files are required".to_string()),
+    }
+}
+
+fn parse_list<T: std::str::FromStr>(value: &str, what: &str) -> Result<Vec<T>, String> {
+    value
+        .split(',')
+        .map(|item| {
+            item.trim()
+                .parse()
+                .map_err(|_| format!("invalid {} '{}'", what, item))
+        })
+        .collect()
+}
+
+fn parse_offset_pattern(value: &str) -> Result<OffsetPattern, String> {
+    let invalid = || {
+        format!(
+            "invalid allowed offsets '{}', expected even, odd, every:N:PHASE or only:M,M,...",
+            value
+        )
+    };
+    match value {
+        "even" => return Ok(OffsetPattern::even()),
+        "odd" => return Ok(OffsetPattern::odd()),
+        _ => {}
+    }
+    if let Some(rest) = value.strip_prefix("every:") {
+        let (n, phase) = rest.split_once(':').ok_or_else(invalid)?;
+        return Ok(OffsetPattern::Every {
+            n: n.parse().map_err(|_| invalid())?,
+            phase: phase.parse().map_err(|_| invalid())?,
+        });
+    }
+    if let Some(rest) = value.strip_prefix("only:") {
+        return Ok(OffsetPattern::Only(
+            parse_list(rest, "offset")?.into_iter().collect(),
+        ));
+    }
+    Err(invalid())
+}
+
+fn parse_rolling_cap(value: &str) -> Result<RollingCap, String> {
+    let invalid = || format!("invalid rolling cap '{}', expected STARTS/N", value);
+    let (starts, minutes) = value.split_once('/').ok_or_else(invalid)?;
+    Ok(RollingCap {
+        starts: starts.parse().map_err(|_| invalid())?,
+        minutes: minutes.parse().map_err(|_| invalid())?,
+    })
+}
+
+fn parse_taper(value: &str) -> Result<Taper, String> {
+    let invalid = || format!("invalid taper '{}', expected N*FACTOR", value);
+    let (zone, factor) = value.split_once('*').ok_or_else(invalid)?;
+    Ok(Taper {
+        zone: zone.parse().map_err(|_| invalid())?,
+        factor: factor.parse().map_err(|_| invalid())?,
+    })
+}
+
+// FORMAT[,OPTION=VALUE]...=FILE; the file is whatever follows the last '='
+fn parse_emit(value: &str) -> Result<Emit, String> {
+    let (spec, path) = value
+        .rsplit_once('=')
+        .filter(|(_, path)| !path.is_empty())
+        .ok_or_else(|| format!("invalid emit '{}', expected FORMAT=FILE", value))?;
+    let mut parts = spec.split(',');
+    let mut render = match parts.next().unwrap_or_default() {
+        "text" => Render::Text,
+        "csv" => Render::Csv,
+        "iof-xml" => Render::IofXml { date: None },
+        "html" => Render::Html { search: true },
+        "commentary" => Render::Commentary,
+        "cards" => Render::Cards(CardOptions::default()),
+        "announcer" => Render::Announcer(AnnouncerOptions::default()),
+        format => {
+            return Err(format!(
+                "unknown emit format '{}', expected text, csv, iof-xml, html, commentary, cards or announcer",
+                format
+            ))
+        }
+    };
+    let format = render.name();
+    for option in parts {
+        let (key, option_value) = option.split_once('=').unwrap_or((option, ""));
+        let invalid = || format!("invalid {} option '{}' in emit '{}'", format, option, value);
+        match (&mut render, key) {
+            (Render::IofXml { date }, "date") => {
+                *date = Some(
+                    NaiveDate::parse_from_str(option_value, "%Y-%m-%d").map_err(|_| invalid())?,
+                )
+            }
+            (Render::Html { search }, "no-js") if option_value.is_empty() => *search = false,
+            (Render::Cards(cards), "call-up") => {
+                cards.call_up = Some(option_value.parse().map_err(|_| invalid())?)
+            }
+            (Render::Cards(cards), "only-window") => {
+                let window: usize = option_value.parse().map_err(|_| invalid())?;
+                cards.only_window = Some(window.checked_sub(1).ok_or_else(invalid)?);
+            }
+            (Render::Cards(cards), "map-scale") => {
+                cards.map_scale = Some(option_value.parse().map_err(|_| invalid())?)
+            }
+            (Render::Announcer(announcer), "call-next") => announcer.call_next = true,
+            (Render::Announcer(announcer), "lead-text") => {
+                announcer.lead_text = option_value.to_string()
+            }
+            _ => return Err(invalid()),
+        }
+    }
+    Ok(Emit {
+        render,
+        path: path.to_string(),
+    })
+}
+
+fn parse_shuttle(value: &str) -> Result<(NaiveTime, Minutes), String> {
+    let invalid = || format!("invalid shuttle '{}', expected HH:MM+N", value);
+    let (departure, travel) = value.split_once('+').ok_or_else(invalid)?;
+    Ok((
+        parse_clock(departure)?,
+        travel.parse().map_err(|_| invalid())?,
+    ))
+}
+
+fn parse_clock_range(value: &str) -> Result<(NaiveTime, NaiveTime), String> {
+    let invalid = || format!("invalid clock range '{}', expected HH:MM-HH:MM", value);
+    let (from, to) = value.split_once('-').ok_or_else(invalid)?;
+    Ok((parse_clock(from)?, parse_clock(to)?))
+}
@@ -0,0 +1,172 @@
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+
+use crate::config::EventConfig;
+use crate::duty::duty_conflicts;
+use crate::output::json_string;
+use crate::{
+    club_spacing_violations, exclusive_radius_violations, spacing_violations,
+    try_generate_startlist_with_rng, CompetitorWithOffset, Minutes, StartListError, Window,
+};
+
+/// What one configuration made of the entries.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RunSummary {
+    pub starts: usize,
+    pub last_start: Option<Minutes>,
+    // between chronologically adjacent starts; None with fewer than two
+    pub min_gap: Option<Minutes>,
+    pub mean_gap: Option<f64>,
+    pub max_gap: Option<Minutes>,
+    // drawn competitors starting in another window than they entered
+    pub moved: usize,
+    // the soft constraints the list breaks, each pair or start counted once
+    pub spacing_violations: usize,
+    pub club_spacing_violations: usize,
+    pub duty_conflicts: usize,
+    pub exclusive_radius_violations: usize,
+}
+
+/// A competitor in both lists with their offset in each.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OffsetChange {
+    pub name: String,
+    pub a: Minutes,
+    pub b: Minutes,
+}
+
+/// Two configurations run on the same entries with the same seed.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ComparisonReport {
+    pub seed: u64,
+    pub a: RunSummary,
+    pub b: RunSummary,
+    // every competitor in both lists, in the order they start with `a`
+    pub offsets: Vec<OffsetChange>,
+}
+
+impl ComparisonReport {
+    /// How much later the last start is with `b` than with `a`.
+    pub fn last_start_difference(&self) -> Option<Minutes> {
+        Some(self.b.last_start? - self.a.last_start?)
+    }
+
+    /// The competitors who start at a different offset with `b`.
+    pub fn changed(&self) -> impl Iterator<Item = &OffsetChange> {
+        self.offsets.iter().filter(|change| change.a != change.b)
+    }
+
+    /// The report as a JSON object: `seed`, `a` and `b` with the fields of
+    /// [`RunSummary`], `null` where they are `None`, and `offsets` as
+    /// `{"name", "a", "b"}` objects.
+    pub fn to_json(&self) -> String {
+        let offsets: Vec<String> = self
+            .offsets
+            .iter()
+            .map(|change| {
+                format!(
+                    "{{\"name\": {}, \"a\": {}, \"b\": {}}}",
+                    json_string(&change.name),
+                    change.a,
+                    change.b
+                )
+            })
+            .collect();
+        format!(
+            "{{\n  \"seed\": {},\n  \"a\": {},\n  \"b\": {},\n  \"offsets\": [{}]\n}}\n",
+            self.seed,
+            summary_json(&self.a),
+            summary_json(&self.b),
+            offsets.join(", ")
+        )
+    }
+}
+
+fn summary_json(summary: &RunSummary) -> String {
+    let or_null = |value: Option<String>| value.unwrap_or_else(|| "null".to_string());
+    format!(
+        "{{\"starts\": {}, \"last_start\": {}, \"min_gap\": {}, \"mean_gap\": {}, \
+         \"max_gap\": {}, \"moved\": {}, \"spacing_violations\": {}, \
+         \"club_spacing_violations\": {}, \"duty_conflicts\": {}, \
+         \"exclusive_radius_violations\": {}}}",
+        summary.starts,
+        or_null(summary.last_start.map(|offset| offset.to_string())),
+        or_null(summary.min_gap.map(|gap| gap.to_string())),
+        or_null(summary.mean_gap.map(|gap| format!("{:.2}", gap))),
+        or_null(summary.max_gap.map(|gap| gap.to_string())),
+        summary.moved,
+        summary.spacing_violations,
+        summary.club_spacing_violations,
+        summary.duty_conflicts,
+        summary.exclusive_radius_violations
+    )
+}
+
+fn summarize(
+    result: &[CompetitorWithOffset],
+    entries: &[Window],
+    config: &EventConfig,
+) -> RunSummary {
+    let mut offsets: Vec<Minutes> = result.iter().map(|c| c.offset).collect();
+    offsets.sort();
+    let gaps: Vec<Minutes> = offsets.windows(2).map(|pair| pair[1] - pair[0]).collect();
+    let entered = |name: &str| {
+        entries
+            .iter()
+            .position(|window| window.competitors.iter().any(|c| c.name == name))
+    };
+    let min_spacings: Vec<Minutes> = entries.iter().map(|w| w.min_spacing(config)).collect();
+    RunSummary {
+        starts: result.len(),
+        last_start: offsets.last().copied(),
+        min_gap: gaps.iter().min().copied(),
+        mean_gap: (!gaps.is_empty())
+            .then(|| gaps.iter().sum::<Minutes>() as f64 / gaps.len() as f64),
+        max_gap: gaps.iter().max().copied(),
+        moved: result
+            .iter()
+            .filter(|c| entered(&c.competitor.name).is_some_and(|window| window != c.window))
+            .count(),
+        spacing_violations: spacing_violations(result, &min_spacings).len(),
+        club_spacing_violations: club_spacing_violations(result, config.same_club_min_spacing)
+            .len(),
+        duty_conflicts: duty_conflicts(result, config).len(),
+        exclusive_radius_violations: exclusive_radius_violations(result).len(),
+    }
+}
+
+/// Draws `entries` once with `a` and once with `b`, both from `seed`, and
+/// reports how the two lists differ: to settle e.g. 2 against 3 minute
+/// intervals with data rather than argument.
+pub fn compare_configs(
+    entries: &[Window],
+    a: &EventConfig,
+    b: &EventConfig,
+    seed: u64,
+) -> Result<ComparisonReport, StartListError> {
+    let run = |config: &EventConfig| {
+        try_generate_startlist_with_rng(entries.to_vec(), config, &mut StdRng::seed_from_u64(seed))
+    };
+    let (result_a, result_b) = (run(a)?, run(b)?);
+    let mut in_order: Vec<&CompetitorWithOffset> = result_a.iter().collect();
+    in_order.sort_by_key(|c| c.offset);
+    let offsets = in_order
+        .into_iter()
+        .filter_map(|start| {
+            let other = result_b
+                .iter()
+                .find(|c| c.competitor.name == start.competitor.name)?;
+            Some(OffsetChange {
+                name: start.competitor.name.clone(),
+                a: start.offset,
+                b: other.offset,
+            })
+        })
+        .collect();
+    Ok(ComparisonReport {
+        seed,
+        a: summarize(&result_a, entries, a),
+        b: summarize(&result_b, entries, b),
+        offsets,
+    })
+}
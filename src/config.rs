@@ -0,0 +1,885 @@
+use std::collections::BTreeSet;
+use std::fmt;
+use std::ops::Range;
+
+use chrono::NaiveTime;
+
+use crate::timefmt::TimeUnit;
+use crate::{window_boundaries, CompetitorWithOffset, Minutes, Window};
+
+pub struct EventConfig {
+    // every offset, duration and spacing counts this unit, minutes by default
+    pub time_unit: TimeUnit,
+    pub spacing_threshold: Minutes,
+    pub min_spacing: Minutes,
+    pub start_time: NaiveTime,
+    pub first_start_offset: Minutes, // warm-up buffer before the first start
+    pub rolling_cap: Option<RollingCap>,
+    pub blocked: Vec<(Minutes, Minutes)>, // [from, to) offsets nobody may start in
+    // wider spacing near the event start, the event end and the blocked intervals
+    pub taper: Option<Taper>,
+    pub ceremonial_starts: Vec<CeremonialStart>,
+    pub sweepers: Vec<Sweeper>,
+    pub require_sweeper: bool, // every window needs at least one sweeper
+    pub transport: Vec<Shuttle>,
+    pub transport_margin: Minutes, // least time between a shuttle's arrival and a start
+    pub repair_transport: bool,    // move starts too early for any shuttle later in their window
+    pub duty_shifts: Vec<DutyShift>,
+    pub call_up: Minutes, // how long before their start competitors are called to it
+    // per class, for the expected finish columns and the commentary sheet
+    pub finish_estimates: Vec<FinishEstimate>,
+    // swap starts inside their club's duty shift with others of their window
+    pub repair_duty_shifts: bool,
+    pub allowed_offsets: OffsetPattern,
+    pub alternate_categories: bool,
+    // clubmates start at least this far apart where the draw allows; 0 turns it off
+    pub same_club_min_spacing: Minutes,
+    // stabilization moves nobody into a window that already has this many of
+    // their club among its drawn competitors; entries above it are a diagnostic
+    pub max_per_club_per_window: Option<usize>,
+    // false keeps every window in entry order, an ordered draw
+    pub shuffle: bool,
+    // an event without competitors is an error instead of an empty list
+    pub empty_event_is_error: bool,
+    // warn when a window's draw correlates with its entry order above this
+    pub shuffle_alarm_threshold: f64,
+    pub displacement_selection: DisplacementSelection,
+    // equally unbalanced window pairs are chosen between by the rng instead of
+    // the earlier pair winning, and recorded in the draw; always off in a
+    // no-entropy build
+    pub random_tie_break: bool,
+    // stabilize against the mean window spacing instead of spacing_threshold
+    pub adaptive_threshold: bool,
+    // derives a window's min_spacing from its expected duration
+    pub min_spacing_policy: Option<Box<dyn Fn(Minutes) -> Minutes>>,
+    // what a relaxed draw may drop when the full set does not fit, first to last
+    pub relaxation_order: Vec<ConstraintKind>,
+}
+
+/// A non-drawn start at an exact offset, e.g. the club chairperson opening the
+/// event. Drawn competitors keep `min_spacing` clear of it on both sides.
+pub struct CeremonialStart {
+    pub name: String,
+    pub offset: Minutes,
+}
+
+/// An official who starts last in `window` (0-based) to check the course.
+/// Sweepers are not drawn and never move between windows.
+pub struct Sweeper {
+    pub name: String,
+    pub window: usize,
+}
+
+/// A shuttle bus from the arena to a remote start, `travel` long, leaving at
+/// `departure` (which may be before the event start time).
+#[derive(Debug, Clone, Copy)]
+pub struct Shuttle {
+    pub departure: Minutes,
+    pub travel: Minutes,
+}
+
+impl Shuttle {
+    pub fn arrival(&self) -> Minutes {
+        self.departure + self.travel
+    }
+}
+
+/// A club staffing the event from `from` until `to`, offsets from the event
+/// start time, so its competitors should not be called up or start then.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DutyShift {
+    pub club: String,
+    pub from: Minutes,
+    pub to: Minutes,
+}
+
+/// How long a class's course takes: the winner about `winning_time` after
+/// their start, the field within `spread_pct` percent more, so the speaker
+/// knows whom to expect at the finish when.
+#[derive(Debug, Clone, PartialEq)]
+pub struct FinishEstimate {
+    pub class: String, // matched against the competitors' category
+    pub winning_time: Minutes,
+    pub spread_pct: f64,
+}
+
+impl FinishEstimate {
+    /// When a start at `offset` is expected at the finish, from the winning
+    /// time to the winning time plus the spread, rounded up.
+    pub fn finish_window(&self, offset: Minutes) -> (Minutes, Minutes) {
+        let from = offset + self.winning_time;
+        let spread = (self.winning_time as f64 * self.spread_pct / 100.0).ceil() as Minutes;
+        (from, from + spread)
+    }
+}
+
+/// The expected finish window of `start` by the estimate of its class, or
+/// `None` for a class without one.
+pub fn expected_finish(
+    estimates: &[FinishEstimate],
+    start: &CompetitorWithOffset,
+) -> Option<(Minutes, Minutes)> {
+    let class = start.competitor.category.as_deref()?;
+    estimates
+        .iter()
+        .find(|estimate| estimate.class == class)
+        .map(|estimate| estimate.finish_window(start.offset))
+}
+
+/// Which minutes starts may use, e.g. odd minutes only when another club
+/// draws the even ones for a shared start.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub enum OffsetPattern {
+    #[default]
+    Any,
+    /// every `n`th minute, starting at `phase`
+    Every {
+        n: Minutes,
+        phase: Minutes,
+    },
+    Only(BTreeSet<Minutes>),
+    Except(BTreeSet<Minutes>),
+}
+
+impl OffsetPattern {
+    pub fn even() -> Self {
+        OffsetPattern::Every { n: 2, phase: 0 }
+    }
+
+    pub fn odd() -> Self {
+        OffsetPattern::Every { n: 2, phase: 1 }
+    }
+
+    /// Every minute except `offsets` and `clearance` minutes either side of
+    /// them, e.g. to keep clear of a partner club's start list.
+    pub fn avoiding(offsets: impl IntoIterator<Item = Minutes>, clearance: Minutes) -> Self {
+        OffsetPattern::Except(
+            offsets
+                .into_iter()
+                .flat_map(|offset| offset - clearance..=offset + clearance)
+                .collect(),
+        )
+    }
+
+    /// The first allowed offset at or after `from`, if there is one.
+    pub fn next_allowed(&self, from: Minutes) -> Option<Minutes> {
+        match self {
+            OffsetPattern::Any => Some(from),
+            OffsetPattern::Every { n, phase } => Some(from + (phase - from).rem_euclid(*n)),
+            OffsetPattern::Only(offsets) => offsets.range(from..).next().copied(),
+            OffsetPattern::Except(offsets) => (from..).find(|offset| !offsets.contains(offset)),
+        }
+    }
+}
+
+/// A constraint a relaxed draw may drop when starts otherwise run past the end
+/// of their window. min_spacing, pinned starts and the clearance around
+/// ceremonial starts keep competitors safe and are never relaxed; the
+/// same-club spacing is only ever kept where the draw allows, so it never
+/// makes a draw infeasible.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConstraintKind {
+    RollingCap,
+    AllowedOffsets,
+    /// the explicitly blocked intervals
+    BlockedIntervals,
+}
+
+impl fmt::Display for ConstraintKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ConstraintKind::RollingCap => write!(f, "the rolling cap"),
+            ConstraintKind::AllowedOffsets => write!(f, "the allowed offsets"),
+            ConstraintKind::BlockedIntervals => write!(f, "the blocked intervals"),
+        }
+    }
+}
+
+/// Who stabilization moves when a window gives a competitor to its neighbour.
+/// Only the person changes, never how many move, so spacing is unaffected.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DisplacementSelection {
+    /// whoever the shuffle left at the end of the window next to the neighbour
+    DequeEnds,
+    /// uniformly among the window's own competitors
+    Random,
+    /// competitors marked flexible first, then uniformly among the rest
+    Volunteer,
+}
+
+impl fmt::Display for DisplacementSelection {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DisplacementSelection::DequeEnds => write!(f, "deque-ends"),
+            DisplacementSelection::Random => write!(f, "random"),
+            DisplacementSelection::Volunteer => write!(f, "volunteer"),
+        }
+    }
+}
+
+/// At most `starts` starts in any `minutes` long stretch of the timeline.
+#[derive(Debug, Clone, Copy)]
+pub struct RollingCap {
+    pub starts: usize,
+    pub minutes: Minutes,
+}
+
+/// Starts within `zone` of the first start, of the event end or of either
+/// side of a blocked interval keep `factor` times their window's min_spacing
+/// from the starts next to them, so the start crew eases in and out.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Taper {
+    pub zone: Minutes,
+    pub factor: f64,
+}
+
+impl Taper {
+    /// `min_spacing` as it is inside the taper zones, rounded up.
+    pub fn spacing(&self, min_spacing: Minutes) -> Minutes {
+        (min_spacing as f64 * self.factor).ceil() as Minutes
+    }
+}
+
+impl EventConfig {
+    pub fn builder() -> EventConfigBuilder {
+        EventConfigBuilder::default()
+    }
+
+    /// The explicitly blocked intervals plus the clearance around every
+    /// ceremonial start: the min_spacing of the window it falls in, or of the
+    /// last window after the event end.
+    pub fn blocked_intervals<'a>(
+        &'a self,
+        windows: &'a [Window],
+    ) -> impl Iterator<Item = (Minutes, Minutes)> + 'a {
+        let boundaries = window_boundaries(windows);
+        self.blocked
+            .iter()
+            .copied()
+            .chain(self.ceremonial_starts.iter().map(move |ceremonial| {
+                let min_spacing = boundaries
+                    .iter()
+                    .position(|&(_, end)| ceremonial.offset < end)
+                    .or(windows.len().checked_sub(1))
+                    .map_or(self.min_spacing, |i| windows[i].min_spacing(self));
+                (
+                    ceremonial.offset - min_spacing + 1,
+                    ceremonial.offset + min_spacing,
+                )
+            }))
+    }
+
+    /// The `[from, to)` stretches the taper widens the spacing in: after the
+    /// first start, before `event_end` and on both sides of every explicitly
+    /// blocked interval. Empty without a taper.
+    pub fn taper_zones(&self, event_end: Minutes) -> Vec<(Minutes, Minutes)> {
+        let Some(taper) = self.taper else {
+            return Vec::new();
+        };
+        let mut zones = vec![
+            (
+                self.first_start_offset,
+                self.first_start_offset + taper.zone,
+            ),
+            (event_end - taper.zone, event_end),
+        ];
+        for &(from, to) in &self.blocked {
+            zones.push((from - taper.zone, from));
+            zones.push((to, to + taper.zone));
+        }
+        zones
+    }
+
+    /// Checks the sweepers against the number of windows the event has.
+    pub fn check_sweepers(&self, window_count: usize) -> Result<(), ConfigError> {
+        if let Some(sweeper) = self.sweepers.iter().find(|s| s.window >= window_count) {
+            return Err(ConfigError::SweeperWindowOutOfRange {
+                name: sweeper.name.clone(),
+                window: sweeper.window,
+                window_count,
+            });
+        }
+        if self.require_sweeper {
+            if let Some(window) =
+                (0..window_count).find(|&w| self.sweepers.iter().all(|s| s.window != w))
+            {
+                return Err(ConfigError::MissingSweeper(window));
+            }
+        }
+        Ok(())
+    }
+
+    /// Checks there are enough allowed offsets, at least min_spacing apart,
+    /// for the competitors entered. Stabilization moves competitors between
+    /// neighbouring unlocked windows, so each run of those is checked as one;
+    /// a locked window is checked on its own.
+    pub fn check_allowed_offsets(&self, windows: &[Window]) -> Result<(), ConfigError> {
+        if self.allowed_offsets == OffsetPattern::Any {
+            return Ok(());
+        }
+        let mut window_start = 0;
+        let mut pool: Option<(usize, usize, usize)> = None; // (first window, slots, entries)
+        let mut per_window = Vec::new(); // the pool's windows
+        for (i, window) in windows.iter().enumerate() {
+            let window_end = window_start + window.duration;
+            let min_spacing = window.min_spacing(self);
+            let mut slots = 0;
+            let mut next = self
+                .allowed_offsets
+                .next_allowed(window_start.max(self.first_start_offset));
+            while let Some(offset) = next.filter(|&offset| offset < window_end) {
+                slots += 1;
+                next = self.allowed_offsets.next_allowed(offset + min_spacing);
+            }
+            let (first, pool_slots, pool_entries) = match pool {
+                Some((first, pool_slots, pool_entries)) if !window.locked => (
+                    first,
+                    pool_slots + slots,
+                    pool_entries + window.competitors.len(),
+                ),
+                _ => {
+                    per_window.clear();
+                    (i, slots, window.competitors.len())
+                }
+            };
+            per_window.push(WindowShortfall {
+                window: i,
+                slots,
+                entries: window.competitors.len(),
+            });
+            let pool_ends = window.locked || windows.get(i + 1).is_none_or(|next| next.locked);
+            if pool_ends && pool_slots < pool_entries {
+                return Err(ConfigError::TooFewAllowedOffsets {
+                    windows: first..i + 1,
+                    slots: pool_slots,
+                    entries: pool_entries,
+                    per_window,
+                });
+            }
+            pool = (!pool_ends).then_some((first, pool_slots, pool_entries));
+            window_start = window_end;
+        }
+        Ok(())
+    }
+}
+
+pub struct EventConfigBuilder {
+    time_unit: TimeUnit,
+    spacing_threshold: Minutes,
+    min_spacing: Minutes,
+    start_time: NaiveTime,
+    first_start_offset: Minutes,
+    rolling_cap: Option<RollingCap>,
+    blocked: Vec<(Minutes, Minutes)>,
+    taper: Option<Taper>,
+    ceremonial_starts: Vec<CeremonialStart>,
+    sweepers: Vec<Sweeper>,
+    require_sweeper: bool,
+    transport: Vec<Shuttle>,
+    transport_margin: Minutes,
+    repair_transport: bool,
+    duty_shifts: Vec<DutyShift>,
+    call_up: Minutes,
+    finish_estimates: Vec<FinishEstimate>,
+    repair_duty_shifts: bool,
+    allowed_offsets: OffsetPattern,
+    alternate_categories: bool,
+    same_club_min_spacing: Minutes,
+    max_per_club_per_window: Option<usize>,
+    shuffle: bool,
+    empty_event_is_error: bool,
+    shuffle_alarm_threshold: f64,
+    displacement_selection: DisplacementSelection,
+    random_tie_break: bool,
+    adaptive_threshold: bool,
+    min_spacing_policy: Option<Box<dyn Fn(Minutes) -> Minutes>>,
+    relaxation_order: Vec<ConstraintKind>,
+}
+
+impl Default for EventConfigBuilder {
+    fn default() -> Self {
+        EventConfigBuilder {
+            time_unit: TimeUnit::Minutes,
+            spacing_threshold: 3,
+            min_spacing: 2,
+            start_time: NaiveTime::from_hms_opt(9, 0, 0).unwrap(),
+            first_start_offset: 0,
+            rolling_cap: None,
+            blocked: Vec::new(),
+            taper: None,
+            ceremonial_starts: Vec::new(),
+            sweepers: Vec::new(),
+            require_sweeper: false,
+            transport: Vec::new(),
+            transport_margin: 0,
+            repair_transport: false,
+            duty_shifts: Vec::new(),
+            call_up: 0,
+            finish_estimates: Vec::new(),
+            repair_duty_shifts: false,
+            allowed_offsets: OffsetPattern::Any,
+            alternate_categories: false,
+            same_club_min_spacing: 0,
+            max_per_club_per_window: None,
+            shuffle: true,
+            empty_event_is_error: false,
+            shuffle_alarm_threshold: 0.9,
+            displacement_selection: DisplacementSelection::DequeEnds,
+            random_tie_break: false,
+            adaptive_threshold: false,
+            min_spacing_policy: None,
+            relaxation_order: Vec::new(),
+        }
+    }
+}
+
+impl EventConfigBuilder {
+    pub fn time_unit(mut self, time_unit: TimeUnit) -> Self {
+        self.time_unit = time_unit;
+        self
+    }
+
+    pub fn spacing_threshold(mut self, spacing_threshold: Minutes) -> Self {
+        self.spacing_threshold = spacing_threshold;
+        self
+    }
+
+    pub fn min_spacing(mut self, min_spacing: Minutes) -> Self {
+        self.min_spacing = min_spacing;
+        self
+    }
+
+    pub fn start_time(mut self, start_time: NaiveTime) -> Self {
+        self.start_time = start_time;
+        self
+    }
+
+    pub fn first_start_offset(mut self, first_start_offset: Minutes) -> Self {
+        self.first_start_offset = first_start_offset;
+        self
+    }
+
+    pub fn rolling_cap(mut self, rolling_cap: RollingCap) -> Self {
+        self.rolling_cap = Some(rolling_cap);
+        self
+    }
+
+    pub fn blocked_interval(mut self, from: Minutes, to: Minutes) -> Self {
+        self.blocked.push((from, to));
+        self
+    }
+
+    pub fn taper(mut self, zone: Minutes, factor: f64) -> Self {
+        self.taper = Some(Taper { zone, factor });
+        self
+    }
+
+    pub fn ceremonial_start(mut self, name: &str, offset: Minutes) -> Self {
+        self.ceremonial_starts.push(CeremonialStart {
+            name: name.to_string(),
+            offset,
+        });
+        self
+    }
+
+    pub fn sweeper(mut self, name: &str, window: usize) -> Self {
+        self.sweepers.push(Sweeper {
+            name: name.to_string(),
+            window,
+        });
+        self
+    }
+
+    pub fn require_sweeper(mut self, require_sweeper: bool) -> Self {
+        self.require_sweeper = require_sweeper;
+        self
+    }
+
+    pub fn shuttle(mut self, departure: Minutes, travel: Minutes) -> Self {
+        self.transport.push(Shuttle { departure, travel });
+        self
+    }
+
+    pub fn transport_margin(mut self, transport_margin: Minutes) -> Self {
+        self.transport_margin = transport_margin;
+        self
+    }
+
+    pub fn repair_transport(mut self, repair_transport: bool) -> Self {
+        self.repair_transport = repair_transport;
+        self
+    }
+
+    pub fn duty_shift(mut self, club: impl Into<String>, from: Minutes, to: Minutes) -> Self {
+        self.duty_shifts.push(DutyShift {
+            club: club.into(),
+            from,
+            to,
+        });
+        self
+    }
+
+    pub fn call_up(mut self, call_up: Minutes) -> Self {
+        self.call_up = call_up;
+        self
+    }
+
+    pub fn finish_estimate(
+        mut self,
+        class: impl Into<String>,
+        winning_time: Minutes,
+        spread_pct: f64,
+    ) -> Self {
+        self.finish_estimates.push(FinishEstimate {
+            class: class.into(),
+            winning_time,
+            spread_pct,
+        });
+        self
+    }
+
+    pub fn repair_duty_shifts(mut self, repair_duty_shifts: bool) -> Self {
+        self.repair_duty_shifts = repair_duty_shifts;
+        self
+    }
+
+    pub fn allowed_offsets(mut self, allowed_offsets: OffsetPattern) -> Self {
+        self.allowed_offsets = allowed_offsets;
+        self
+    }
+
+    pub fn alternate_categories(mut self, alternate_categories: bool) -> Self {
+        self.alternate_categories = alternate_categories;
+        self
+    }
+
+    pub fn same_club_min_spacing(mut self, same_club_min_spacing: Minutes) -> Self {
+        self.same_club_min_spacing = same_club_min_spacing;
+        self
+    }
+
+    pub fn max_per_club_per_window(mut self, max_per_club_per_window: usize) -> Self {
+        self.max_per_club_per_window = Some(max_per_club_per_window);
+        self
+    }
+
+    pub fn shuffle(mut self, shuffle: bool) -> Self {
+        self.shuffle = shuffle;
+        self
+    }
+
+    pub fn empty_event_is_error(mut self, empty_event_is_error: bool) -> Self {
+        self.empty_event_is_error = empty_event_is_error;
+        self
+    }
+
+    pub fn shuffle_alarm_threshold(mut self, shuffle_alarm_threshold: f64) -> Self {
+        self.shuffle_alarm_threshold = shuffle_alarm_threshold;
+        self
+    }
+
+    pub fn displacement_selection(mut self, displacement_selection: DisplacementSelection) -> Self {
+        self.displacement_selection = displacement_selection;
+        self
+    }
+
+    pub fn random_tie_break(mut self, random_tie_break: bool) -> Self {
+        self.random_tie_break = random_tie_break;
+        self
+    }
+
+    pub fn adaptive_threshold(mut self, adaptive_threshold: bool) -> Self {
+        self.adaptive_threshold = adaptive_threshold;
+        self
+    }
+
+    pub fn min_spacing_policy(mut self, policy: impl Fn(Minutes) -> Minutes + 'static) -> Self {
+        self.min_spacing_policy = Some(Box::new(policy));
+        self
+    }
+
+    /// Repeats of a constraint are ignored.
+    pub fn relaxation_order(mut self, relaxation_order: Vec<ConstraintKind>) -> Self {
+        self.relaxation_order.clear();
+        for kind in relaxation_order {
+            if !self.relaxation_order.contains(&kind) {
+                self.relaxation_order.push(kind);
+            }
+        }
+        self
+    }
+
+    pub fn build(self) -> Result<EventConfig, ConfigError> {
+        if self.spacing_threshold <= 0 {
+            return Err(ConfigError::NonPositiveSpacingThreshold(
+                self.spacing_threshold,
+            ));
+        }
+        if self.min_spacing <= 0 {
+            return Err(ConfigError::NonPositiveMinSpacing(self.min_spacing));
+        }
+        if self.min_spacing > self.spacing_threshold {
+            return Err(ConfigError::MinSpacingAboveThreshold {
+                min_spacing: self.min_spacing,
+                spacing_threshold: self.spacing_threshold,
+            });
+        }
+        if self.first_start_offset < 0 {
+            return Err(ConfigError::NegativeFirstStartOffset(
+                self.first_start_offset,
+            ));
+        }
+        if self.same_club_min_spacing < 0 {
+            return Err(ConfigError::NegativeSameClubSpacing(
+                self.same_club_min_spacing,
+            ));
+        }
+        if self.max_per_club_per_window == Some(0) {
+            return Err(ConfigError::ZeroClubCap);
+        }
+        if let Some(cap) = self.rolling_cap {
+            if cap.starts == 0 || cap.minutes <= 0 {
+                return Err(ConfigError::InvalidRollingCap(cap));
+            }
+        }
+        if let Some(&(from, to)) = self.blocked.iter().find(|(from, to)| from >= to) {
+            return Err(ConfigError::EmptyBlockedInterval { from, to });
+        }
+        if let Some(taper) = self.taper {
+            if taper.zone <= 0 || taper.factor.is_nan() || taper.factor < 1.0 {
+                return Err(ConfigError::InvalidTaper(taper));
+            }
+        }
+        if let OffsetPattern::Every { n, .. } = self.allowed_offsets {
+            if n <= 0 {
+                return Err(ConfigError::NonPositiveOffsetPeriod(n));
+            }
+        }
+        if let Some(&shuttle) = self.transport.iter().find(|shuttle| shuttle.travel < 0) {
+            return Err(ConfigError::NegativeShuttleTravel(shuttle));
+        }
+        if self.transport_margin < 0 {
+            return Err(ConfigError::NegativeTransportMargin(self.transport_margin));
+        }
+        if let Some(shift) = self.duty_shifts.iter().find(|shift| shift.from >= shift.to) {
+            return Err(ConfigError::EmptyDutyShift(shift.clone()));
+        }
+        if self.call_up < 0 {
+            return Err(ConfigError::NegativeCallUp(self.call_up));
+        }
+        if let Some(estimate) = self.finish_estimates.iter().find(|estimate| {
+            estimate.winning_time <= 0 || estimate.spread_pct.is_nan() || estimate.spread_pct < 0.0
+        }) {
+            return Err(ConfigError::InvalidFinishEstimate(estimate.clone()));
+        }
+        if let Some(ceremonial) = self.ceremonial_starts.iter().find(|c| c.offset < 0) {
+            return Err(ConfigError::CeremonialBeforeStart(ceremonial.name.clone()));
+        }
+
+        Ok(EventConfig {
+            time_unit: self.time_unit,
+            spacing_threshold: self.spacing_threshold,
+            min_spacing: self.min_spacing,
+            start_time: self.start_time,
+            first_start_offset: self.first_start_offset,
+            rolling_cap: self.rolling_cap,
+            blocked: self.blocked,
+            taper: self.taper,
+            ceremonial_starts: self.ceremonial_starts,
+            sweepers: self.sweepers,
+            require_sweeper: self.require_sweeper,
+            transport: self.transport,
+            transport_margin: self.transport_margin,
+            repair_transport: self.repair_transport,
+            duty_shifts: self.duty_shifts,
+            call_up: self.call_up,
+            finish_estimates: self.finish_estimates,
+            repair_duty_shifts: self.repair_duty_shifts,
+            allowed_offsets: self.allowed_offsets,
+            alternate_categories: self.alternate_categories,
+            same_club_min_spacing: self.same_club_min_spacing,
+            max_per_club_per_window: self.max_per_club_per_window,
+            shuffle: self.shuffle,
+            empty_event_is_error: self.empty_event_is_error,
+            shuffle_alarm_threshold: self.shuffle_alarm_threshold,
+            displacement_selection: self.displacement_selection,
+            random_tie_break: self.random_tie_break && !cfg!(feature = "no-entropy"),
+            adaptive_threshold: self.adaptive_threshold,
+            min_spacing_policy: self.min_spacing_policy,
+            relaxation_order: self.relaxation_order,
+        })
+    }
+}
+
+#[derive(Debug)]
+pub enum ConfigError {
+    NonPositiveSpacingThreshold(Minutes),
+    NonPositiveMinSpacing(Minutes),
+    MinSpacingAboveThreshold {
+        min_spacing: Minutes,
+        spacing_threshold: Minutes,
+    },
+    NegativeFirstStartOffset(Minutes),
+    NegativeSameClubSpacing(Minutes),
+    ZeroClubCap,
+    InvalidRollingCap(RollingCap),
+    EmptyBlockedInterval {
+        from: Minutes,
+        to: Minutes,
+    },
+    InvalidTaper(Taper),
+    CeremonialBeforeStart(String),
+    SweeperWindowOutOfRange {
+        name: String,
+        window: usize,
+        window_count: usize,
+    },
+    MissingSweeper(usize),
+    NegativeShuttleTravel(Shuttle),
+    NegativeTransportMargin(Minutes),
+    EmptyDutyShift(DutyShift),
+    NegativeCallUp(Minutes),
+    InvalidFinishEstimate(FinishEstimate),
+    NonPositiveOffsetPeriod(Minutes),
+    TooFewAllowedOffsets {
+        windows: Range<usize>,
+        slots: usize,
+        entries: usize,
+        // each of `windows` on its own
+        per_window: Vec<WindowShortfall>,
+    },
+}
+
+/// One window's share of a shortfall of starts.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WindowShortfall {
+    pub window: usize,
+    pub slots: usize,   // starts it has room for
+    pub entries: usize, // competitors entered in it
+}
+
+impl fmt::Display for ConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ConfigError::NonPositiveSpacingThreshold(value) => {
+                write!(f, "spacing_threshold must be positive, got {}", value)
+            }
+            ConfigError::NonPositiveMinSpacing(value) => {
+                write!(f, "min_spacing must be positive, got {}", value)
+            }
+            ConfigError::MinSpacingAboveThreshold {
+                min_spacing,
+                spacing_threshold,
+            } => write!(
+                f,
+                "min_spacing ({}) must not exceed spacing_threshold ({})",
+                min_spacing, spacing_threshold
+            ),
+            ConfigError::NegativeFirstStartOffset(value) => {
+                write!(f, "first_start_offset must not be negative, got {}", value)
+            }
+            ConfigError::NegativeSameClubSpacing(value) => write!(
+                f,
+                "same_club_min_spacing must not be negative, got {}",
+                value
+            ),
+            ConfigError::ZeroClubCap => {
+                write!(f, "max_per_club_per_window must be at least 1")
+            }
+            ConfigError::InvalidRollingCap(cap) => write!(
+                f,
+                "rolling cap needs at least one start per positive span, got {} per {} minutes",
+                cap.starts, cap.minutes
+            ),
+            ConfigError::EmptyBlockedInterval { from, to } => write!(
+                f,
+                "blocked interval must end after it starts, got minutes {} to {}",
+                from, to
+            ),
+            ConfigError::InvalidTaper(taper) => write!(
+                f,
+                "taper needs a positive zone and a factor of at least 1, got {} minutes times {}",
+                taper.zone, taper.factor
+            ),
+            ConfigError::CeremonialBeforeStart(name) => write!(
+                f,
+                "ceremonial start of {} is before the event start time",
+                name
+            ),
+            ConfigError::SweeperWindowOutOfRange {
+                name,
+                window,
+                window_count,
+            } => write!(
+                f,
+                "sweeper {} is in window {}, but there are only {} windows",
+                name,
+                window + 1,
+                window_count
+            ),
+            ConfigError::MissingSweeper(window) => {
+                write!(f, "window {} has no sweeper", window + 1)
+            }
+            ConfigError::NegativeShuttleTravel(shuttle) => write!(
+                f,
+                "shuttle leaving at minute {} must not arrive before it leaves, got travel {}",
+                shuttle.departure, shuttle.travel
+            ),
+            ConfigError::NegativeTransportMargin(value) => {
+                write!(f, "transport_margin must not be negative, got {}", value)
+            }
+            ConfigError::EmptyDutyShift(shift) => write!(
+                f,
+                "duty shift of {} must end after it starts, got minutes {} to {}",
+                shift.club, shift.from, shift.to
+            ),
+            ConfigError::NegativeCallUp(value) => {
+                write!(f, "call_up must not be negative, got {}", value)
+            }
+            ConfigError::InvalidFinishEstimate(estimate) => write!(
+                f,
+                "finish estimate of {} needs a positive winning time and a spread of at least 0%, \
+                 got {} and {}%",
+                estimate.class, estimate.winning_time, estimate.spread_pct
+            ),
+            ConfigError::NonPositiveOffsetPeriod(n) => {
+                write!(
+                    f,
+                    "allowed offsets must repeat every positive number of minutes, got {}",
+                    n
+                )
+            }
+            ConfigError::TooFewAllowedOffsets {
+                windows,
+                slots,
+                entries,
+                per_window,
+            } => {
+                if windows.len() == 1 {
+                    write!(f, "window {}", windows.start + 1)?;
+                } else {
+                    write!(f, "windows {}-{}", windows.start + 1, windows.end)?;
+                }
+                write!(
+                    f,
+                    " can start {} competitors on the allowed minutes, {} are entered",
+                    slots, entries
+                )?;
+                if per_window.len() > 1 {
+                    for row in per_window {
+                        write!(
+                            f,
+                            "\n  window {}: room for {}, {} entered",
+                            row.window + 1,
+                            row.slots,
+                            row.entries
+                        )?;
+                    }
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+impl std::error::Error for ConfigError {}
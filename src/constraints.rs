@@ -0,0 +1,308 @@
+use std::fmt;
+
+use crate::{spacing_violations, window_boundaries, CompetitorWithOffset, Minutes, Window};
+
+/// The version of the `[constraints]` section `output::export_draw` writes;
+/// `input::parse_event` reads no other.
+pub const CONSTRAINTS_VERSION: u32 = 1;
+
+/// A rule a start list must keep to whoever edits it, e.g. the timing
+/// company's tooling. Its text form is the one kept in a saved draw.
+pub trait Constraint: fmt::Display {
+    /// Every way `startlist` breaks the rule; empty if it keeps it.
+    fn check(&self, startlist: &[CompetitorWithOffset]) -> Vec<ConstraintViolation>;
+}
+
+/// One way a start list breaks a constraint.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ConstraintViolation {
+    // the constraint's text form
+    pub constraint: String,
+    pub message: String,
+}
+
+impl fmt::Display for ConstraintViolation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {}", self.constraint, self.message)
+    }
+}
+
+fn violation(constraint: &impl Constraint, message: String) -> ConstraintViolation {
+    ConstraintViolation {
+        constraint: constraint.to_string(),
+        message,
+    }
+}
+
+// the drawn starts, leaving out ceremonial starts and sweepers
+fn drawn(startlist: &[CompetitorWithOffset]) -> impl Iterator<Item = &CompetitorWithOffset> {
+    startlist
+        .iter()
+        .filter(|c| !c.competitor.ceremonial && !c.competitor.sweeper)
+}
+
+/// A pinned competitor starts at exactly this offset. Text form
+/// `NAME@OFFSET`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FixedSlot {
+    pub name: String,
+    pub offset: Minutes,
+}
+
+impl fmt::Display for FixedSlot {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}@{}", self.name, self.offset)
+    }
+}
+
+impl Constraint for FixedSlot {
+    fn check(&self, startlist: &[CompetitorWithOffset]) -> Vec<ConstraintViolation> {
+        match drawn(startlist).find(|c| c.competitor.name == self.name) {
+            None => vec![violation(self, format!("{} is missing", self.name))],
+            Some(start) if start.offset != self.offset => vec![violation(
+                self,
+                format!("{} starts at {}", self.name, start.offset),
+            )],
+            Some(_) => Vec::new(),
+        }
+    }
+}
+
+/// Nobody else starts within `radius` of a pinned start's offset. Text form
+/// `NAME@OFFSET~RADIUS`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ExclusiveRadius {
+    pub name: String,
+    pub offset: Minutes,
+    pub radius: Minutes,
+}
+
+impl fmt::Display for ExclusiveRadius {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}@{}~{}", self.name, self.offset, self.radius)
+    }
+}
+
+impl Constraint for ExclusiveRadius {
+    fn check(&self, startlist: &[CompetitorWithOffset]) -> Vec<ConstraintViolation> {
+        drawn(startlist)
+            .filter(|c| c.competitor.name != self.name)
+            .filter(|c| (c.offset - self.offset).abs() <= self.radius)
+            .map(|c| {
+                violation(
+                    self,
+                    format!("{} starts at {}", c.competitor.name, c.offset),
+                )
+            })
+            .collect()
+    }
+}
+
+/// A locked window's competitors all start in its `[from, to)` and nobody
+/// else does. Text form `WINDOW@FROM-TO`, the window 1-based, with the names
+/// listed apart.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LockedWindow {
+    pub window: usize,
+    pub from: Minutes,
+    pub to: Minutes,
+    pub names: Vec<String>,
+}
+
+impl fmt::Display for LockedWindow {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}@{}-{}", self.window + 1, self.from, self.to)
+    }
+}
+
+impl Constraint for LockedWindow {
+    fn check(&self, startlist: &[CompetitorWithOffset]) -> Vec<ConstraintViolation> {
+        let inside = |offset: Minutes| self.from <= offset && offset < self.to;
+        let mut violations = Vec::new();
+        for name in &self.names {
+            match drawn(startlist).find(|c| &c.competitor.name == name) {
+                None => violations.push(violation(self, format!("{} is missing", name))),
+                Some(start) if !inside(start.offset) => violations.push(violation(
+                    self,
+                    format!("{} starts outside it, at {}", name, start.offset),
+                )),
+                Some(_) => {}
+            }
+        }
+        violations.extend(
+            drawn(startlist)
+                .filter(|c| inside(c.offset) && !self.names.contains(&c.competitor.name))
+                .map(|c| {
+                    violation(
+                        self,
+                        format!("{} was moved in, at {}", c.competitor.name, c.offset),
+                    )
+                }),
+        );
+        violations
+    }
+}
+
+/// Consecutive starts keep the `min_spacing` of their windows apart, the
+/// larger of the two across a window boundary. Text form `min_spacing`; it is
+/// not kept in a saved draw, whose spacings are its own.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MinSpacing {
+    pub min_spacings: Vec<Minutes>, // per window, as Window::min_spacing gives them
+}
+
+impl fmt::Display for MinSpacing {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "min_spacing")
+    }
+}
+
+impl Constraint for MinSpacing {
+    fn check(&self, startlist: &[CompetitorWithOffset]) -> Vec<ConstraintViolation> {
+        spacing_violations(startlist, &self.min_spacings)
+            .into_iter()
+            .map(|(earlier, later)| {
+                violation(
+                    self,
+                    format!(
+                        "{} at {} and {} at {} start {} apart",
+                        earlier.competitor.name,
+                        earlier.offset,
+                        later.competitor.name,
+                        later.offset,
+                        later.offset - earlier.offset
+                    ),
+                )
+            })
+            .collect()
+    }
+}
+
+/// Every constraint a saved draw records, for tooling that edits its start
+/// list to keep to.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Constraints {
+    pub fixed_slots: Vec<FixedSlot>,
+    pub exclusive_radii: Vec<ExclusiveRadius>,
+    pub locked_windows: Vec<LockedWindow>,
+}
+
+impl Constraints {
+    /// The constraints `windows` set: their pinned starts and exclusive radii,
+    /// and their locked windows with everyone in them.
+    pub fn of_draw(windows: &[Window]) -> Self {
+        let mut constraints = Constraints::default();
+        for (i, (window, (from, to))) in windows.iter().zip(window_boundaries(windows)).enumerate()
+        {
+            for competitor in &window.competitors {
+                let Some(offset) = competitor.fixed_offset else {
+                    continue;
+                };
+                constraints.fixed_slots.push(FixedSlot {
+                    name: competitor.name.clone(),
+                    offset,
+                });
+                if competitor.exclusive_radius > 0 {
+                    constraints.exclusive_radii.push(ExclusiveRadius {
+                        name: competitor.name.clone(),
+                        offset,
+                        radius: competitor.exclusive_radius,
+                    });
+                }
+            }
+            if window.locked {
+                constraints.locked_windows.push(LockedWindow {
+                    window: i,
+                    from,
+                    to,
+                    names: window.competitors.iter().map(|c| c.name.clone()).collect(),
+                });
+            }
+        }
+        constraints
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.fixed_slots.is_empty()
+            && self.exclusive_radii.is_empty()
+            && self.locked_windows.is_empty()
+    }
+
+    /// Every constraint, fixed slots first, then exclusive radii, then locked
+    /// windows.
+    pub fn all(&self) -> Vec<&dyn Constraint> {
+        self.fixed_slots
+            .iter()
+            .map(|c| c as &dyn Constraint)
+            .chain(self.exclusive_radii.iter().map(|c| c as &dyn Constraint))
+            .chain(self.locked_windows.iter().map(|c| c as &dyn Constraint))
+            .collect()
+    }
+
+    /// Every way `startlist` breaks any of the constraints, in the order of
+    /// [`Constraints::all`].
+    pub fn check(&self, startlist: &[CompetitorWithOffset]) -> Vec<ConstraintViolation> {
+        self.all()
+            .into_iter()
+            .flat_map(|constraint| constraint.check(startlist))
+            .collect()
+    }
+}
+
+/// A start list that may still break its constraints: a draft, which any
+/// renderer takes, until [`StartList::finalize`] turns it into the
+/// [`FinalStartList`] the final renderers take.
+#[derive(Debug, Clone)]
+pub struct StartList {
+    pub starts: Vec<CompetitorWithOffset>,
+}
+
+/// A start list every check held for, or that was forced past them: the only
+/// list `output::export_final_csv`, `export_final_iof_xml` and
+/// `export_final_html` write.
+#[derive(Debug, Clone)]
+pub struct FinalStartList {
+    starts: Vec<CompetitorWithOffset>,
+}
+
+impl StartList {
+    pub fn new(starts: Vec<CompetitorWithOffset>) -> Self {
+        StartList { starts }
+    }
+
+    /// The final list, or every way the starts break `checks`, in their
+    /// order.
+    pub fn finalize(
+        self,
+        checks: &[&dyn Constraint],
+    ) -> Result<FinalStartList, Vec<ConstraintViolation>> {
+        match self.finalize_forced(checks) {
+            (list, violations) if violations.is_empty() => Ok(list),
+            (_, violations) => Err(violations),
+        }
+    }
+
+    /// The final list whatever `checks` find, with what they found, for a
+    /// caller that has been told to publish it anyway.
+    pub fn finalize_forced(
+        self,
+        checks: &[&dyn Constraint],
+    ) -> (FinalStartList, Vec<ConstraintViolation>) {
+        let violations = checks
+            .iter()
+            .flat_map(|check| check.check(&self.starts))
+            .collect();
+        (
+            FinalStartList {
+                starts: self.starts,
+            },
+            violations,
+        )
+    }
+}
+
+impl FinalStartList {
+    pub fn starts(&self) -> &[CompetitorWithOffset] {
+        &self.starts
+    }
+}
@@ -0,0 +1,81 @@
+use std::str::FromStr;
+
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+
+use crate::input::parse_event;
+use crate::{Competitor, Minutes, Window};
+
+const DEMO_SEED: u64 = 2023;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DemoSize {
+    Small,
+    Medium,
+    Large,
+}
+
+impl DemoSize {
+    // (competitors, windows, window duration), sized so min_spacing 2 fits
+    fn layout(self) -> (usize, usize, Minutes) {
+        match self {
+            DemoSize::Small => (20, 3, 30),
+            DemoSize::Medium => (200, 8, 60),
+            DemoSize::Large => (2000, 50, 150),
+        }
+    }
+}
+
+impl FromStr for DemoSize {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "small" => Ok(DemoSize::Small),
+            "medium" => Ok(DemoSize::Medium),
+            "large" => Ok(DemoSize::Large),
+            _ => Err(format!(
+                "unknown demo size '{}', expected small, medium or large",
+                s
+            )),
+        }
+    }
+}
+
+fn window(duration: Minutes, number: usize, count: usize) -> Window {
+    let competitors = (0..count).rev().map(|i| {
+        let mut competitor = Competitor::new(format!("{} Competitor {}", number, i));
+        competitor.category = Some(if i % 2 == 0 { "M" } else { "F" }.to_string());
+        competitor
+    });
+    Window::new(duration, competitors)
+}
+
+// an event file like any other, so the example shows the format as well
+const EXAMPLE_EVENT: &str = include_str!("../examples/example_event.toml");
+
+/// The original three-window example: a sparse, an overfull and a light window,
+/// with the longest expected course first, read from the event file
+/// `examples/example_event.toml` built into the crate.
+pub fn example_windows() -> Vec<Window> {
+    parse_event(EXAMPLE_EVENT)
+        .expect("the built-in example event parses")
+        .windows
+}
+
+/// Deterministically spreads the demo competitors unevenly over the windows,
+/// so stabilization has real work to do.
+pub fn demo_windows(size: DemoSize) -> Vec<Window> {
+    let (competitors_count, windows_count, duration) = size.layout();
+    let mut rng = StdRng::seed_from_u64(DEMO_SEED);
+    let mut counts = vec![0; windows_count];
+    for _ in 0..competitors_count {
+        counts[rng.gen_range(0..windows_count)] += 1;
+    }
+
+    counts
+        .into_iter()
+        .enumerate()
+        .map(|(w, count)| window(duration, w + 1, count))
+        .collect()
+}
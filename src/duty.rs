@@ -0,0 +1,80 @@
+use crate::config::EventConfig;
+use crate::{CompetitorWithOffset, Minutes};
+
+/// Returns the starts, in clock order, of competitors whose club is on duty
+/// at any time from their call-up (`config.call_up` before the start) to the
+/// start itself.
+pub fn duty_conflicts<'a>(
+    competitors: &'a [CompetitorWithOffset],
+    config: &EventConfig,
+) -> Vec<&'a CompetitorWithOffset> {
+    let mut conflicts: Vec<&CompetitorWithOffset> = competitors
+        .iter()
+        .filter(|c| on_duty(c.competitor.club.as_deref(), c.offset, config))
+        .collect();
+    conflicts.sort_by_key(|c| c.offset);
+    conflicts
+}
+
+fn on_duty(club: Option<&str>, offset: Minutes, config: &EventConfig) -> bool {
+    let Some(club) = club else {
+        return false;
+    };
+    config.duty_shifts.iter().any(|shift| {
+        shift.club == club && shift.from <= offset && offset - config.call_up < shift.to
+    })
+}
+
+// swaps each start in its club's duty shift with the nearest start of the
+// same window that is off duty in either place; offsets stay put. Like the
+// club separation, moved-in competitors and sweepers keep their slots, only
+// same-category starts swap with alternate_categories, and no swap brings
+// clubmates closer than same_club_min_spacing. When nobody fits the conflict
+// is left for duty_conflicts to report
+pub(crate) fn repair_duty_shifts(competitors: &mut [CompetitorWithOffset], config: &EventConfig) {
+    competitors.sort_by_key(|c| c.offset);
+    let swappable = |c: &CompetitorWithOffset| {
+        c.competitor.origin == 0
+            && !c.competitor.sweeper
+            && !c.competitor.ceremonial
+            && c.competitor.fixed_offset.is_none()
+    };
+    // whether the competitor at `from` would start too close to a clubmate in
+    // the slot at `to`, were the two swapped
+    let club_clash = |competitors: &[CompetitorWithOffset], from: usize, to: usize| {
+        let club = &competitors[from].competitor.club;
+        config.same_club_min_spacing > 0
+            && club.is_some()
+            && competitors.iter().enumerate().any(|(k, c)| {
+                k != from
+                    && k != to
+                    && &c.competitor.club == club
+                    && (c.offset - competitors[to].offset).abs() < config.same_club_min_spacing
+            })
+    };
+    for i in 0..competitors.len() {
+        let c = &competitors[i];
+        if !swappable(c) || !on_duty(c.competitor.club.as_deref(), c.offset, config) {
+            continue;
+        }
+        let replacement = (0..competitors.len())
+            .filter(|&j| {
+                let (a, b) = (&competitors[i], &competitors[j]);
+                j != i
+                    && b.window == a.window
+                    && swappable(b)
+                    && (!config.alternate_categories
+                        || a.competitor.category == b.competitor.category)
+                    && !on_duty(a.competitor.club.as_deref(), b.offset, config)
+                    && !on_duty(b.competitor.club.as_deref(), a.offset, config)
+                    && !club_clash(competitors, i, j)
+                    && !club_clash(competitors, j, i)
+            })
+            .min_by_key(|&j| (competitors[j].offset - competitors[i].offset).abs());
+        if let Some(j) = replacement {
+            let (low, high) = (i.min(j), i.max(j));
+            let (before, after) = competitors.split_at_mut(high);
+            std::mem::swap(&mut before[low].competitor, &mut after[0].competitor);
+        }
+    }
+}
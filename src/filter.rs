@@ -0,0 +1,259 @@
+use std::fmt;
+
+use crate::config::EventConfig;
+use crate::timefmt::{offset_of, parse_clock};
+use crate::{CompetitorWithOffset, Minutes};
+
+/// Which starts a partial export keeps, e.g. one club's for its team leader
+/// or the first hour's for the web team. Build one directly or with
+/// [`Filter::parse`].
+#[derive(Debug, Clone, PartialEq)]
+pub enum Filter {
+    Club(String),
+    // the competitor's category
+    Class(String),
+    // 0-based indices of the windows the starts are in
+    Window(Vec<usize>),
+    Time(Comparison, Minutes),
+    Flag(StartFlag),
+    And(Box<Filter>, Box<Filter>),
+    Or(Box<Filter>, Box<Filter>),
+}
+
+/// How a start's offset compares to a [`Filter::Time`] offset.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Comparison {
+    Before,
+    AtOrBefore,
+    After,
+    AtOrAfter,
+}
+
+/// A start outside the draw or at a fixed time.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StartFlag {
+    Ceremonial,
+    Sweeper,
+    Pinned,
+}
+
+/// Where in a filter expression it stops making sense, as a 0-based
+/// character position.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FilterError {
+    pub position: usize,
+    pub message: String,
+}
+
+impl fmt::Display for FilterError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} at position {}", self.message, self.position + 1)
+    }
+}
+
+impl std::error::Error for FilterError {}
+
+impl Filter {
+    pub fn and(self, other: Filter) -> Filter {
+        Filter::And(Box::new(self), Box::new(other))
+    }
+
+    pub fn or(self, other: Filter) -> Filter {
+        Filter::Or(Box::new(self), Box::new(other))
+    }
+
+    /// Whether `start` is one the filter keeps.
+    pub fn matches(&self, start: &CompetitorWithOffset) -> bool {
+        let competitor = &start.competitor;
+        match self {
+            Filter::Club(club) => competitor.club.as_deref() == Some(club.as_str()),
+            Filter::Class(class) => competitor.category.as_deref() == Some(class.as_str()),
+            Filter::Window(windows) => windows.contains(&start.window),
+            Filter::Time(comparison, offset) => match comparison {
+                Comparison::Before => start.offset < *offset,
+                Comparison::AtOrBefore => start.offset <= *offset,
+                Comparison::After => start.offset > *offset,
+                Comparison::AtOrAfter => start.offset >= *offset,
+            },
+            Filter::Flag(StartFlag::Ceremonial) => competitor.ceremonial,
+            Filter::Flag(StartFlag::Sweeper) => competitor.sweeper,
+            Filter::Flag(StartFlag::Pinned) => competitor.fixed_offset.is_some(),
+            Filter::And(a, b) => a.matches(start) && b.matches(start),
+            Filter::Or(a, b) => a.matches(start) || b.matches(start),
+        }
+    }
+
+    /// Parses the compact syntax of `--select`: terms `club=NAME`,
+    /// `class=NAME`, `window=N[,N...]` (1-based), `time<HH:MM` (also `<=`,
+    /// `>` and `>=`) and `flag=ceremonial|sweeper|pinned`, combined with `&`
+    /// and `|`, `&` binding tighter, and grouped with parentheses. A name
+    /// runs to the next `&`, `|` or `)`, its surrounding spaces trimmed.
+    /// Clock times count from the config's start time.
+    pub fn parse(expression: &str, config: &EventConfig) -> Result<Filter, FilterError> {
+        let mut parser = Parser {
+            chars: expression.chars().collect(),
+            position: 0,
+            config,
+        };
+        let filter = parser.or()?;
+        parser.skip_spaces();
+        match parser.peek() {
+            None => Ok(filter),
+            Some(c) => Err(parser.error(format!("unexpected '{}'", c))),
+        }
+    }
+}
+
+struct Parser<'a> {
+    chars: Vec<char>,
+    position: usize,
+    config: &'a EventConfig,
+}
+
+impl Parser<'_> {
+    fn peek(&self) -> Option<char> {
+        self.chars.get(self.position).copied()
+    }
+
+    fn skip_spaces(&mut self) {
+        while self.peek().is_some_and(char::is_whitespace) {
+            self.position += 1;
+        }
+    }
+
+    fn error(&self, message: impl Into<String>) -> FilterError {
+        FilterError {
+            position: self.position,
+            message: message.into(),
+        }
+    }
+
+    fn or(&mut self) -> Result<Filter, FilterError> {
+        let mut filter = self.and()?;
+        loop {
+            self.skip_spaces();
+            if self.peek() != Some('|') {
+                return Ok(filter);
+            }
+            self.position += 1;
+            filter = filter.or(self.and()?);
+        }
+    }
+
+    fn and(&mut self) -> Result<Filter, FilterError> {
+        let mut filter = self.term()?;
+        loop {
+            self.skip_spaces();
+            if self.peek() != Some('&') {
+                return Ok(filter);
+            }
+            self.position += 1;
+            filter = filter.and(self.term()?);
+        }
+    }
+
+    fn term(&mut self) -> Result<Filter, FilterError> {
+        self.skip_spaces();
+        if self.peek() == Some('(') {
+            self.position += 1;
+            let filter = self.or()?;
+            self.skip_spaces();
+            if self.peek() != Some(')') {
+                return Err(self.error("expected ')'"));
+            }
+            self.position += 1;
+            return Ok(filter);
+        }
+
+        let field_start = self.position;
+        while self
+            .peek()
+            .is_some_and(|c| c.is_ascii_alphanumeric() || c == '_')
+        {
+            self.position += 1;
+        }
+        let field: String = self.chars[field_start..self.position].iter().collect();
+        if field.is_empty() {
+            return Err(self.error("expected a term such as club=NAME"));
+        }
+        if !["club", "class", "window", "time", "flag"].contains(&field.as_str()) {
+            return Err(FilterError {
+                position: field_start,
+                message: format!(
+                    "unknown field '{}', expected club, class, window, time or flag",
+                    field
+                ),
+            });
+        }
+        self.skip_spaces();
+        let operator_start = self.position;
+        let operator = match (self.peek(), self.chars.get(self.position + 1)) {
+            (Some('<' | '>'), Some('=')) => 2,
+            (Some('<' | '>' | '='), _) => 1,
+            _ => return Err(self.error(format!("expected =, <, <=, > or >= after '{}'", field))),
+        };
+        self.position += operator;
+        let operator: String = self.chars[operator_start..self.position].iter().collect();
+
+        self.skip_spaces();
+        let value_start = self.position;
+        while self.peek().is_some_and(|c| !matches!(c, '&' | '|' | ')')) {
+            self.position += 1;
+        }
+        let value: String = self.chars[value_start..self.position].iter().collect();
+        let value = value.trim();
+        let invalid = |message: String| FilterError {
+            position: value_start,
+            message,
+        };
+        if value.is_empty() {
+            return Err(invalid(format!("expected a value for '{}'", field)));
+        }
+
+        if field == "time" {
+            let clock = parse_clock(value).map_err(invalid)?;
+            let offset = offset_of(self.config.start_time, clock, self.config.time_unit);
+            let comparison = match operator.as_str() {
+                "<" => Comparison::Before,
+                "<=" => Comparison::AtOrBefore,
+                ">" => Comparison::After,
+                ">=" => Comparison::AtOrAfter,
+                _ => {
+                    return Err(FilterError {
+                        position: operator_start,
+                        message: "time takes <, <=, > or >=".to_string(),
+                    })
+                }
+            };
+            return Ok(Filter::Time(comparison, offset));
+        }
+        if operator != "=" {
+            return Err(FilterError {
+                position: operator_start,
+                message: format!("{} takes =", field),
+            });
+        }
+        match field.as_str() {
+            "club" => Ok(Filter::Club(value.to_string())),
+            "class" => Ok(Filter::Class(value.to_string())),
+            "window" => value
+                .split(',')
+                .map(|window| match window.trim().parse::<usize>() {
+                    Ok(window) if window > 0 => Ok(window - 1),
+                    _ => Err(invalid(format!("invalid window '{}'", window.trim()))),
+                })
+                .collect::<Result<_, _>>()
+                .map(Filter::Window),
+            "flag" => match value {
+                "ceremonial" => Ok(Filter::Flag(StartFlag::Ceremonial)),
+                "sweeper" => Ok(Filter::Flag(StartFlag::Sweeper)),
+                "pinned" => Ok(Filter::Flag(StartFlag::Pinned)),
+                _ => Err(invalid(format!(
+                    "unknown flag '{}', expected ceremonial, sweeper or pinned",
+                    value
+                ))),
+            },
+            _ => unreachable!("the field was checked above"),
+        }
+    }
+}
@@ -0,0 +1,397 @@
+use std::fmt;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+
+use crate::input::{
+    csv_header, entries_from_csv, is_comment, is_entries_header, startlist_from_csv, window_index,
+    windows_from_csv_skipping, InputError, SkippedRow,
+};
+use crate::output::offset_column;
+use crate::timefmt::TimeUnit;
+use crate::{Competitor, Minutes, Window};
+
+/// How many bytes of a file [`Importer::detect`] is shown.
+pub const DETECT_HEAD_LEN: usize = 4096;
+
+/// Reads entries in one file format. Implement it for a format this crate
+/// does not know and add it to an [`ImporterRegistry`].
+pub trait Importer {
+    /// Whether this importer reads the file at `path`, judged by its name and
+    /// its first bytes.
+    fn detect(&self, path: &Path, head: &[u8]) -> bool;
+
+    fn import(
+        &self,
+        reader: &mut dyn Read,
+        options: &ImportOptions,
+    ) -> Result<ImportedEntries, ImportError>;
+}
+
+/// What an importer knows of the event beyond the file itself.
+#[derive(Debug, Clone, Default)]
+pub struct ImportOptions {
+    // the event file's windows, `None` for a window without a name
+    pub window_names: Vec<Option<String>>,
+    // rows an importer may leave out before it gives up on the file
+    pub max_skipped: Option<usize>,
+    // the event file's window durations, for entries without a window
+    pub window_durations: Vec<Minutes>,
+    // a start list read back as entries keeps everyone in the window they
+    // started in, instead of dealing them out afresh
+    pub reuse_windows: bool,
+}
+
+impl ImportOptions {
+    /// The 0-based index of the event window `reference` names, by its name
+    /// or its 1-based position.
+    pub fn window_index(&self, reference: &str) -> Option<usize> {
+        window_index(&self.window_names, reference)
+    }
+}
+
+/// What an importer read: window definitions, competitors for windows
+/// defined elsewhere, or both.
+#[derive(Debug, Clone, Default)]
+pub struct ImportedEntries {
+    // with the competitors entered in them
+    pub windows: Option<Vec<Window>>,
+    // the 0-based index of each one's window, among `windows` if the import
+    // defines them, otherwise among the event's
+    pub competitors: Vec<(usize, Competitor)>,
+    // what the organiser should know, e.g. rows the importer skipped
+    pub diagnostics: Vec<String>,
+}
+
+impl ImportedEntries {
+    /// The windows to draw: the import's own, or else `event_windows`, with
+    /// the imported competitors added.
+    pub fn into_windows(
+        self,
+        event_windows: Option<Vec<Window>>,
+    ) -> Result<Vec<Window>, ImportError> {
+        let mut windows = self
+            .windows
+            .or(event_windows)
+            .ok_or(ImportError::NoWindows)?;
+        for (index, competitor) in self.competitors {
+            let window_count = windows.len();
+            windows
+                .get_mut(index)
+                .ok_or(ImportError::UnknownWindow {
+                    window: index,
+                    window_count,
+                })?
+                .competitors
+                .push_back(competitor);
+        }
+        Ok(windows)
+    }
+}
+
+/// The importers to try on a file, in the order they are consulted. The
+/// default registry holds the built-in ones; a downstream crate adds its own
+/// with [`ImporterRegistry::register`].
+pub struct ImporterRegistry {
+    importers: Vec<Box<dyn Importer>>,
+}
+
+impl Default for ImporterRegistry {
+    fn default() -> Self {
+        let mut registry = ImporterRegistry::empty();
+        registry
+            .register(WindowsCsvImporter)
+            .register(EntriesCsvImporter)
+            .register(StartListCsvImporter);
+        registry
+    }
+}
+
+impl ImporterRegistry {
+    pub fn empty() -> Self {
+        ImporterRegistry {
+            importers: Vec::new(),
+        }
+    }
+
+    /// Adds `importer` after those already registered.
+    pub fn register(&mut self, importer: impl Importer + 'static) -> &mut Self {
+        self.importers.push(Box::new(importer));
+        self
+    }
+
+    /// Reads the file at `path` with the first importer that detects it.
+    pub fn import_path(
+        &self,
+        path: &Path,
+        options: &ImportOptions,
+    ) -> Result<ImportedEntries, ImportError> {
+        let bytes = std::fs::read(path).map_err(ImportError::Io)?;
+        let head = &bytes[..bytes.len().min(DETECT_HEAD_LEN)];
+        let importer = self
+            .importers
+            .iter()
+            .find(|importer| importer.detect(path, head))
+            .ok_or_else(|| ImportError::UnknownFormat(path.to_path_buf()))?;
+        importer.import(&mut bytes.as_slice(), options)
+    }
+}
+
+/// The windows CSV of [`crate::input::windows_from_csv`], told apart by its
+/// `window_id` column.
+pub struct WindowsCsvImporter;
+
+impl Importer for WindowsCsvImporter {
+    fn detect(&self, _path: &Path, head: &[u8]) -> bool {
+        first_line(head)
+            .and_then(|header| csv_header(&header).ok())
+            .is_some_and(|(_, fields)| fields.iter().any(|field| field.trim() == "window_id"))
+    }
+
+    fn import(
+        &self,
+        reader: &mut dyn Read,
+        options: &ImportOptions,
+    ) -> Result<ImportedEntries, ImportError> {
+        let (windows, skipped) = windows_from_csv_skipping(&read_text(reader)?)?;
+        Ok(ImportedEntries {
+            windows: Some(windows),
+            diagnostics: skipped_rows(skipped, options)?,
+            ..ImportedEntries::default()
+        })
+    }
+}
+
+/// The entries CSV of [`crate::input::EventInput::add_entries_csv`], for the
+/// event file's windows.
+pub struct EntriesCsvImporter;
+
+impl Importer for EntriesCsvImporter {
+    fn detect(&self, _path: &Path, head: &[u8]) -> bool {
+        first_line(head).is_some_and(|header| is_entries_header(&header))
+    }
+
+    fn import(
+        &self,
+        reader: &mut dyn Read,
+        options: &ImportOptions,
+    ) -> Result<ImportedEntries, ImportError> {
+        let mut skipped = Vec::new();
+        let competitors = entries_from_csv(
+            &read_text(reader)?,
+            &options.window_names,
+            Some(&mut skipped),
+        )?;
+        Ok(ImportedEntries {
+            competitors,
+            diagnostics: skipped_rows(skipped, options)?,
+            ..ImportedEntries::default()
+        })
+    }
+}
+
+/// A start list of [`crate::output::export_csv`] read back as entries, e.g.
+/// last year's list as this year's entry list, told apart by its `position`,
+/// `start_time` and `window_index` columns. The old start times are dropped,
+/// and so are ceremonial starts and sweepers, who were never entered. With
+/// [`ImportOptions::reuse_windows`] everyone goes back to the window they
+/// started in; otherwise they are dealt out over the event's windows in
+/// proportion to the windows' durations, in name order.
+pub struct StartListCsvImporter;
+
+impl Importer for StartListCsvImporter {
+    fn detect(&self, _path: &Path, head: &[u8]) -> bool {
+        first_line(head)
+            .and_then(|header| csv_header(&header).ok())
+            .is_some_and(|(_, fields)| {
+                ["position", "start_time", "window_index"]
+                    .iter()
+                    .all(|column| fields.iter().any(|field| field.trim() == *column))
+            })
+    }
+
+    fn import(
+        &self,
+        reader: &mut dyn Read,
+        options: &ImportOptions,
+    ) -> Result<ImportedEntries, ImportError> {
+        let text = read_text(reader)?;
+        let unit = if first_line(text.as_bytes())
+            .unwrap_or_default()
+            .contains(offset_column(TimeUnit::Seconds))
+        {
+            TimeUnit::Seconds
+        } else {
+            TimeUnit::Minutes
+        };
+        let mut starts: Vec<_> = startlist_from_csv(&text, unit)?
+            .into_iter()
+            .filter(|start| !start.competitor.ceremonial && !start.competitor.sweeper)
+            .collect();
+        if options.reuse_windows {
+            starts.sort_by_key(|start| start.offset);
+            return Ok(ImportedEntries {
+                competitors: starts
+                    .into_iter()
+                    .map(|start| (start.window, start.competitor))
+                    .collect(),
+                ..ImportedEntries::default()
+            });
+        }
+
+        if options.window_durations.is_empty() {
+            return Err(ImportError::NoWindows);
+        }
+        starts.sort_by(|a, b| a.competitor.name.cmp(&b.competitor.name));
+        let shares = shares(starts.len(), &options.window_durations);
+        // each goes where the fewest of the window's share are dealt yet, so
+        // every window gets names from across the alphabet
+        let mut dealt = vec![0usize; shares.len()];
+        let competitors = starts
+            .into_iter()
+            .map(|start| {
+                let window = (0..shares.len())
+                    .filter(|&i| dealt[i] < shares[i])
+                    .min_by(|&i, &j| {
+                        let filled = |k: usize| dealt[k] as f64 / shares[k] as f64;
+                        filled(i).total_cmp(&filled(j))
+                    })
+                    .expect("the shares add up to every competitor");
+                dealt[window] += 1;
+                (window, start.competitor)
+            })
+            .collect();
+        Ok(ImportedEntries {
+            competitors,
+            ..ImportedEntries::default()
+        })
+    }
+}
+
+// `count` split in proportion to `durations`, the largest remainders
+// rounded up, the earlier window first among equal ones
+fn shares(count: usize, durations: &[Minutes]) -> Vec<usize> {
+    let total: Minutes = durations.iter().map(|&d| d.max(0)).sum();
+    if total == 0 {
+        let mut shares = vec![0; durations.len()];
+        shares[0] = count;
+        return shares;
+    }
+    let exact: Vec<f64> = durations
+        .iter()
+        .map(|&d| count as f64 * d.max(0) as f64 / total as f64)
+        .collect();
+    let mut shares: Vec<usize> = exact.iter().map(|share| share.floor() as usize).collect();
+    let mut by_remainder: Vec<usize> = (0..durations.len()).collect();
+    by_remainder
+        .sort_by(|&i, &j| (exact[j] - exact[j].floor()).total_cmp(&(exact[i] - exact[i].floor())));
+    let left = count - shares.iter().sum::<usize>();
+    for &i in by_remainder.iter().take(left) {
+        shares[i] += 1;
+    }
+    shares
+}
+
+// the skipped rows as diagnostics, unless there are more than allowed
+fn skipped_rows(
+    skipped: Vec<SkippedRow>,
+    options: &ImportOptions,
+) -> Result<Vec<String>, ImportError> {
+    if let Some(max_skipped) = options.max_skipped.filter(|&max| skipped.len() > max) {
+        return Err(ImportError::TooManySkipped {
+            skipped,
+            max_skipped,
+        });
+    }
+    Ok(skipped.iter().map(SkippedRow::to_string).collect())
+}
+
+// the first line that is not blank; the head may end mid-line
+fn first_line(head: &[u8]) -> Option<String> {
+    String::from_utf8_lossy(head)
+        .lines()
+        .find(|line| !line.trim().is_empty() && !is_comment(line))
+        .map(str::to_string)
+}
+
+fn read_text(reader: &mut dyn Read) -> Result<String, ImportError> {
+    let mut text = String::new();
+    reader.read_to_string(&mut text).map_err(ImportError::Io)?;
+    Ok(text)
+}
+
+#[derive(Debug)]
+pub enum ImportError {
+    Io(std::io::Error),
+    // no registered importer detected the file
+    UnknownFormat(PathBuf),
+    // competitors were imported, but neither the import nor the event has windows
+    NoWindows,
+    UnknownWindow {
+        window: usize,
+        window_count: usize,
+    },
+    Input(InputError),
+    TooManySkipped {
+        skipped: Vec<SkippedRow>,
+        max_skipped: usize,
+    },
+    // for importers outside this crate
+    Other(Box<dyn std::error::Error + Send + Sync>),
+}
+
+impl From<InputError> for ImportError {
+    fn from(error: InputError) -> Self {
+        ImportError::Input(error)
+    }
+}
+
+impl fmt::Display for ImportError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ImportError::Io(error) => write!(f, "{}", error),
+            ImportError::UnknownFormat(path) => {
+                write!(f, "no importer reads {}", path.display())
+            }
+            ImportError::NoWindows => {
+                write!(f, "the entries are for an event's windows, but it has none")
+            }
+            ImportError::UnknownWindow {
+                window,
+                window_count,
+            } => write!(
+                f,
+                "an entry is for window {}, but there are {} windows",
+                window + 1,
+                window_count
+            ),
+            ImportError::Input(error) => write!(f, "{}", error),
+            ImportError::TooManySkipped {
+                skipped,
+                max_skipped,
+            } => {
+                write!(
+                    f,
+                    "{} rows could not be read, more than the {} allowed",
+                    skipped.len(),
+                    max_skipped
+                )?;
+                for row in skipped {
+                    write!(f, "\n{}", row)?;
+                }
+                Ok(())
+            }
+            ImportError::Other(error) => write!(f, "{}", error),
+        }
+    }
+}
+
+impl std::error::Error for ImportError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            ImportError::Io(error) => Some(error),
+            ImportError::Input(error) => Some(error),
+            ImportError::Other(error) => Some(error.as_ref()),
+            _ => None,
+        }
+    }
+}
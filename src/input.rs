@@ -0,0 +1,1287 @@
+use std::fmt;
+
+use chrono::NaiveTime;
+
+use crate::config::FinishEstimate;
+use crate::constraints::{
+    Constraints, ExclusiveRadius, FixedSlot, LockedWindow, CONSTRAINTS_VERSION,
+};
+use crate::import::ImportOptions;
+use crate::output::offset_column;
+use crate::sync::DrawOperation;
+use crate::timefmt::{parse_clock, TimeUnit};
+use crate::{Competitor, CompetitorWithOffset, Minutes, Window};
+
+/// An event read from an input file. Settings the file leaves out are `None`
+/// so the caller's defaults apply.
+pub struct EventInput {
+    pub start_time: Option<NaiveTime>,
+    pub time_unit: Option<TimeUnit>, // what durations and spacings in the file count
+    pub spacing_threshold: Option<Minutes>,
+    pub min_spacing: Option<Minutes>,
+    pub same_club_min_spacing: Option<Minutes>,
+    pub max_per_club_per_window: Option<usize>,
+    // (club, from, to) of the clubs staffing the event
+    pub duty_shifts: Vec<(String, NaiveTime, NaiveTime)>,
+    pub call_up: Option<Minutes>,
+    // of the [[class]] sections, for the expected finish times
+    pub finish_estimates: Vec<FinishEstimate>,
+    pub entries: Option<String>, // path of a `window,name` CSV, relative to the file
+    // of a saved draw that sync updated, see SavedDraw
+    pub revision: u32,
+    pub log: Vec<(u32, DrawOperation)>,
+    // the saved draw's [constraints] section, if it has one
+    pub constraints: Option<Constraints>,
+    pub windows: Vec<Window>,
+    window_names: Vec<Option<String>>,
+}
+
+/// Parses an event file, a small subset of TOML:
+///
+/// ```toml
+/// start_time = "09:00"
+/// time_unit = "minutes"  # or "seconds"
+/// spacing_threshold = 3
+/// min_spacing = 2
+/// same_club_min_spacing = 4  # optional, clubs come from the entries CSV
+/// max_per_club_per_window = 6  # optional
+/// duty_shifts = ["OK Norr@10:00-11:00"]  # clubs staffing the event, optional
+/// call_up = 3  # competitors are called this long before their start
+/// revision = 2  # of a saved draw, with the log of what each update changed
+/// log = ["1: removed Bo Ek from window 1", "2: added Dan Fors to window 2"]
+///
+/// [[window]]
+/// name = "early"
+/// duration = 30
+/// competitors = ["Anna Berg", "Bo Ek"]
+/// pinned = ["Cecilia Dahl@12"]  # starts exactly 12 after start_time
+/// clubs = ["OK Linné", "", "IFK Lidingö"]  # "" for none
+/// categories = ["W21", "M21", "W21"]
+/// locked = true
+/// spacing_multiplier = 1.5
+/// expected_duration = 90
+///
+/// [constraints]  # of a saved draw, see output::export_draw
+/// version = 1
+/// fixed_slots = ["Cecilia Dahl@12"]
+/// exclusive_radii = ["Cecilia Dahl@12~4"]
+/// locked_windows = ["1@0-30"]
+/// locked_entries = ["1:Anna Berg", "1:Bo Ek", "1:Cecilia Dahl"]
+///
+/// [[class]]  # optional, one per category with a finish estimate
+/// name = "W21"
+/// est_winning_time = 45  # after the start
+/// spread_pct = 30  # the field finishes within 30% more, 0 if not given
+/// ```
+///
+/// Every window needs a positive `duration`; everything else is optional.
+/// `clubs` and `categories` give one entry per competitor, then one per
+/// pinned start. Competitors may instead come from the `entries` CSV, see
+/// [`EventInput::add_entries_csv`]. The `[constraints]` section ends at the
+/// next `[[window]]`, as does a `[[class]]` section.
+pub fn parse_event(text: &str) -> Result<EventInput, InputError> {
+    let mut event = EventInput {
+        start_time: None,
+        time_unit: None,
+        spacing_threshold: None,
+        min_spacing: None,
+        same_club_min_spacing: None,
+        max_per_club_per_window: None,
+        duty_shifts: Vec::new(),
+        call_up: None,
+        finish_estimates: Vec::new(),
+        entries: None,
+        revision: 0,
+        log: Vec::new(),
+        constraints: None,
+        windows: Vec::new(),
+        window_names: Vec::new(),
+    };
+    let mut durations: Vec<Option<Minutes>> = Vec::new();
+    // (from_earlier, from_later) of a saved draw, per window
+    let mut moved_in: Vec<(usize, usize)> = Vec::new();
+    let mut pinned: Vec<Vec<Competitor>> = Vec::new();
+    // (key, line, one entry per competitor) of the window's clubs and categories
+    let mut details: Vec<Vec<(&'static str, usize, Vec<String>)>> = Vec::new();
+
+    // (line, window, name) of every locked_entries item, for their locked windows
+    let mut locked_entries: Vec<(usize, usize, String)> = Vec::new();
+    let mut in_constraints = false;
+    // (line, name, est_winning_time, spread_pct) of every [[class]]
+    let mut classes: Vec<(usize, Option<String>, Option<Minutes>, f64)> = Vec::new();
+    let mut in_class = false;
+
+    let mut lines = text.lines().enumerate().map(|(i, line)| (i + 1, line));
+    while let Some((line_number, line)) = lines.next() {
+        let line = strip_comment(line).trim();
+        if line.is_empty() {
+            continue;
+        }
+        if line == "[constraints]" {
+            event.constraints = Some(Constraints::default());
+            in_constraints = true;
+            in_class = false;
+            continue;
+        }
+        if line == "[[class]]" {
+            classes.push((line_number, None, None, 0.0));
+            in_class = true;
+            in_constraints = false;
+            continue;
+        }
+        if line == "[[window]]" {
+            in_constraints = false;
+            in_class = false;
+            event.windows.push(Window::new(0, []));
+            event.window_names.push(None);
+            durations.push(None);
+            moved_in.push((0, 0));
+            pinned.push(Vec::new());
+            details.push(Vec::new());
+            continue;
+        }
+        let (key, raw) = line.split_once('=').ok_or_else(|| InputError::Syntax {
+            line: line_number,
+            message: format!("expected 'key = value' or '[[window]]', got '{}'", line),
+        })?;
+        let key = key.trim();
+        // an array may run over several lines until its closing bracket
+        let mut raw = raw.trim().to_string();
+        if raw.starts_with('[') {
+            while !raw.ends_with(']') {
+                let (_, next) = lines.next().ok_or_else(|| InputError::Syntax {
+                    line: line_number,
+                    message: format!("unterminated list for '{}'", key),
+                })?;
+                raw.push(' ');
+                raw.push_str(strip_comment(next).trim());
+            }
+        }
+        let value = parse_value(&raw, line_number)?;
+
+        if in_class {
+            let class = classes.last_mut().unwrap();
+            match key {
+                "name" => class.1 = Some(value.text(key, line_number)?),
+                "est_winning_time" => class.2 = Some(value.integer(key, line_number)?),
+                "spread_pct" => class.3 = value.float(key, line_number)?,
+                _ => {
+                    return Err(InputError::UnknownKey {
+                        line: line_number,
+                        key: key.to_string(),
+                    })
+                }
+            }
+            continue;
+        }
+        if in_constraints {
+            let constraints = event.constraints.as_mut().unwrap();
+            let invalid = |entry: &str, expected: &str| InputError::Syntax {
+                line: line_number,
+                message: format!("invalid {} entry '{}', expected {}", key, entry, expected),
+            };
+            match key {
+                "version" => {
+                    let version = value.integer(key, line_number)?;
+                    if version != CONSTRAINTS_VERSION as isize {
+                        return Err(InputError::Syntax {
+                            line: line_number,
+                            message: format!(
+                                "constraints version {} is not supported, only {}",
+                                version, CONSTRAINTS_VERSION
+                            ),
+                        });
+                    }
+                }
+                "fixed_slots" => {
+                    for entry in value.list(key, line_number)? {
+                        let (name, offset) = entry
+                            .rsplit_once('@')
+                            .and_then(|(name, offset)| Some((name, offset.trim().parse().ok()?)))
+                            .ok_or_else(|| invalid(&entry, "NAME@OFFSET"))?;
+                        constraints.fixed_slots.push(FixedSlot {
+                            name: name.to_string(),
+                            offset,
+                        });
+                    }
+                }
+                "exclusive_radii" => {
+                    for entry in value.list(key, line_number)? {
+                        let (name, offset, radius) = entry
+                            .rsplit_once('@')
+                            .and_then(|(name, rest)| {
+                                let (offset, radius) = rest.split_once('~')?;
+                                Some((
+                                    name,
+                                    offset.trim().parse().ok()?,
+                                    radius.trim().parse().ok()?,
+                                ))
+                            })
+                            .ok_or_else(|| invalid(&entry, "NAME@OFFSET~RADIUS"))?;
+                        constraints.exclusive_radii.push(ExclusiveRadius {
+                            name: name.to_string(),
+                            offset,
+                            radius,
+                        });
+                    }
+                }
+                "locked_windows" => {
+                    for entry in value.list(key, line_number)? {
+                        let (window, from, to) = entry
+                            .split_once('@')
+                            .and_then(|(window, range)| {
+                                let (from, to) = range.split_once('-')?;
+                                Some((
+                                    window.trim().parse::<usize>().ok()?.checked_sub(1)?,
+                                    from.trim().parse().ok()?,
+                                    to.trim().parse().ok()?,
+                                ))
+                            })
+                            .ok_or_else(|| invalid(&entry, "WINDOW@FROM-TO"))?;
+                        constraints.locked_windows.push(LockedWindow {
+                            window,
+                            from,
+                            to,
+                            names: Vec::new(),
+                        });
+                    }
+                }
+                "locked_entries" => {
+                    for entry in value.list(key, line_number)? {
+                        let (window, name) = entry
+                            .split_once(':')
+                            .and_then(|(window, name)| {
+                                Some((window.trim().parse::<usize>().ok()?.checked_sub(1)?, name))
+                            })
+                            .ok_or_else(|| invalid(&entry, "WINDOW:NAME"))?;
+                        locked_entries.push((line_number, window, name.to_string()));
+                    }
+                }
+                _ => {
+                    return Err(InputError::UnknownKey {
+                        line: line_number,
+                        key: key.to_string(),
+                    })
+                }
+            }
+            continue;
+        }
+
+        match (event.windows.last_mut(), key) {
+            (None, "start_time") => {
+                let clock = value.text(key, line_number)?;
+                event.start_time =
+                    Some(parse_clock(&clock).map_err(|message| InputError::Syntax {
+                        line: line_number,
+                        message,
+                    })?);
+            }
+            (None, "spacing_threshold") => {
+                event.spacing_threshold = Some(value.integer(key, line_number)?)
+            }
+            (None, "min_spacing") => event.min_spacing = Some(value.integer(key, line_number)?),
+            (None, "same_club_min_spacing") => {
+                event.same_club_min_spacing = Some(value.integer(key, line_number)?)
+            }
+            (None, "max_per_club_per_window") => {
+                let cap = value.integer(key, line_number)?;
+                event.max_per_club_per_window =
+                    Some(usize::try_from(cap).map_err(|_| InputError::Syntax {
+                        line: line_number,
+                        message: format!(
+                            "max_per_club_per_window must not be negative, got {}",
+                            cap
+                        ),
+                    })?)
+            }
+            (None, "duty_shifts") => {
+                for entry in value.list(key, line_number)? {
+                    let invalid = |message: String| InputError::Syntax {
+                        line: line_number,
+                        message,
+                    };
+                    let (club, shift) = entry.rsplit_once('@').ok_or_else(|| {
+                        invalid(format!(
+                            "invalid duty shift '{}', expected CLUB@HH:MM-HH:MM",
+                            entry
+                        ))
+                    })?;
+                    let (from, to) = shift.split_once('-').ok_or_else(|| {
+                        invalid(format!(
+                            "invalid duty shift '{}', expected CLUB@HH:MM-HH:MM",
+                            entry
+                        ))
+                    })?;
+                    event.duty_shifts.push((
+                        club.trim().to_string(),
+                        parse_clock(from.trim()).map_err(invalid)?,
+                        parse_clock(to.trim()).map_err(invalid)?,
+                    ));
+                }
+            }
+            (None, "call_up") => event.call_up = Some(value.integer(key, line_number)?),
+            (None, "entries") => event.entries = Some(value.text(key, line_number)?),
+            (None, "revision") => {
+                let revision = value.integer(key, line_number)?;
+                event.revision = u32::try_from(revision)
+                    .map_err(|_| wrong_type(key, "a revision number", line_number))?;
+            }
+            (None, "log") => {
+                for entry in value.list(key, line_number)? {
+                    let invalid = |message: String| InputError::Syntax {
+                        line: line_number,
+                        message,
+                    };
+                    let (revision, operation) = entry.split_once(": ").ok_or_else(|| {
+                        invalid(format!(
+                            "invalid log entry '{}', expected REVISION: OPERATION",
+                            entry
+                        ))
+                    })?;
+                    event.log.push((
+                        revision.trim().parse().map_err(|_| {
+                            invalid(format!("invalid revision '{}' in the log", revision))
+                        })?,
+                        operation.parse().map_err(invalid)?,
+                    ));
+                }
+            }
+            (None, "time_unit") => {
+                let unit = value.text(key, line_number)?;
+                event.time_unit = Some(unit.parse().map_err(|message| InputError::Syntax {
+                    line: line_number,
+                    message,
+                })?);
+            }
+            (Some(window), "duration") => {
+                let duration = value.integer(key, line_number)?;
+                window.duration = duration;
+                *durations.last_mut().unwrap() = Some(duration);
+            }
+            (Some(window), "min_spacing") => {
+                window.min_spacing = Some(value.integer(key, line_number)?)
+            }
+            (Some(window), "spacing_threshold") => {
+                window.spacing_threshold = Some(value.integer(key, line_number)?)
+            }
+            (Some(window), "locked") => window.locked = value.boolean(key, line_number)?,
+            (Some(window), "spacing_multiplier") => {
+                window.spacing_multiplier = value.float(key, line_number)?
+            }
+            (Some(window), "expected_duration") => {
+                window.expected_duration = Some(value.integer(key, line_number)?)
+            }
+            (Some(_), "clubs") => details.last_mut().unwrap().push((
+                "clubs",
+                line_number,
+                value.list(key, line_number)?,
+            )),
+            (Some(_), "categories") => details.last_mut().unwrap().push((
+                "categories",
+                line_number,
+                value.list(key, line_number)?,
+            )),
+            (Some(_), "from_earlier" | "from_later") => {
+                let count = value.integer(key, line_number)?;
+                let count = usize::try_from(count)
+                    .map_err(|_| wrong_type(key, "a count of competitors", line_number))?;
+                let moved_in = moved_in.last_mut().unwrap();
+                if key == "from_earlier" {
+                    moved_in.0 = count;
+                } else {
+                    moved_in.1 = count;
+                }
+            }
+            (Some(_), "name") => {
+                *event.window_names.last_mut().unwrap() = Some(value.text(key, line_number)?)
+            }
+            (Some(_), "pinned") => {
+                for entry in value.list(key, line_number)? {
+                    let invalid = || InputError::Syntax {
+                        line: line_number,
+                        message: format!(
+                            "invalid pinned start '{}', expected NAME@OFFSET[~RADIUS]",
+                            entry
+                        ),
+                    };
+                    let (name, offset) = entry.rsplit_once('@').ok_or_else(invalid)?;
+                    let (offset, radius) = offset.split_once('~').unwrap_or((offset, "0"));
+                    let mut competitor = competitor(name.to_string(), line_number)?;
+                    competitor.fixed_offset = Some(offset.trim().parse().map_err(|_| invalid())?);
+                    competitor.exclusive_radius = radius
+                        .trim()
+                        .parse()
+                        .ok()
+                        .filter(|&radius| radius >= 0)
+                        .ok_or_else(invalid)?;
+                    pinned.last_mut().unwrap().push(competitor);
+                }
+            }
+            (Some(window), "competitors") => {
+                for name in value.list(key, line_number)? {
+                    window.competitors.push_back(competitor(name, line_number)?);
+                }
+            }
+            _ => {
+                return Err(InputError::UnknownKey {
+                    line: line_number,
+                    key: key.to_string(),
+                })
+            }
+        }
+    }
+
+    for (line, name, winning_time, spread_pct) in classes {
+        let missing = |key: &str| InputError::Syntax {
+            line,
+            message: format!("[[class]] needs a {}", key),
+        };
+        event.finish_estimates.push(FinishEstimate {
+            class: name.ok_or_else(|| missing("name"))?,
+            winning_time: winning_time.ok_or_else(|| missing("est_winning_time"))?,
+            spread_pct,
+        });
+    }
+    for (line, window, name) in locked_entries {
+        event
+            .constraints
+            .as_mut()
+            .unwrap()
+            .locked_windows
+            .iter_mut()
+            .find(|locked| locked.window == window)
+            .ok_or_else(|| InputError::Syntax {
+                line,
+                message: format!("window {} is not among the locked_windows", window + 1),
+            })?
+            .names
+            .push(name);
+    }
+    if event.windows.is_empty() {
+        return Err(InputError::NoWindows);
+    }
+    for (i, duration) in durations.into_iter().enumerate() {
+        match duration {
+            None => return Err(InputError::MissingDuration { window: i }),
+            Some(duration) if duration <= 0 => {
+                return Err(InputError::NonPositiveDuration {
+                    window: i,
+                    duration,
+                })
+            }
+            Some(_) => {}
+        }
+    }
+    // a saved draw lists who moved in from a neighbour first and last
+    for (i, (window, (from_earlier, from_later))) in
+        event.windows.iter_mut().zip(moved_in).enumerate()
+    {
+        let competitors = window.competitors.len();
+        if from_earlier + from_later > competitors {
+            return Err(InputError::TooManyMovedIn {
+                window: i,
+                moved_in: from_earlier + from_later,
+                competitors,
+            });
+        }
+        for competitor in window.competitors.range_mut(..from_earlier) {
+            competitor.origin = -1;
+        }
+        for competitor in window.competitors.range_mut(competitors - from_later..) {
+            competitor.origin = 1;
+        }
+    }
+    for (window, window_pinned) in event.windows.iter_mut().zip(pinned) {
+        window.competitors.extend(window_pinned);
+    }
+    for (window, window_details) in event.windows.iter_mut().zip(details) {
+        for (key, line, entries) in window_details {
+            if entries.len() != window.competitors.len() {
+                return Err(InputError::Syntax {
+                    line,
+                    message: format!(
+                        "'{}' has {} entries for {} competitors",
+                        key,
+                        entries.len(),
+                        window.competitors.len()
+                    ),
+                });
+            }
+            for (competitor, entry) in window.competitors.iter_mut().zip(entries) {
+                if key == "clubs" {
+                    competitor.club = club_of(&entry);
+                } else {
+                    competitor.category = Some(entry.trim())
+                        .filter(|category| !category.is_empty())
+                        .map(str::to_string);
+                }
+            }
+        }
+    }
+    Ok(event)
+}
+
+impl EventInput {
+    /// Adds competitors from a CSV with a `window,name` header, or
+    /// `window,name,club` to give their clubs. A window is referred to by its
+    /// `name` or by its 1-based position in the file.
+    pub fn add_entries_csv(&mut self, text: &str) -> Result<(), InputError> {
+        for (index, competitor) in entries_from_csv(text, &self.window_names, None)? {
+            self.windows[index].competitors.push_back(competitor);
+        }
+        Ok(())
+    }
+
+    /// The options an importer needs to add entries to this event's windows.
+    pub fn import_options(&self) -> ImportOptions {
+        ImportOptions {
+            window_names: self.window_names.clone(),
+            window_durations: self.windows.iter().map(|w| w.duration).collect(),
+            ..ImportOptions::default()
+        }
+    }
+}
+
+/// Whether `header` is that of an entries CSV, see
+/// [`EventInput::add_entries_csv`].
+pub(crate) fn is_entries_header(header: &str) -> bool {
+    entries_header(header).is_some()
+}
+
+// the delimiter and whether there is a club column
+fn entries_header(header: &str) -> Option<(char, bool)> {
+    let (delimiter, fields) = csv_header(header).ok()?;
+    let fields: Vec<&str> = fields.iter().map(|field| field.trim()).collect();
+    match fields[..] {
+        ["window", "name"] => Some((delimiter, false)),
+        ["window", "name", "club"] => Some((delimiter, true)),
+        _ => None,
+    }
+}
+
+// every competitor of an entries CSV with the 0-based index of its window,
+// one of `window_names`; with `skipped`, rows that cannot be read are left
+// out there instead of failing the file
+pub(crate) fn entries_from_csv(
+    text: &str,
+    window_names: &[Option<String>],
+    mut skipped: Option<&mut Vec<SkippedRow>>,
+) -> Result<Vec<(usize, Competitor)>, InputError> {
+    let Some((header_line, header)) = text
+        .lines()
+        .enumerate()
+        .map(|(i, line)| (i + 1, line))
+        .find(|(_, line)| !line.trim().is_empty() && !is_comment(line))
+    else {
+        return Ok(Vec::new());
+    };
+    let (delimiter, with_club) = entries_header(header).ok_or_else(|| InputError::Syntax {
+        line: header_line,
+        message: format!(
+            "expected the header 'window,name' or 'window,name,club', got '{}'",
+            header
+        ),
+    })?;
+    let expected = if with_club { 3 } else { 2 };
+    let mut entries = Vec::new();
+    for (line_number, record) in csv_records(text, delimiter).into_iter().skip(1) {
+        let read_row = || -> Result<(usize, Competitor), InputError> {
+            let syntax = |message| InputError::Syntax {
+                line: line_number,
+                message,
+            };
+            let fields = split_delimited(&record, delimiter).map_err(syntax)?;
+            // a row may end before the optional club
+            if fields.len() < 2 || fields.len() > expected {
+                return Err(syntax(format!(
+                    "expected {} fields, got {}",
+                    expected,
+                    fields.len()
+                )));
+            }
+            let mut fields = fields.into_iter();
+            let (window, name, club) = (
+                fields.next().unwrap(),
+                fields.next().unwrap(),
+                fields.next(),
+            );
+            let index = window_index(window_names, window.trim()).ok_or_else(|| {
+                InputError::UnknownWindow {
+                    line: line_number,
+                    window: window.trim().to_string(),
+                }
+            })?;
+            let mut competitor = competitor(name, line_number)?;
+            competitor.club = club_of(club.as_deref().unwrap_or(""));
+            Ok((index, competitor))
+        };
+        match read_row() {
+            Ok(entry) => entries.push(entry),
+            Err(error) => skip_row(&mut skipped, line_number, &record, error)?,
+        }
+    }
+    Ok(entries)
+}
+
+// a window by its name, or by its 1-based position among `window_names`
+pub(crate) fn window_index(window_names: &[Option<String>], reference: &str) -> Option<usize> {
+    if let Some(index) = window_names
+        .iter()
+        .position(|name| name.as_deref() == Some(reference))
+    {
+        return Some(index);
+    }
+    reference
+        .parse::<usize>()
+        .ok()
+        .filter(|&number| (1..=window_names.len()).contains(&number))
+        .map(|number| number - 1)
+}
+
+/// Reads the `start_time` column of another start list, e.g. a partner
+/// club's, so its minutes can be kept clear.
+pub fn start_times_from_csv(text: &str) -> Result<Vec<NaiveTime>, InputError> {
+    let mut lines = text
+        .lines()
+        .enumerate()
+        .map(|(i, line)| (i + 1, line))
+        .filter(|(_, line)| !line.trim().is_empty())
+        .skip_while(|(_, line)| is_comment(line));
+    let Some((header_line, header)) = lines.next() else {
+        return Ok(Vec::new());
+    };
+    let syntax = |line, message| InputError::Syntax { line, message };
+    let column = split_csv_record(header)
+        .map_err(|message| syntax(header_line, message))?
+        .iter()
+        .position(|name| name.trim() == "start_time")
+        .ok_or(InputError::MissingColumn("start_time"))?;
+    lines
+        .map(|(line_number, line)| {
+            let fields = split_csv_record(line).map_err(|message| syntax(line_number, message))?;
+            let field = fields.get(column).ok_or_else(|| {
+                syntax(
+                    line_number,
+                    format!("expected a start_time in field {}", column + 1),
+                )
+            })?;
+            parse_clock(field.trim()).map_err(|message| syntax(line_number, message))
+        })
+        .collect()
+}
+
+/// Reads a start list written by `output::export_csv` back, taking each
+/// start from the `name`, `offset_minutes` (`offset_seconds` when `unit` is
+/// seconds) and `window_index` columns, an optional `flag` column marking
+/// ceremonial starts and sweepers, and optional `club` and `class` columns,
+/// the class read as the category.
+pub fn startlist_from_csv(
+    text: &str,
+    unit: TimeUnit,
+) -> Result<Vec<CompetitorWithOffset>, InputError> {
+    let mut lines = text
+        .lines()
+        .enumerate()
+        .map(|(i, line)| (i + 1, line))
+        .filter(|(_, line)| !line.trim().is_empty())
+        .skip_while(|(_, line)| is_comment(line));
+    let Some((header_line, header)) = lines.next() else {
+        return Ok(Vec::new());
+    };
+    let syntax = |line, message| InputError::Syntax { line, message };
+    let header = split_csv_record(header).map_err(|message| syntax(header_line, message))?;
+    let column = |name: &'static str| {
+        header
+            .iter()
+            .position(|field| field.trim() == name)
+            .ok_or(InputError::MissingColumn(name))
+    };
+    let offset_name = offset_column(unit);
+    let (name_column, offset_column, window_column) = (
+        column("name")?,
+        column(offset_name)?,
+        column("window_index")?,
+    );
+    let flag_column = column("flag").ok();
+    let (club_column, class_column) = (column("club").ok(), column("class").ok());
+    lines
+        .map(|(line_number, line)| {
+            let fields = split_csv_record(line).map_err(|message| syntax(line_number, message))?;
+            let field = |column: usize, what: &str| {
+                fields.get(column).map(|field| field.trim()).ok_or_else(|| {
+                    syntax(
+                        line_number,
+                        format!("expected a {} in field {}", what, column + 1),
+                    )
+                })
+            };
+            let invalid = |what: &str, value: &str| {
+                syntax(line_number, format!("invalid {} '{}'", what, value))
+            };
+            let offset = field(offset_column, offset_name)?;
+            let window = field(window_column, "window_index")?;
+            let mut competitor = competitor(field(name_column, "name")?.to_string(), line_number)?;
+            let optional = |column: Option<usize>| {
+                column
+                    .and_then(|column| fields.get(column))
+                    .map(|field| field.trim())
+                    .filter(|field| !field.is_empty())
+            };
+            competitor.club = optional(club_column).map(str::to_string);
+            competitor.category = optional(class_column).map(str::to_string);
+            match optional(flag_column).unwrap_or_default() {
+                "" => {}
+                "ceremonial" => competitor.ceremonial = true,
+                "sweeper" => competitor.sweeper = true,
+                flag => return Err(invalid("flag", flag)),
+            }
+            Ok(CompetitorWithOffset {
+                competitor,
+                offset: offset.parse().map_err(|_| invalid(offset_name, offset))?,
+                window: window
+                    .parse()
+                    .map_err(|_| invalid("window_index", window))?,
+            })
+        })
+        .collect()
+}
+
+/// Reads the windows and their entries from the CSV file at `path`, see
+/// [`windows_from_csv`].
+pub fn load_windows_from_csv(path: &str) -> Result<Vec<Window>, InputError> {
+    let text = std::fs::read_to_string(path).map_err(InputError::Io)?;
+    windows_from_csv(&text)
+}
+
+/// Reads windows from a CSV with `window_id`, `duration` and
+/// `competitor_name` columns, one row per competitor. Windows are laid out in
+/// the order their ids first appear and keep their competitors in row order;
+/// every row of a window must give the same duration. A row with an empty
+/// name declares a window without adding anyone to it.
+///
+/// An optional `origin` column carries the moves of a saved draw (negative
+/// for moved in from the window before, positive from the window after) for
+/// [`crate::reassign_offsets`]; a fresh draw ignores it. It defaults to 0.
+/// An optional `club` column gives each competitor's club, blank for none.
+/// An optional `locked` column locks a window any of its rows says `true`
+/// for, and an optional `pinned_offset` column pins a competitor there.
+///
+/// Fields are separated by commas, or by semicolons if the header is. A
+/// quoted field may run over several lines, and a row that ends before an
+/// optional column leaves it blank.
+pub fn windows_from_csv(text: &str) -> Result<Vec<Window>, InputError> {
+    read_windows_csv(text, None)
+}
+
+/// Like [`windows_from_csv`], but a row that cannot be read is left out and
+/// returned beside the windows instead of failing the file.
+pub fn windows_from_csv_skipping(text: &str) -> Result<(Vec<Window>, Vec<SkippedRow>), InputError> {
+    let mut skipped = Vec::new();
+    let windows = read_windows_csv(text, Some(&mut skipped))?;
+    Ok((windows, skipped))
+}
+
+fn read_windows_csv(
+    text: &str,
+    mut skipped: Option<&mut Vec<SkippedRow>>,
+) -> Result<Vec<Window>, InputError> {
+    let Some((header_line, header)) = text
+        .lines()
+        .enumerate()
+        .map(|(i, line)| (i + 1, line))
+        .find(|(_, line)| !line.trim().is_empty() && !is_comment(line))
+    else {
+        return Ok(Vec::new());
+    };
+    let syntax = |line, message| InputError::Syntax { line, message };
+    let (delimiter, header) = csv_header(header).map_err(|message| syntax(header_line, message))?;
+    let column = |name: &'static str| header.iter().position(|field| field.trim() == name);
+    let (id_column, duration_column, name_column, origin_column, club_column) = (
+        column("window_id").ok_or(InputError::MissingColumn("window_id"))?,
+        column("duration").ok_or(InputError::MissingColumn("duration"))?,
+        column("competitor_name").ok_or(InputError::MissingColumn("competitor_name"))?,
+        column("origin"),
+        column("club"),
+    );
+    let (locked_column, pinned_column) = (column("locked"), column("pinned_offset"));
+
+    let mut ids: Vec<String> = Vec::new();
+    let mut windows: Vec<Window> = Vec::new();
+    for (line_number, record) in csv_records(text, delimiter).into_iter().skip(1) {
+        let mut read_row = || -> Result<(), InputError> {
+            let fields = split_delimited(&record, delimiter)
+                .map_err(|message| syntax(line_number, message))?;
+            let field = |column: usize, what: &str| {
+                fields.get(column).map(|field| field.trim()).ok_or_else(|| {
+                    syntax(
+                        line_number,
+                        format!("expected a {} in field {}", what, column + 1),
+                    )
+                })
+            };
+            let invalid = |what: &str, value: &str| {
+                syntax(line_number, format!("invalid {} '{}'", what, value))
+            };
+
+            let id = field(id_column, "window_id")?;
+            let duration = field(duration_column, "duration")?;
+            let duration: Minutes = duration
+                .parse()
+                .map_err(|_| invalid("duration", duration))?;
+            let known = ids.iter().position(|known| known == id);
+            if let Some(index) = known {
+                if windows[index].duration != duration {
+                    return Err(InputError::ConflictingDuration {
+                        line: line_number,
+                        window: id.to_string(),
+                        duration,
+                        expected: windows[index].duration,
+                    });
+                }
+            } else if duration <= 0 {
+                return Err(InputError::NonPositiveDuration {
+                    window: ids.len(),
+                    duration,
+                });
+            }
+
+            let locked = match locked_column.map_or("", |column| {
+                fields.get(column).map_or("", |field| field.trim())
+            }) {
+                "" | "false" => false,
+                "true" => true,
+                locked => return Err(invalid("locked", locked)),
+            };
+            let name = field(name_column, "competitor_name")?;
+            let mut entry = None;
+            if !name.is_empty() {
+                let mut competitor = competitor(name.to_string(), line_number)?;
+                if let Some(origin_column) = origin_column {
+                    // a blank origin is the window's own competitor
+                    let origin = fields.get(origin_column).map_or("", |field| field.trim());
+                    if !origin.is_empty() {
+                        competitor.origin =
+                            origin.parse().map_err(|_| invalid("origin", origin))?;
+                    }
+                }
+                if let Some(club_column) = club_column {
+                    competitor.club =
+                        club_of(fields.get(club_column).map_or("", |field| field.as_str()));
+                }
+                if let Some(pinned_column) = pinned_column {
+                    let offset = fields.get(pinned_column).map_or("", |field| field.trim());
+                    if !offset.is_empty() {
+                        competitor.fixed_offset = Some(
+                            offset
+                                .parse()
+                                .map_err(|_| invalid("pinned_offset", offset))?,
+                        );
+                    }
+                }
+                // moved-in competitors sit at the ends: earlier ones first, later last
+                if let Some(last) = known.and_then(|index| windows[index].competitors.back()) {
+                    if last.origin.signum() > competitor.origin.signum() {
+                        return Err(syntax(
+                            line_number,
+                            format!(
+                                "competitors of window '{}' must be ordered by origin: \
+                                 negative, then 0, then positive",
+                                id
+                            ),
+                        ));
+                    }
+                }
+                entry = Some(competitor);
+            }
+
+            // only a row read in full declares its window
+            let index = known.unwrap_or_else(|| {
+                ids.push(id.to_string());
+                windows.push(Window::new(duration, []));
+                ids.len() - 1
+            });
+            windows[index].competitors.extend(entry);
+            windows[index].locked |= locked;
+            Ok(())
+        };
+        if let Err(error) = read_row() {
+            skip_row(&mut skipped, line_number, &record, error)?;
+        }
+    }
+    Ok(windows)
+}
+
+fn competitor(name: String, line: usize) -> Result<Competitor, InputError> {
+    if name.trim().is_empty() {
+        return Err(InputError::EmptyName { line });
+    }
+    Ok(Competitor::new(name.trim()))
+}
+
+// a blank club is no club
+fn club_of(field: &str) -> Option<String> {
+    Some(field.trim())
+        .filter(|club| !club.is_empty())
+        .map(str::to_string)
+}
+
+enum Value {
+    Integer(Minutes),
+    Float(f64),
+    Boolean(bool),
+    Text(String),
+    List(Vec<String>),
+}
+
+impl Value {
+    fn integer(self, key: &str, line: usize) -> Result<Minutes, InputError> {
+        match self {
+            Value::Integer(value) => Ok(value),
+            _ => Err(wrong_type(key, "an integer", line)),
+        }
+    }
+
+    // an integer is a number too, e.g. `spacing_multiplier = 2`
+    fn float(self, key: &str, line: usize) -> Result<f64, InputError> {
+        match self {
+            Value::Float(value) => Ok(value),
+            Value::Integer(value) => Ok(value as f64),
+            _ => Err(wrong_type(key, "a number", line)),
+        }
+    }
+
+    fn boolean(self, key: &str, line: usize) -> Result<bool, InputError> {
+        match self {
+            Value::Boolean(value) => Ok(value),
+            _ => Err(wrong_type(key, "true or false", line)),
+        }
+    }
+
+    fn text(self, key: &str, line: usize) -> Result<String, InputError> {
+        match self {
+            Value::Text(value) => Ok(value),
+            _ => Err(wrong_type(key, "a string", line)),
+        }
+    }
+
+    fn list(self, key: &str, line: usize) -> Result<Vec<String>, InputError> {
+        match self {
+            Value::List(value) => Ok(value),
+            _ => Err(wrong_type(key, "a list of strings", line)),
+        }
+    }
+}
+
+fn wrong_type(key: &str, expected: &str, line: usize) -> InputError {
+    InputError::Syntax {
+        line,
+        message: format!("'{}' must be {}", key, expected),
+    }
+}
+
+fn parse_value(raw: &str, line: usize) -> Result<Value, InputError> {
+    let syntax = |message: String| InputError::Syntax { line, message };
+    if raw.starts_with('"') {
+        let (text, rest) = parse_string(raw).map_err(syntax)?;
+        if !rest.trim().is_empty() {
+            return Err(syntax(format!("unexpected '{}' after string", rest.trim())));
+        }
+        return Ok(Value::Text(text));
+    }
+    if let Some(inner) = raw.strip_prefix('[') {
+        let mut items = Vec::new();
+        let mut rest = inner.trim_start();
+        loop {
+            if let Some(after) = rest.strip_prefix(']') {
+                if !after.trim().is_empty() {
+                    return Err(syntax(format!("unexpected '{}' after list", after.trim())));
+                }
+                return Ok(Value::List(items));
+            }
+            let (item, after) = parse_string(rest).map_err(syntax)?;
+            items.push(item);
+            rest = after.trim_start();
+            match rest.strip_prefix(',') {
+                Some(after) => rest = after.trim_start(),
+                None if rest.starts_with(']') => {}
+                None => return Err(syntax("expected ',' or ']' in list".to_string())),
+            }
+        }
+    }
+    match raw {
+        "true" => return Ok(Value::Boolean(true)),
+        "false" => return Ok(Value::Boolean(false)),
+        _ => {}
+    }
+    if let Ok(integer) = raw.parse() {
+        return Ok(Value::Integer(integer));
+    }
+    // "inf" and "NaN" parse as floats but are no use in an event
+    raw.parse()
+        .ok()
+        .filter(|float: &f64| float.is_finite())
+        .map(Value::Float)
+        .ok_or_else(|| syntax(format!("invalid value '{}'", raw)))
+}
+
+// reads a double-quoted string off the front of `raw`, returning what follows
+fn parse_string(raw: &str) -> Result<(String, &str), String> {
+    let body = raw
+        .strip_prefix('"')
+        .ok_or_else(|| format!("expected a quoted string, got '{}'", raw))?;
+    let mut text = String::new();
+    let mut escaped = false;
+    for (i, c) in body.char_indices() {
+        if escaped {
+            match c {
+                '"' | '\\' => text.push(c),
+                'n' => text.push('\n'),
+                't' => text.push('\t'),
+                _ => return Err(format!("unknown escape '\\{}'", c)),
+            }
+            escaped = false;
+        } else if c == '\\' {
+            escaped = true;
+        } else if c == '"' {
+            return Ok((text, &body[i + 1..]));
+        } else {
+            text.push(c);
+        }
+    }
+    Err("unterminated string".to_string())
+}
+
+fn strip_comment(line: &str) -> &str {
+    let mut in_string = false;
+    let mut escaped = false;
+    for (i, c) in line.char_indices() {
+        match c {
+            _ if escaped => escaped = false,
+            '\\' if in_string => escaped = true,
+            '"' => in_string = !in_string,
+            '#' if !in_string => return &line[..i],
+            _ => {}
+        }
+    }
+    line
+}
+
+/// Splits one CSV line into fields. Fields may be quoted, with `""` standing
+/// for a quote inside them.
+pub fn split_csv_record(line: &str) -> Result<Vec<String>, String> {
+    split_delimited(line, ',')
+}
+
+/// A CSV row an importer left out instead of failing the whole file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SkippedRow {
+    pub line: usize,
+    // the start of the row as written, to find it by
+    pub snippet: String,
+    pub reason: String,
+}
+
+impl fmt::Display for SkippedRow {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "line {}: skipped '{}': {}",
+            self.line, self.snippet, self.reason
+        )
+    }
+}
+
+const SNIPPET_LEN: usize = 40;
+
+// records the row an error was found in, or passes the error on when nothing
+// may be skipped
+fn skip_row(
+    skipped: &mut Option<&mut Vec<SkippedRow>>,
+    line: usize,
+    record: &str,
+    error: InputError,
+) -> Result<(), InputError> {
+    let Some(skipped) = skipped else {
+        return Err(error);
+    };
+    let mut snippet: String = record.chars().take(SNIPPET_LEN).collect();
+    if snippet.len() < record.len() {
+        snippet.push('…');
+    }
+    let reason = error.to_string();
+    // the row's line is already given, once is enough
+    let reason = reason
+        .strip_prefix(&format!("line {}: ", line))
+        .unwrap_or(&reason)
+        .to_string();
+    skipped.push(SkippedRow {
+        line,
+        snippet: snippet.replace('\n', " "),
+        reason,
+    });
+    Ok(())
+}
+
+/// The header's fields, split on `;` when it has more of those than commas,
+/// as a CSV saved in a locale with decimal commas does, otherwise on `,`.
+/// Whether `line` is a comment, as the provenance block at the top of a CSV
+/// this tool wrote is; CSV readers skip the ones before the header.
+pub(crate) fn is_comment(line: &str) -> bool {
+    line.starts_with('#')
+}
+
+pub(crate) fn csv_header(header: &str) -> Result<(char, Vec<String>), String> {
+    let count = |delimiter| split_delimited(header, delimiter).map_or(0, |fields| fields.len());
+    let delimiter = if count(';') > count(',') { ';' } else { ',' };
+    Ok((delimiter, split_delimited(header, delimiter)?))
+}
+
+// the non-blank records of a CSV with the line each starts on; a quoted field
+// may run over several lines, and an unterminated one to the end of the text
+fn csv_records(text: &str, delimiter: char) -> Vec<(usize, String)> {
+    let mut records = Vec::new();
+    let mut lines = text.lines().enumerate().map(|(i, line)| (i + 1, line));
+    while let Some((line_number, line)) = lines.next() {
+        if line.trim().is_empty() || records.is_empty() && is_comment(line) {
+            continue;
+        }
+        let mut record = line.to_string();
+        while split_delimited(&record, delimiter).is_err() {
+            let Some((_, next)) = lines.next() else {
+                break;
+            };
+            record.push('\n');
+            record.push_str(next);
+        }
+        records.push((line_number, record));
+    }
+    records
+}
+
+fn split_delimited(line: &str, delimiter: char) -> Result<Vec<String>, String> {
+    let mut fields = Vec::new();
+    let mut field = String::new();
+    let mut chars = line.chars().peekable();
+    let mut quoted = false;
+    loop {
+        match chars.next() {
+            Some('"') if quoted => {
+                if chars.next_if_eq(&'"').is_some() {
+                    field.push('"');
+                } else {
+                    quoted = false;
+                }
+            }
+            Some('"') if field.trim().is_empty() => {
+                field.clear();
+                quoted = true;
+            }
+            Some(c) if c == delimiter && !quoted => fields.push(std::mem::take(&mut field)),
+            Some(c) => field.push(c),
+            None if quoted => return Err("unterminated quoted field".to_string()),
+            None => {
+                fields.push(field);
+                return Ok(fields);
+            }
+        }
+    }
+}
+
+#[derive(Debug)]
+pub enum InputError {
+    Syntax {
+        line: usize,
+        message: String,
+    },
+    UnknownKey {
+        line: usize,
+        key: String,
+    },
+    NoWindows,
+    MissingDuration {
+        window: usize,
+    },
+    NonPositiveDuration {
+        window: usize,
+        duration: Minutes,
+    },
+    EmptyName {
+        line: usize,
+    },
+    UnknownWindow {
+        line: usize,
+        window: String,
+    },
+    MissingColumn(&'static str),
+    TooManyMovedIn {
+        window: usize,
+        moved_in: usize,
+        competitors: usize,
+    },
+    ConflictingDuration {
+        line: usize,
+        window: String,
+        duration: Minutes,
+        expected: Minutes,
+    },
+    Io(std::io::Error),
+}
+
+impl fmt::Display for InputError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            InputError::Syntax { line, message } => write!(f, "line {}: {}", line, message),
+            InputError::UnknownKey { line, key } => {
+                write!(f, "line {}: unknown key '{}'", line, key)
+            }
+            InputError::NoWindows => write!(f, "the event has no [[window]]"),
+            InputError::MissingDuration { window } => {
+                write!(f, "window {} has no duration", window + 1)
+            }
+            InputError::NonPositiveDuration { window, duration } => write!(
+                f,
+                "window {} must have a positive duration, got {}",
+                window + 1,
+                duration
+            ),
+            InputError::EmptyName { line } => {
+                write!(f, "line {}: competitor name is empty", line)
+            }
+            InputError::UnknownWindow { line, window } => {
+                write!(f, "line {}: there is no window '{}'", line, window)
+            }
+            InputError::MissingColumn(column) => write!(f, "the header has no '{}' column", column),
+            InputError::TooManyMovedIn {
+                window,
+                moved_in,
+                competitors,
+            } => write!(
+                f,
+                "window {} has {} competitors moved in but only {} competitors",
+                window + 1,
+                moved_in,
+                competitors
+            ),
+            InputError::ConflictingDuration {
+                line,
+                window,
+                duration,
+                expected,
+            } => write!(
+                f,
+                "line {}: window '{}' has duration {}, but earlier rows gave {}",
+                line, window, duration, expected
+            ),
+            InputError::Io(error) => error.fmt(f),
+        }
+    }
+}
+
+impl std::error::Error for InputError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            InputError::Io(error) => Some(error),
+            _ => None,
+        }
+    }
+}
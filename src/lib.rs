@@ -0,0 +1,2210 @@
+#![feature(iter_map_windows)]
+
+use std::borrow::Cow;
+use std::cmp::{max, min};
+use std::collections::{HashMap, VecDeque};
+use std::fmt;
+use std::ops::Div;
+use std::time::{Duration, Instant};
+
+use rand::prelude::SliceRandom;
+use rand::rngs::StdRng;
+#[cfg(not(feature = "no-entropy"))]
+use rand::thread_rng;
+use rand::{Rng, SeedableRng};
+
+use crate::config::{
+    ConfigError, ConstraintKind, DisplacementSelection, EventConfig, OffsetPattern, RollingCap,
+    Sweeper,
+};
+use crate::sync::DrawOperation;
+use crate::timefmt::{format_clock, ClockStyle};
+
+pub mod allocation;
+pub mod announcer;
+pub mod balance;
+pub mod cards;
+pub mod compare;
+pub mod config;
+pub mod constraints;
+pub mod demo;
+pub mod duty;
+pub mod filter;
+pub mod import;
+pub mod input;
+pub mod output;
+pub mod provenance;
+pub mod sync;
+pub mod timefmt;
+pub mod transport;
+
+/// Offsets and durations from the event start time, counted in the config's
+/// `time_unit`: whole minutes unless the event is set to seconds.
+pub type Minutes = isize;
+
+/// An entry in a window's draw.
+#[derive(Debug, Clone)]
+pub struct Competitor {
+    pub(crate) origin: isize, // positive->top, negative->bottom, zero->current
+    pub name: String,
+    pub category: Option<String>,
+    pub club: Option<String>, // kept EventConfig::same_club_min_spacing apart from clubmates
+    pub flexible: bool,       // volunteers to be moved to a neighbouring window
+    pub ceremonial: bool,     // placed at a fixed minute outside the draw
+    pub sweeper: bool,        // placed last in its window, outside the draw
+    // starts exactly here, outside the draw, and never leaves its window
+    pub fixed_offset: Option<Minutes>,
+    // with a fixed_offset, no other start falls within this many minutes of it
+    pub exclusive_radius: Minutes,
+}
+
+/// A competitor's place in the generated start list.
+#[derive(Debug, Clone)]
+pub struct CompetitorWithOffset {
+    pub competitor: Competitor,
+    pub offset: Minutes,
+    pub window: usize, // index of the window the competitor starts in after stabilization
+}
+
+/// A stretch of the timeline and the competitors entered to start in it.
+#[derive(Debug, Clone)]
+pub struct Window {
+    pub duration: Minutes,
+    pub competitors: VecDeque<Competitor>,
+    pub spacing_multiplier: f64, // scales the spacing of current-origin competitors, 1.0 keeps it
+    pub expected_duration: Option<Minutes>, // feeds EventConfig::min_spacing_policy
+    pub locked: bool,            // stabilization neither moves competitors in nor out
+    // when set, used for this window instead of the config's
+    pub min_spacing: Option<Minutes>,
+    pub spacing_threshold: Option<Minutes>,
+}
+
+impl Competitor {
+    /// A drawn competitor without a category or club who has not volunteered
+    /// to move.
+    pub fn new(name: impl Into<String>) -> Self {
+        Competitor {
+            origin: 0,
+            name: name.into(),
+            category: None,
+            club: None,
+            flexible: false,
+            ceremonial: false,
+            sweeper: false,
+            fixed_offset: None,
+            exclusive_radius: 0,
+        }
+    }
+}
+
+impl Window {
+    /// An unlocked window with no spacing adjustments, its competitors in
+    /// entry order.
+    pub fn new(duration: Minutes, competitors: impl IntoIterator<Item = Competitor>) -> Self {
+        Window {
+            duration,
+            competitors: competitors.into_iter().collect(),
+            spacing_multiplier: 1.0,
+            expected_duration: None,
+            locked: false,
+            min_spacing: None,
+            spacing_threshold: None,
+        }
+    }
+
+    // `usable` is the part of the duration starts may use, see usable_durations
+    fn calculate_spacing(&self, usable: Minutes) -> f64 {
+        if self.competitors.is_empty() {
+            return usable as f64;
+        }
+
+        (usable as f64).div(self.competitors.len() as f64)
+    }
+
+    /// The minimum gap between this window's starts: its own min_spacing if
+    /// set, else derived from the expected duration when the config has a
+    /// policy, otherwise the config's min_spacing.
+    pub fn min_spacing(&self, config: &EventConfig) -> Minutes {
+        match (
+            self.min_spacing,
+            &config.min_spacing_policy,
+            self.expected_duration,
+        ) {
+            (Some(min_spacing), _, _) => min_spacing,
+            // a policy may never let two competitors start together
+            (None, Some(policy), Some(expected_duration)) => max(policy(expected_duration), 1),
+            _ => config.min_spacing,
+        }
+    }
+
+    /// The spacing this window aims for: its own spacing_threshold if set,
+    /// otherwise the config's.
+    pub fn spacing_threshold(&self, config: &EventConfig) -> Minutes {
+        self.spacing_threshold.unwrap_or(config.spacing_threshold)
+    }
+}
+
+// below this a genuine shuffle too often looks ordered to be worth a warning
+const SHUFFLE_ALARM_MIN_COMPETITORS: usize = 10;
+
+/// Spearman correlation between entry positions, listed in drawn order, and
+/// the drawn positions themselves; 1.0 means the draw kept the entry order.
+fn rank_correlation(entry_positions: impl ExactSizeIterator<Item = usize>) -> f64 {
+    let n = entry_positions.len() as f64;
+    let squared_diffs: f64 = entry_positions
+        .enumerate()
+        .map(|(drawn, entry)| (drawn as f64 - entry as f64).powi(2))
+        .sum();
+    1.0 - 6.0 * squared_diffs / (n * (n * n - 1.0))
+}
+
+/// Returns each window's `[start, end)` offset range, laid out back to back
+/// in declaration order.
+pub fn window_boundaries(windows: &[Window]) -> Vec<(Minutes, Minutes)> {
+    let mut windows_curr_start = 0;
+    windows
+        .iter()
+        .map(|window| {
+            let start = windows_curr_start;
+            windows_curr_start += window.duration;
+            (start, windows_curr_start)
+        })
+        .collect()
+}
+
+/// Returns each window's `[start, end)` as clock times after the event's
+/// `start_time`, e.g. `("09:30", "10:00")`, for schedule headers.
+pub fn window_clock_ranges(windows: &[Window], config: &EventConfig) -> Vec<(String, String)> {
+    let style = ClockStyle {
+        unit: config.time_unit,
+        ..ClockStyle::default()
+    };
+    window_boundaries(windows)
+        .into_iter()
+        .map(|(start, end)| {
+            (
+                format_clock(config.start_time, start, style),
+                format_clock(config.start_time, end, style),
+            )
+        })
+        .collect()
+}
+
+/// The spacing of a set of windows, as stabilization sees it.
+#[derive(Debug, Clone, PartialEq)]
+pub struct BalanceStats {
+    pub spacings: Vec<f64>, // minutes per competitor, in window order
+    pub max_diff: f64,      // between the spacings of the unlocked windows
+}
+
+/// What the windows' spacing would be if `competitor_name` were moved into
+/// `target_window`, without moving them: an editor can show the consequence
+/// of a drag before it is dropped. A name in no window moves nobody.
+///
+/// Spacings are over whole window durations, as without a warm-up buffer.
+///
+/// # Panics
+///
+/// If `target_window` is not one of `windows`.
+pub fn simulate_move(
+    windows: &[Window],
+    competitor_name: &str,
+    target_window: usize,
+) -> BalanceStats {
+    assert!(target_window < windows.len(), "no window {}", target_window);
+    let mut windows = windows.to_vec();
+    let found = windows.iter().enumerate().find_map(|(i, w)| {
+        w.competitors
+            .iter()
+            .position(|c| c.name == competitor_name)
+            .map(|position| (i, position))
+    });
+    if let Some((from, position)) = found {
+        let competitor = windows[from].competitors.remove(position).unwrap();
+        windows[target_window].competitors.push_back(competitor);
+    }
+    let spacings: Vec<f64> = windows
+        .iter()
+        .map(|w| w.calculate_spacing(w.duration))
+        .collect();
+    BalanceStats {
+        max_diff: calculate_max_diff(&windows, &spacings),
+        spacings,
+    }
+}
+
+// each window's duration less the part of the warm-up buffer inside it
+fn usable_durations(windows: &[Window], config: &EventConfig) -> Vec<Minutes> {
+    window_boundaries(windows)
+        .into_iter()
+        .map(|(start, end)| max(0, end - max(start, config.first_start_offset)))
+        .collect()
+}
+
+/// Draws a start list: shuffles each window, rebalances competitors between
+/// neighbouring windows whose spacing falls below the threshold, then assigns
+/// every competitor a start offset from `config.start_time`, in
+/// `config.time_unit`.
+///
+/// Windows are laid out back to back in the given order. The result holds
+/// every competitor exactly once, plus any ceremonial starts and sweepers from
+/// `config`.
+#[cfg(not(feature = "no-entropy"))]
+pub fn generate_startlist(windows: Vec<Window>, config: &EventConfig) -> Vec<CompetitorWithOffset> {
+    generate_startlist_with_rng(windows, config, &mut thread_rng())
+}
+
+/// Like [`generate_startlist`], but drawn from `seed`: publishing the seed
+/// lets anyone regenerate the exact list from the same windows and config.
+pub fn generate_startlist_seeded(
+    windows: Vec<Window>,
+    config: &EventConfig,
+    seed: u64,
+) -> Vec<CompetitorWithOffset> {
+    generate_startlist_with_rng(windows, config, &mut StdRng::seed_from_u64(seed))
+}
+
+/// Like [`generate_startlist`], but every random choice (the shuffle, the
+/// spreading of leftover minutes and the choice of who is displaced) comes
+/// from `rng`, so the same rng state, windows and config give the same list.
+pub fn generate_startlist_with_rng(
+    windows: Vec<Window>,
+    config: &EventConfig,
+    rng: &mut impl Rng,
+) -> Vec<CompetitorWithOffset> {
+    generate_startlist_iter(&windows, config, rng)
+        .map(Assignment::into_owned)
+        .collect()
+}
+
+/// A start handed out by [`generate_startlist_iter`]: drawn competitors are
+/// borrowed from the caller's windows, ceremonial starts and sweepers, which
+/// only the config names, are owned.
+#[derive(Debug, Clone)]
+pub struct Assignment<'a> {
+    pub competitor: Cow<'a, Competitor>,
+    pub offset: Minutes,
+    pub window: usize, // index of the window the competitor starts in after stabilization
+}
+
+impl Assignment<'_> {
+    /// The start as [`generate_startlist_with_rng`] gives it.
+    pub fn into_owned(self) -> CompetitorWithOffset {
+        CompetitorWithOffset {
+            competitor: self.competitor.into_owned(),
+            offset: self.offset,
+            window: self.window,
+        }
+    }
+}
+
+/// Like [`generate_startlist_with_rng`], but the start list borrows its
+/// competitors from `windows` instead of owning them, so a large event is
+/// held once, in the caller's arena.
+///
+/// The draw, stabilization and every offset are settled before the first
+/// assignment comes out: club separation, the start restrictions, the taper
+/// and duty repair move starts across windows. The assignments then come
+/// window by window in start order, and the iterator must be consumed that
+/// way: a caller wanting one window reads up to its last start, as the
+/// windows are not handed out separately. A competitor is matched back to the
+/// arena by name, club and category, so of two entries alike in all three,
+/// either may be the one borrowed.
+pub fn generate_startlist_iter<'a>(
+    windows: &'a [Window],
+    config: &EventConfig,
+    rng: &mut impl Rng,
+) -> impl Iterator<Item = Assignment<'a>> + 'a {
+    let drawn = draw_order(windows.to_vec(), config, rng).windows;
+    let startlist = assign_offsets(drawn, config, &[], rng, false)
+        .expect("an unchecked draw only fails if it loses competitors");
+    let mut arena: HashMap<ArenaKey, Vec<&'a Competitor>> = HashMap::new();
+    for competitor in windows
+        .iter()
+        .flat_map(|window| window.competitors.iter())
+        .rev()
+    {
+        arena
+            .entry(arena_key(competitor))
+            .or_default()
+            .push(competitor);
+    }
+    let borrowed: Vec<Option<&'a Competitor>> = startlist
+        .iter()
+        .map(|start| {
+            let outside_draw = start.competitor.ceremonial || start.competitor.sweeper;
+            arena
+                .get_mut(&arena_key(&start.competitor))
+                .filter(|_| !outside_draw)
+                .and_then(Vec::pop)
+        })
+        .collect();
+    startlist
+        .into_iter()
+        .zip(borrowed)
+        .map(|(start, borrowed)| Assignment {
+            competitor: match borrowed {
+                Some(competitor) => Cow::Borrowed(competitor),
+                None => Cow::Owned(start.competitor),
+            },
+            offset: start.offset,
+            window: start.window,
+        })
+}
+
+// name, club and category
+type ArenaKey<'a> = (&'a str, Option<&'a str>, Option<&'a str>);
+
+fn arena_key(competitor: &Competitor) -> ArenaKey<'_> {
+    (
+        competitor.name.as_str(),
+        competitor.club.as_deref(),
+        competitor.category.as_deref(),
+    )
+}
+
+/// Like [`generate_startlist`], but malformed or overfull windows are an error
+/// instead of a panic or starts spilling past their window, as is a start that
+/// blocked intervals, the rolling cap or the allowed offsets push past the end
+/// of its window. An event without competitors gives an empty list, or
+/// [`StartListError::NoCompetitors`] with `empty_event_is_error`.
+#[cfg(not(feature = "no-entropy"))]
+pub fn try_generate_startlist(
+    windows: Vec<Window>,
+    config: &EventConfig,
+) -> Result<Vec<CompetitorWithOffset>, StartListError> {
+    try_generate_startlist_with_rng(windows, config, &mut thread_rng())
+}
+
+/// The checked counterpart of [`generate_startlist_with_rng`].
+pub fn try_generate_startlist_with_rng(
+    windows: Vec<Window>,
+    config: &EventConfig,
+    rng: &mut impl Rng,
+) -> Result<Vec<CompetitorWithOffset>, StartListError> {
+    check_windows(&windows, config, &[])?;
+    let windows = draw_order(windows, config, rng).windows;
+    assign_offsets(windows, config, &[], rng, true)
+}
+
+/// A start list and the constraints dropped to fit it, see
+/// [`EventConfig::relaxation_order`].
+#[derive(Debug, Clone)]
+pub struct RelaxedStartList {
+    pub startlist: Vec<CompetitorWithOffset>,
+    // in relaxation_order; empty when the full set of constraints fit
+    pub relaxations_applied: Vec<ConstraintKind>,
+}
+
+/// Like [`try_generate_startlist`], but when the rolling cap, the allowed
+/// offsets or the blocked intervals push starts past the end of their window,
+/// the constraints in `config.relaxation_order` are dropped until the list
+/// fits: as few as possible, and among as many, the earliest in the order.
+/// Without a relaxation that fits, the error is the unrelaxed one.
+#[cfg(not(feature = "no-entropy"))]
+pub fn try_generate_startlist_relaxed(
+    windows: Vec<Window>,
+    config: &EventConfig,
+) -> Result<RelaxedStartList, StartListError> {
+    try_generate_startlist_relaxed_with_rng(windows, config, &mut thread_rng())
+}
+
+pub fn try_generate_startlist_relaxed_with_rng(
+    windows: Vec<Window>,
+    config: &EventConfig,
+    rng: &mut impl Rng,
+) -> Result<RelaxedStartList, StartListError> {
+    let saved = try_generate_draw_with_rng(windows, config, rng)?;
+    reassign_offsets_relaxed_with_rng(&saved, config, rng)
+}
+
+/// The best-scoring start list a search found, and the seed that draws it
+/// again with [`generate_startlist_seeded`].
+#[derive(Debug, Clone)]
+pub struct BestStartList {
+    pub startlist: Vec<CompetitorWithOffset>,
+    pub seed: u64,
+    pub score: f64,
+    pub tried: u64, // seeds drawn, including any that failed
+}
+
+/// Draws the windows from seeds 0, 1, 2, ... until `budget` has passed, and
+/// keeps the list `score_fn` rates highest; the earliest seed wins a tie. At
+/// least one seed is drawn, however small the budget. Seeds that fail are
+/// skipped; only when every one fails is the last error returned.
+pub fn generate_best_within(
+    windows: &[Window],
+    config: &EventConfig,
+    budget: Duration,
+    score_fn: impl Fn(&[CompetitorWithOffset]) -> f64,
+) -> Result<BestStartList, StartListError> {
+    let deadline = Instant::now() + budget;
+    let mut best: Option<BestStartList> = None;
+    let mut last_error = None;
+    let mut seed = 0;
+    loop {
+        match try_generate_startlist_with_rng(
+            windows.to_vec(),
+            config,
+            &mut StdRng::seed_from_u64(seed),
+        ) {
+            Ok(startlist) => {
+                let score = score_fn(&startlist);
+                if best.as_ref().is_none_or(|best| score > best.score) {
+                    best = Some(BestStartList {
+                        startlist,
+                        seed,
+                        score,
+                        tried: 0,
+                    });
+                }
+            }
+            Err(error) => last_error = Some(error),
+        }
+        seed += 1;
+        if Instant::now() >= deadline || seed == u64::MAX {
+            break;
+        }
+    }
+    match best {
+        Some(best) => Ok(BestStartList {
+            tried: seed,
+            ..best
+        }),
+        None => Err(last_error.unwrap()),
+    }
+}
+
+/// A drawn running order: every window's competitors in start order, after
+/// the shuffle and stabilization but before any offsets are assigned. Keeping
+/// it lets the list be re-timed without drawing again.
+#[derive(Debug, Clone)]
+pub struct SavedDraw {
+    pub windows: Vec<Window>,
+    // one per window, from the shuffle; empty for a draw read back from a file
+    pub witnesses: Vec<DrawWitness>,
+    // only with EventConfig::random_tie_break, in the order they were drawn
+    pub tie_breaks: Vec<TieBreak>,
+    // the config's; None for a draw read back from a file
+    pub displacement_selection: Option<DisplacementSelection>,
+    // what every stabilization iteration balanced against, in order: the
+    // spacing threshold, the mean spacing when adaptive, or 1.0 for spacings
+    // relative to per-window thresholds; empty for a draw read back
+    pub thresholds: Vec<f64>,
+    pub diagnostics: Vec<Diagnostic>,
+    // how many updates sync applied since the draw, 0 for a fresh one
+    pub revision: u32,
+    // every update's changes, each with the revision it made
+    pub log: Vec<(u32, DrawOperation)>,
+}
+
+/// A stabilization step where several adjacent window pairs were equally
+/// unbalanced and the rng, not the window order, chose the pair to even out.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TieBreak {
+    // the earlier window of every tied pair, in window order
+    pub pairs: Vec<usize>,
+    pub chosen: usize,
+}
+
+/// What one window's shuffle did, for a jury to check it ran on the declared
+/// entries: `drawn_order[i]` is `entry_order[permutation[i]]`. Pinned
+/// competitors are not shuffled and appear in neither order. The drawn order
+/// is the shuffle's, before category alternation and stabilization.
+#[derive(Debug, Clone)]
+pub struct DrawWitness {
+    pub entry_order: Vec<String>,
+    pub drawn_order: Vec<String>,
+    pub permutation: Vec<usize>,
+    // rank correlation of the drawn order with the entry order, for windows
+    // large enough that a high one is suspicious
+    pub correlation: Option<f64>,
+}
+
+/// Something about a successful draw the organiser should look at.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Diagnostic {
+    // no window has anyone to draw; an error instead with
+    // EventConfig::empty_event_is_error
+    EmptyEvent,
+    // a window's drawn order correlates with its entry order above
+    // EventConfig::shuffle_alarm_threshold although the config asked for a
+    // shuffle, as if it never ran
+    ShuffleAlarm {
+        window: usize,
+        correlation: f64,
+    },
+    // more of a club's entries in a window than
+    // EventConfig::max_per_club_per_window, with where the rest would fit:
+    // (window, how many) pairs, nearest window first
+    ClubCapExceeded {
+        window: usize,
+        club: String,
+        entries: usize,
+        cap: usize,
+        suggestion: Vec<(usize, usize)>,
+    },
+}
+
+impl fmt::Display for Diagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Diagnostic::EmptyEvent => write!(f, "the event has no competitors to draw"),
+            Diagnostic::ShuffleAlarm {
+                window,
+                correlation,
+            } => write!(
+                f,
+                "window {} draw order correlates {:.2} with entry order, check that the shuffle ran",
+                window + 1,
+                correlation
+            ),
+            Diagnostic::ClubCapExceeded {
+                window,
+                club,
+                entries,
+                cap,
+                suggestion,
+            } => {
+                write!(
+                    f,
+                    "window {} has {} entries from {}, above the cap of {}",
+                    window + 1,
+                    entries,
+                    club,
+                    cap
+                )?;
+                if suggestion.is_empty() {
+                    return write!(f, "; no other window has room for them");
+                }
+                let moves: Vec<String> = suggestion
+                    .iter()
+                    .map(|(to, count)| format!("{} to window {}", count, to + 1))
+                    .collect();
+                write!(f, "; move {}", moves.join(", "))
+            }
+        }
+    }
+}
+
+// ceremonial starts are not entries, so they alone leave an event empty
+fn is_empty_event(windows: &[Window], config: &EventConfig) -> bool {
+    windows.iter().all(|w| w.competitors.is_empty()) && config.sweepers.is_empty()
+}
+
+// how many of `club` are among the drawn competitors of `competitors`
+fn club_count(competitors: &VecDeque<Competitor>, club: &str) -> usize {
+    competitors
+        .iter()
+        .filter(|c| c.fixed_offset.is_none() && c.club.as_deref() == Some(club))
+        .count()
+}
+
+// the entries already above the club cap, each with the other windows that
+// have room under it for the surplus
+fn club_cap_diagnostics(windows: &[Window], config: &EventConfig) -> Vec<Diagnostic> {
+    let Some(cap) = config.max_per_club_per_window else {
+        return Vec::new();
+    };
+    let mut diagnostics = Vec::new();
+    let mut counts: Vec<Vec<(&str, usize)>> = windows
+        .iter()
+        .map(|window| {
+            let mut clubs: Vec<&str> = window
+                .competitors
+                .iter()
+                .filter(|c| c.fixed_offset.is_none())
+                .filter_map(|c| c.club.as_deref())
+                .collect();
+            clubs.sort_unstable();
+            clubs.dedup();
+            clubs
+                .into_iter()
+                .map(|club| (club, club_count(&window.competitors, club)))
+                .collect()
+        })
+        .collect();
+    for window in 0..windows.len() {
+        for j in 0..counts[window].len() {
+            let (club, entries) = counts[window][j];
+            if entries <= cap {
+                continue;
+            }
+            let mut surplus = entries - cap;
+            let mut others: Vec<usize> = (0..windows.len()).filter(|&w| w != window).collect();
+            others.sort_by_key(|&w| w.abs_diff(window));
+            let mut suggestion = Vec::new();
+            for other in others {
+                let there = counts[other]
+                    .iter_mut()
+                    .find(|(c, _)| *c == club)
+                    .map(|(_, count)| count);
+                let room = cap.saturating_sub(there.as_deref().copied().unwrap_or(0));
+                let moved = min(room, surplus);
+                if moved == 0 {
+                    continue;
+                }
+                // later windows' suggestions see these as already there
+                match there {
+                    Some(count) => *count += moved,
+                    None => counts[other].push((club, moved)),
+                }
+                suggestion.push((other, moved));
+                surplus -= moved;
+                if surplus == 0 {
+                    break;
+                }
+            }
+            diagnostics.push(Diagnostic::ClubCapExceeded {
+                window,
+                club: club.to_string(),
+                entries,
+                cap,
+                suggestion,
+            });
+        }
+    }
+    diagnostics
+}
+
+// an ordered draw keeps the entry order on purpose and raises no alarm
+fn shuffle_alarms(witnesses: &[DrawWitness], config: &EventConfig) -> Vec<Diagnostic> {
+    if !config.shuffle {
+        return Vec::new();
+    }
+    witnesses
+        .iter()
+        .enumerate()
+        .filter_map(|(window, witness)| {
+            let correlation = witness.correlation?;
+            (correlation > config.shuffle_alarm_threshold).then_some(Diagnostic::ShuffleAlarm {
+                window,
+                correlation,
+            })
+        })
+        .collect()
+}
+
+/// The first half of [`try_generate_startlist`]: shuffles and stabilizes, but
+/// leaves the offsets to [`reassign_offsets`]. The draw carries any
+/// diagnostics for the organiser.
+#[cfg(not(feature = "no-entropy"))]
+pub fn try_generate_draw(
+    windows: Vec<Window>,
+    config: &EventConfig,
+) -> Result<SavedDraw, StartListError> {
+    try_generate_draw_with_rng(windows, config, &mut thread_rng())
+}
+
+pub fn try_generate_draw_with_rng(
+    windows: Vec<Window>,
+    config: &EventConfig,
+    rng: &mut impl Rng,
+) -> Result<SavedDraw, StartListError> {
+    // a shortage of allowed offsets is left to a relaxed re-timing
+    check_windows(&windows, config, &config.relaxation_order)?;
+    Ok(draw_order(windows, config, rng))
+}
+
+/// Assigns fresh offsets to a saved draw under `config`, e.g. after the start
+/// interval changed. Nobody is reshuffled or moved to another window, so the
+/// order of starts stays as drawn; a window that no longer fits its
+/// competitors is an error.
+#[cfg(not(feature = "no-entropy"))]
+pub fn reassign_offsets(
+    saved: &SavedDraw,
+    config: &EventConfig,
+) -> Result<Vec<CompetitorWithOffset>, StartListError> {
+    reassign_offsets_with_rng(saved, config, &mut thread_rng())
+}
+
+pub fn reassign_offsets_with_rng(
+    saved: &SavedDraw,
+    config: &EventConfig,
+    rng: &mut impl Rng,
+) -> Result<Vec<CompetitorWithOffset>, StartListError> {
+    check_windows(&saved.windows, config, &[])?;
+    assign_offsets(saved.windows.clone(), config, &[], rng, true)
+}
+
+/// The relaxed counterpart of [`reassign_offsets_with_rng`], see
+/// [`try_generate_startlist_relaxed`]. Every attempt keeps the saved order of
+/// starts.
+pub fn reassign_offsets_relaxed_with_rng(
+    saved: &SavedDraw,
+    config: &EventConfig,
+    rng: &mut impl Rng,
+) -> Result<RelaxedStartList, StartListError> {
+    let order = &config.relaxation_order;
+    // every subset of the order, fewest first, then by their earliest kinds
+    let mut attempts: Vec<Vec<ConstraintKind>> = (0..1u32 << order.len())
+        .map(|mask| {
+            (0..order.len())
+                .filter(|i| mask & (1 << i) != 0)
+                .map(|i| order[i])
+                .collect()
+        })
+        .collect();
+    attempts.sort_by_key(|relaxed: &Vec<ConstraintKind>| {
+        let positions: Vec<usize> = relaxed
+            .iter()
+            .map(|kind| order.iter().position(|k| k == kind).unwrap())
+            .collect();
+        (relaxed.len(), positions)
+    });
+    let mut unrelaxed_error = None;
+    for relaxed in attempts {
+        let result = check_windows(&saved.windows, config, &relaxed)
+            .and_then(|()| assign_offsets(saved.windows.clone(), config, &relaxed, rng, true));
+        match result {
+            Ok(startlist) => {
+                return Ok(RelaxedStartList {
+                    startlist,
+                    relaxations_applied: relaxed,
+                })
+            }
+            Err(
+                error @ (StartListError::WindowOverrun { .. }
+                | StartListError::Config(ConfigError::TooFewAllowedOffsets { .. })),
+            ) => {
+                unrelaxed_error.get_or_insert(error);
+            }
+            Err(error) => return Err(error),
+        }
+    }
+    Err(unrelaxed_error.expect("the unrelaxed attempt always runs"))
+}
+
+// `relaxed` constraints are left out of the checks
+fn check_windows(
+    windows: &[Window],
+    config: &EventConfig,
+    relaxed: &[ConstraintKind],
+) -> Result<(), StartListError> {
+    if windows.is_empty() {
+        return Err(StartListError::NoWindows);
+    }
+    if config.empty_event_is_error && is_empty_event(windows, config) {
+        return Err(StartListError::NoCompetitors);
+    }
+    if let Some((window, duration)) = windows
+        .iter()
+        .map(|w| w.duration)
+        .enumerate()
+        .find(|&(_, duration)| duration <= 0)
+    {
+        return Err(StartListError::NonPositiveDuration { window, duration });
+    }
+    for (i, window) in windows.iter().enumerate() {
+        let (min_spacing, spacing_threshold) =
+            (window.min_spacing(config), window.spacing_threshold(config));
+        if min_spacing <= 0 || min_spacing > spacing_threshold {
+            return Err(StartListError::WindowSpacing {
+                window: i,
+                min_spacing,
+                spacing_threshold,
+            });
+        }
+    }
+    let mut pinned: Vec<(&Competitor, Minutes, Minutes)> = windows
+        .iter()
+        .flat_map(|window| {
+            let min_spacing = window.min_spacing(config);
+            window.competitors.iter().filter_map(move |competitor| {
+                Some((competitor, competitor.fixed_offset?, min_spacing))
+            })
+        })
+        .collect();
+    pinned.sort_by_key(|&(_, offset, _)| offset);
+    if let Some(&(competitor, _, _)) = pinned
+        .first()
+        .filter(|&&(_, offset, _)| offset < config.first_start_offset)
+    {
+        return Err(StartListError::PinnedBeforeStart(competitor.name.clone()));
+    }
+    for pair in pinned.windows(2) {
+        let ((first, first_offset, first_spacing), (second, second_offset, second_spacing)) =
+            (pair[0], pair[1]);
+        let required_gap = max(
+            max(first_spacing, second_spacing),
+            max(first.exclusive_radius, second.exclusive_radius) + 1,
+        );
+        if second_offset - first_offset < required_gap {
+            return Err(StartListError::PinnedCollision {
+                first: first.name.clone(),
+                second: second.name.clone(),
+                offsets: (first_offset, second_offset),
+                required_gap,
+            });
+        }
+    }
+    config.check_sweepers(windows.len())?;
+    if !relaxed.contains(&ConstraintKind::AllowedOffsets) {
+        config.check_allowed_offsets(windows)?;
+    }
+    Ok(())
+}
+
+// how far either side of a pinned start no other start may fall
+fn pinned_reach(competitor: &Competitor, min_spacing: Minutes) -> Minutes {
+    max(min_spacing - 1, competitor.exclusive_radius)
+}
+
+// the spacing a start at `offset` keeps from the starts next to it:
+// `min_spacing`, or the taper's inside one of its zones
+fn tapered_spacing(
+    offset: Minutes,
+    min_spacing: Minutes,
+    zones: &[(Minutes, Minutes)],
+    config: &EventConfig,
+) -> Minutes {
+    match config.taper {
+        Some(taper)
+            if zones
+                .iter()
+                .any(|(from, to)| (*from..*to).contains(&offset)) =>
+        {
+            taper.spacing(min_spacing)
+        }
+        _ => min_spacing,
+    }
+}
+
+// the earliest offset after `offset` the next start may take, both keeping
+// the spacing either of them needs
+fn next_tapered_slot(
+    offset: Minutes,
+    min_spacing: Minutes,
+    zones: &[(Minutes, Minutes)],
+    config: &EventConfig,
+) -> Minutes {
+    let spacing = tapered_spacing(offset, min_spacing, zones, config);
+    (offset + min_spacing..)
+        .find(|&next| {
+            next - offset >= max(spacing, tapered_spacing(next, min_spacing, zones, config))
+        })
+        .unwrap()
+}
+
+/// Counts the starts in `competitors`, ceremonial ones aside, that fall in
+/// one of the config's taper zones and so keep the tapered spacing; 0
+/// without a taper. `event_end` is where the last window ends.
+pub fn tapered_starts(
+    competitors: &[CompetitorWithOffset],
+    event_end: Minutes,
+    config: &EventConfig,
+) -> usize {
+    let zones = config.taper_zones(event_end);
+    competitors
+        .iter()
+        .filter(|c| !c.competitor.ceremonial)
+        .filter(|c| {
+            zones
+                .iter()
+                .any(|(from, to)| (*from..*to).contains(&c.offset))
+        })
+        .count()
+}
+
+// shuffles every window and rebalances them; no offsets yet
+fn draw_order(mut windows: Vec<Window>, config: &EventConfig, rng: &mut impl Rng) -> SavedDraw {
+    let club_caps = club_cap_diagnostics(&windows, config);
+    let mut competitors_count: isize = 0;
+    let mut pinned = Vec::with_capacity(windows.len());
+    let mut witnesses = Vec::with_capacity(windows.len());
+
+    for window in windows.iter_mut() {
+        // pinned competitors sit out the shuffle and stabilization
+        let (window_pinned, drawn): (Vec<Competitor>, Vec<Competitor>) = window
+            .competitors
+            .drain(..)
+            .partition(|c| c.fixed_offset.is_some());
+        pinned.push(window_pinned);
+        let entry_order: Vec<String> = drawn.iter().map(|c| c.name.clone()).collect();
+        let mut entries: Vec<(usize, Competitor)> = drawn.into_iter().enumerate().collect();
+        if config.shuffle {
+            entries.shuffle(rng);
+        }
+        witnesses.push(DrawWitness {
+            entry_order,
+            drawn_order: entries.iter().map(|(_, c)| c.name.clone()).collect(),
+            permutation: entries.iter().map(|(entry, _)| *entry).collect(),
+            correlation: (entries.len() >= SHUFFLE_ALARM_MIN_COMPETITORS)
+                .then(|| rank_correlation(entries.iter().map(|(entry, _)| *entry))),
+        });
+        // moves recorded in a saved draw do not carry over into a new one
+        window
+            .competitors
+            .extend(entries.into_iter().map(|(_, competitor)| Competitor {
+                origin: 0,
+                ..competitor
+            }));
+        if config.alternate_categories {
+            alternate_categories(&mut window.competitors);
+        }
+        competitors_count += window.competitors.len() as isize;
+    }
+    competitors_count += config.sweepers.len() as isize;
+    let (mut tie_breaks, mut thresholds) = (Vec::new(), Vec::new());
+    if competitors_count > 0 {
+        (tie_breaks, thresholds) = stabilize_windows(&mut windows, config, rng);
+    }
+    // back in their own windows, after anyone moved in from the window before
+    for (window, window_pinned) in windows.iter_mut().zip(pinned) {
+        let at = window
+            .competitors
+            .iter()
+            .take_while(|c| c.origin < 0)
+            .count();
+        for competitor in window_pinned.into_iter().rev() {
+            window.competitors.insert(at, competitor);
+        }
+    }
+    let mut diagnostics = Vec::new();
+    if is_empty_event(&windows, config) {
+        diagnostics.push(Diagnostic::EmptyEvent);
+    }
+    diagnostics.extend(shuffle_alarms(&witnesses, config));
+    diagnostics.extend(club_caps);
+    SavedDraw {
+        windows,
+        diagnostics,
+        witnesses,
+        tie_breaks,
+        displacement_selection: Some(config.displacement_selection),
+        thresholds,
+        revision: 0,
+        log: Vec::new(),
+    }
+}
+
+// with `checked`, a window stabilization left with more competitors than fit
+// min_spacing apart is an error; otherwise its starts spill over. `relaxed`
+// constraints are ignored
+fn assign_offsets(
+    mut windows: Vec<Window>,
+    config: &EventConfig,
+    relaxed: &[ConstraintKind],
+    rng: &mut impl Rng,
+    checked: bool,
+) -> Result<Vec<CompetitorWithOffset>, StartListError> {
+    let mut pinned = Vec::new();
+    for (i, window) in windows.iter_mut().enumerate() {
+        let (window_pinned, drawn): (VecDeque<Competitor>, VecDeque<Competitor>) = window
+            .competitors
+            .drain(..)
+            .partition(|c| c.fixed_offset.is_some());
+        window.competitors = drawn;
+        pinned.extend(
+            window_pinned
+                .into_iter()
+                .map(|competitor| CompetitorWithOffset {
+                    offset: competitor.fixed_offset.unwrap(),
+                    competitor,
+                    window: i,
+                }),
+        );
+    }
+    let competitors_count = windows
+        .iter()
+        .map(|window| window.competitors.len() as isize)
+        .sum::<isize>()
+        + config.sweepers.len() as isize;
+
+    let boundaries = window_boundaries(&windows);
+    let window_ends: Vec<Minutes> = boundaries.iter().map(|&(_, end)| end).collect();
+    let min_spacings: Vec<Minutes> = windows.iter().map(|w| w.min_spacing(config)).collect();
+    // the explicit intervals come first, before the ceremonial clearances
+    let explicit_blocked = if relaxed.contains(&ConstraintKind::BlockedIntervals) {
+        config.blocked.len()
+    } else {
+        0
+    };
+    let config_blocked: Vec<(Minutes, Minutes)> = config
+        .blocked_intervals(&windows)
+        .skip(explicit_blocked)
+        .collect();
+    let rolling_cap = config
+        .rolling_cap
+        .filter(|_| !relaxed.contains(&ConstraintKind::RollingCap));
+    let allowed_offsets = if relaxed.contains(&ConstraintKind::AllowedOffsets) {
+        &OffsetPattern::Any
+    } else {
+        &config.allowed_offsets
+    };
+    let taper_zones = config.taper_zones(window_ends.last().copied().unwrap_or(0));
+
+    if checked {
+        // each window's room starts only min_spacing after the last start
+        // the window before has, with its starts packed from its beginning
+        let mut last: Option<(Minutes, Minutes)> = None;
+        for (i, window) in windows.iter().enumerate() {
+            let min_spacing = min_spacings[i];
+            let (start, end) = boundaries[i];
+            let mut first = max(start, config.first_start_offset);
+            if let Some((last, last_min_spacing)) = last {
+                first = max(first, last + max(last_min_spacing, min_spacing));
+            }
+            // an exclusive radius wider than a pinned start's own clearance
+            // takes the minutes it reaches beyond it out of the window
+            let excluded: Minutes = pinned
+                .iter()
+                .filter(|p| p.window == i)
+                .map(|p| {
+                    let within = |reach: Minutes| {
+                        max(
+                            0,
+                            min(p.offset + reach + 1, end) - max(p.offset - reach, first),
+                        )
+                    };
+                    within(pinned_reach(&p.competitor, min_spacing)) - within(min_spacing - 1)
+                })
+                .sum();
+            let room = end - first - excluded;
+            // packed from the window's beginning, tapered inside the zones
+            let packed = std::iter::successors(Some(first), |&offset| {
+                Some(next_tapered_slot(offset, min_spacing, &taper_zones, config))
+            });
+            let capacity = if taper_zones.is_empty() {
+                if room > 0 {
+                    ((room - 1) / min_spacing + 1) as usize
+                } else {
+                    0
+                }
+            } else {
+                packed
+                    .clone()
+                    .take_while(|&offset| offset < end - excluded)
+                    .count()
+            };
+            let competitors = window.competitors.len()
+                + config.sweepers.iter().filter(|s| s.window == i).count()
+                + pinned.iter().filter(|p| p.window == i).count();
+            if competitors > capacity {
+                // sweepers and pinned starts have their places; the drawn
+                // competitors last in draw order are the ones left over
+                let unplaced = window
+                    .competitors
+                    .iter()
+                    .skip(
+                        window
+                            .competitors
+                            .len()
+                            .saturating_sub(competitors - capacity),
+                    )
+                    .map(|c| c.name.clone())
+                    .collect();
+                return Err(StartListError::WindowOverfull {
+                    window: i,
+                    competitors,
+                    capacity,
+                    unplaced,
+                });
+            }
+            if competitors > 0 {
+                last = Some((packed.clone().nth(competitors - 1).unwrap(), min_spacing));
+            }
+        }
+    }
+    let mut competitors = smart_offset_assignments(windows, config, competitors_count, rng)?;
+    if competitors.len() != competitors_count as usize {
+        return Err(StartListError::PlacementMismatch {
+            window: None,
+            entered: competitors_count as usize,
+            placed: competitors.len(),
+        });
+    }
+    if config.same_club_min_spacing > 0 && competitors.iter().any(|c| c.competitor.club.is_some()) {
+        separate_clubs(&mut competitors, config);
+    }
+    if config.repair_transport {
+        transport::repair_transport(&mut competitors, &boundaries, &min_spacings, config);
+    }
+    // drawn competitors keep min_spacing clear of a pinned start, like of a
+    // ceremonial one, or its exclusive radius if that reaches further
+    let pinned_clearance: Vec<(Minutes, Minutes)> = pinned
+        .iter()
+        .map(|p| {
+            let reach = pinned_reach(&p.competitor, min_spacings[p.window]);
+            (p.offset - reach, p.offset + reach + 1)
+        })
+        .collect();
+    let blocked: Vec<(Minutes, Minutes)> =
+        config_blocked.into_iter().chain(pinned_clearance).collect();
+    if rolling_cap.is_some() || !blocked.is_empty() || *allowed_offsets != OffsetPattern::Any {
+        apply_start_restrictions(
+            &mut competitors,
+            rolling_cap,
+            allowed_offsets,
+            &blocked,
+            &min_spacings,
+        );
+    }
+    // re-laying a window ignores the allowed offsets and the rolling cap, so
+    // without pinned starts it is only done for plain blocked intervals
+    if !pinned.is_empty()
+        || (!blocked.is_empty() && *allowed_offsets == OffsetPattern::Any && rolling_cap.is_none())
+    {
+        fit_around_blocked(&mut competitors, &boundaries, &min_spacings, &blocked);
+    }
+    if !taper_zones.is_empty() {
+        apply_taper(
+            &mut competitors,
+            &boundaries,
+            &min_spacings,
+            &blocked,
+            &taper_zones,
+            config,
+        );
+    }
+    // blocked intervals, the rolling cap, allowed offsets and the spacing
+    // kept across windows only ever push starts later, possibly past the end
+    // of their window
+    if checked {
+        if let Some(overrun) = competitors
+            .iter()
+            .find(|c| c.offset >= boundaries[c.window].1)
+        {
+            return Err(StartListError::WindowOverrun {
+                window: overrun.window,
+                names: competitors
+                    .iter()
+                    .filter(|c| c.window == overrun.window && c.offset >= boundaries[c.window].1)
+                    .map(|c| c.competitor.name.clone())
+                    .collect(),
+                offset: overrun.offset,
+            });
+        }
+    }
+    // last, once every offset is final
+    if config.repair_duty_shifts && !config.duty_shifts.is_empty() {
+        duty::repair_duty_shifts(&mut competitors, config);
+    }
+    if !pinned.is_empty() {
+        competitors.extend(pinned);
+        competitors.sort_by_key(|c| c.offset);
+    }
+    if !config.ceremonial_starts.is_empty() {
+        for ceremonial in config.ceremonial_starts.iter() {
+            competitors.push(CompetitorWithOffset {
+                competitor: Competitor {
+                    ceremonial: true,
+                    ..Competitor::new(ceremonial.name.as_str())
+                },
+                offset: ceremonial.offset,
+                window: window_ends
+                    .iter()
+                    .position(|&end| ceremonial.offset < end)
+                    .unwrap_or(window_ends.len().saturating_sub(1)),
+            });
+        }
+        competitors.sort_by_key(|c| c.offset);
+    }
+    Ok(competitors)
+}
+
+#[derive(Debug)]
+pub enum StartListError {
+    NoWindows,
+    // only with EventConfig::empty_event_is_error
+    NoCompetitors,
+    NonPositiveDuration {
+        window: usize,
+        duration: Minutes,
+    },
+    // after stabilization, which could not move enough competitors out; the
+    // room left is counted from min_spacing after the window before's starts
+    WindowOverfull {
+        window: usize,
+        competitors: usize,
+        capacity: usize,
+        // the drawn competitors who do not fit, last in draw order
+        unplaced: Vec<String>,
+    },
+    // generation placed a different number of competitors than entered, in
+    // one window or (`None`) overall, e.g. for a saved draw whose moved-in
+    // competitors are out of order
+    PlacementMismatch {
+        window: Option<usize>,
+        entered: usize,
+        placed: usize,
+    },
+    // a start pushed past its window's end, by the start restrictions or by
+    // the spacing it must keep from the starts before it
+    WindowOverrun {
+        window: usize,
+        // every start past the end, in start order
+        names: Vec<String>,
+        // where the first of them was pushed
+        offset: Minutes,
+    },
+    // a window's spacing overrides, or what they leave of the config's
+    WindowSpacing {
+        window: usize,
+        min_spacing: Minutes,
+        spacing_threshold: Minutes,
+    },
+    // before the event start time, or inside the warm-up buffer
+    PinnedBeforeStart(String),
+    // two pinned starts closer than the stricter min_spacing of their
+    // windows, or inside either's exclusive radius
+    PinnedCollision {
+        first: String,
+        second: String,
+        offsets: (Minutes, Minutes),
+        required_gap: Minutes,
+    },
+    Config(ConfigError),
+}
+
+impl From<ConfigError> for StartListError {
+    fn from(error: ConfigError) -> Self {
+        StartListError::Config(error)
+    }
+}
+
+impl fmt::Display for StartListError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            StartListError::NoWindows => write!(f, "there are no windows to draw"),
+            StartListError::NoCompetitors => write!(f, "there are no competitors to draw"),
+            StartListError::NonPositiveDuration { window, duration } => write!(
+                f,
+                "window {} must have a positive duration, got {}",
+                window + 1,
+                duration
+            ),
+            StartListError::WindowOverfull {
+                window,
+                competitors,
+                capacity,
+                unplaced,
+            } => {
+                write!(
+                    f,
+                    "window {} has {} competitors but room for {} at min_spacing",
+                    window + 1,
+                    competitors,
+                    capacity
+                )?;
+                if !unplaced.is_empty() {
+                    write!(f, "; {} do not fit", unplaced.join(", "))?;
+                }
+                Ok(())
+            }
+            StartListError::PlacementMismatch {
+                window,
+                entered,
+                placed,
+            } => {
+                match window {
+                    Some(window) => write!(f, "window {}", window + 1)?,
+                    None => write!(f, "the draw")?,
+                }
+                write!(
+                    f,
+                    " placed {} of {} competitors; moved-in competitors must come first \
+                     or last in their window",
+                    placed, entered
+                )
+            }
+            StartListError::WindowOverrun {
+                window,
+                names,
+                offset,
+            } => write!(
+                f,
+                "{} pushed past the end of window {}, the first to offset {}, by the start \
+                 restrictions or the spacing from the starts before",
+                names.join(", "),
+                window + 1,
+                offset
+            ),
+            StartListError::WindowSpacing {
+                window,
+                min_spacing,
+                spacing_threshold,
+            } => write!(
+                f,
+                "window {} needs 0 < min_spacing ({}) <= spacing_threshold ({})",
+                window + 1,
+                min_spacing,
+                spacing_threshold
+            ),
+            StartListError::PinnedBeforeStart(name) => {
+                write!(f, "pinned start of {} is before the first start", name)
+            }
+            StartListError::PinnedCollision {
+                first,
+                second,
+                offsets,
+                required_gap,
+            } => write!(
+                f,
+                "pinned starts of {} and {}, at offsets {} and {}, must be at least {} apart",
+                first, second, offsets.0, offsets.1, required_gap
+            ),
+            StartListError::Config(error) => error.fmt(f),
+        }
+    }
+}
+
+impl std::error::Error for StartListError {}
+
+// interleaves the two categories of a mixed window, keeping the shuffled order
+// within each; the larger group leads so its remainder ends up at the back
+fn alternate_categories(competitors: &mut VecDeque<Competitor>) {
+    let mut categories: Vec<&Option<String>> = vec![];
+    for competitor in competitors.iter() {
+        if !categories.contains(&&competitor.category) {
+            categories.push(&competitor.category);
+        }
+    }
+    if categories.len() != 2 {
+        return;
+    }
+
+    let first = categories[0].clone();
+    let (mut larger, mut smaller): (VecDeque<_>, VecDeque<_>) =
+        competitors.drain(..).partition(|c| c.category == first);
+    if larger.len() < smaller.len() {
+        std::mem::swap(&mut larger, &mut smaller);
+    }
+    while let Some(competitor) = larger.pop_front() {
+        competitors.push_back(competitor);
+        if let Some(competitor) = smaller.pop_front() {
+            competitors.push_back(competitor);
+        }
+    }
+}
+
+/// Counts consecutive starts (in clock order) whose categories differ, out of
+/// all consecutive pairs.
+pub fn category_alternation(competitors: &[CompetitorWithOffset]) -> (usize, usize) {
+    let mut ordered: Vec<&CompetitorWithOffset> = competitors.iter().collect();
+    ordered.sort_by_key(|c| c.offset);
+    let alternating = ordered
+        .windows(2)
+        .filter(|pair| pair[0].competitor.category != pair[1].competitor.category)
+        .count();
+    (alternating, ordered.len().saturating_sub(1))
+}
+
+/// Returns chronologically adjacent starts closer than their windows allow,
+/// whichever windows they belong to. `min_spacings` holds each window's
+/// min_spacing; across a boundary the stricter of the two applies.
+pub fn spacing_violations<'a>(
+    competitors: &'a [CompetitorWithOffset],
+    min_spacings: &[Minutes],
+) -> Vec<(&'a CompetitorWithOffset, &'a CompetitorWithOffset)> {
+    let mut ordered: Vec<&CompetitorWithOffset> = competitors.iter().collect();
+    ordered.sort_by_key(|c| c.offset);
+    ordered
+        .windows(2)
+        .filter(|pair| {
+            let required = max(min_spacings[pair[0].window], min_spacings[pair[1].window]);
+            pair[1].offset - pair[0].offset < required
+        })
+        .map(|pair| (pair[0], pair[1]))
+        .collect()
+}
+
+/// Returns every start within a pinned start's exclusive radius, paired with
+/// the pinned start (first) it is too close to.
+pub fn exclusive_radius_violations(
+    competitors: &[CompetitorWithOffset],
+) -> Vec<(&CompetitorWithOffset, &CompetitorWithOffset)> {
+    let mut violations = Vec::new();
+    for pinned in competitors
+        .iter()
+        .filter(|c| c.competitor.fixed_offset.is_some() && c.competitor.exclusive_radius > 0)
+    {
+        violations.extend(
+            competitors
+                .iter()
+                .filter(|other| {
+                    !std::ptr::eq(*other, pinned)
+                        && (other.offset - pinned.offset).abs()
+                            <= pinned.competitor.exclusive_radius
+                })
+                .map(|other| (pinned, other)),
+        );
+    }
+    violations
+}
+
+/// Returns pairs of starts (in clock order) from the same club closer than
+/// `same_club_min_spacing`. Competitors without a club never clash.
+pub fn club_spacing_violations(
+    competitors: &[CompetitorWithOffset],
+    same_club_min_spacing: Minutes,
+) -> Vec<(&CompetitorWithOffset, &CompetitorWithOffset)> {
+    let mut ordered: Vec<&CompetitorWithOffset> = competitors.iter().collect();
+    ordered.sort_by_key(|c| c.offset);
+    let mut violations = Vec::new();
+    for (i, earlier) in ordered.iter().enumerate() {
+        if earlier.competitor.club.is_none() {
+            continue;
+        }
+        for later in ordered[i + 1..]
+            .iter()
+            .take_while(|later| later.offset - earlier.offset < same_club_min_spacing)
+        {
+            if later.competitor.club == earlier.competitor.club {
+                violations.push((*earlier, *later));
+            }
+        }
+    }
+    violations
+}
+
+// walks the starts in clock order and, when one comes too soon after a
+// clubmate, swaps names with the nearest later start of the same window who
+// fits there; offsets stay put. Moved-in competitors and sweepers keep their
+// slots, and with alternate_categories only same-category starts swap. When
+// nobody fits the clash is left for club_spacing_violations to report.
+fn separate_clubs(competitors: &mut [CompetitorWithOffset], config: &EventConfig) {
+    competitors.sort_by_key(|c| c.offset);
+    let swappable = |c: &CompetitorWithOffset| c.competitor.origin == 0 && !c.competitor.sweeper;
+    let clashes = |competitors: &[CompetitorWithOffset], i: usize, club: &Option<String>| {
+        club.is_some()
+            && competitors[..i]
+                .iter()
+                .rev()
+                .take_while(|earlier| {
+                    competitors[i].offset - earlier.offset < config.same_club_min_spacing
+                })
+                .any(|earlier| &earlier.competitor.club == club)
+    };
+    for i in 0..competitors.len() {
+        if !swappable(&competitors[i]) || !clashes(competitors, i, &competitors[i].competitor.club)
+        {
+            continue;
+        }
+        let replacement = (i + 1..competitors.len())
+            .take_while(|&j| competitors[j].window == competitors[i].window)
+            .find(|&j| {
+                swappable(&competitors[j])
+                    && (!config.alternate_categories
+                        || competitors[j].competitor.category == competitors[i].competitor.category)
+                    && !clashes(competitors, i, &competitors[j].competitor.club)
+            });
+        if let Some(j) = replacement {
+            let (before, after) = competitors.split_at_mut(j);
+            std::mem::swap(&mut before[i].competitor, &mut after[0].competitor);
+        }
+    }
+}
+
+// `blocked` holds the config's blocked intervals and any clearance around
+// pinned starts; `min_spacings` holds each window's min_spacing
+fn apply_start_restrictions(
+    competitors: &mut [CompetitorWithOffset],
+    rolling_cap: Option<RollingCap>,
+    allowed_offsets: &OffsetPattern,
+    blocked: &[(Minutes, Minutes)],
+    min_spacings: &[Minutes],
+) {
+    competitors.sort_by_key(|c| c.offset);
+
+    // a start inside a blocked interval moves to its end, and a start that
+    // would break the rolling cap waits until the oldest start in the span
+    // drops out; everyone after keeps their gap to their predecessor
+    let mut recent = VecDeque::new();
+    let mut prev: Option<(Minutes, Minutes)> = None; // (original, shifted)
+    let mut prev_final: Option<(Minutes, usize)> = None; // (offset, window)
+    let restricted = *allowed_offsets != OffsetPattern::Any;
+    for competitor in competitors.iter_mut() {
+        let original = competitor.offset;
+        let mut offset = match prev {
+            Some((prev_original, prev_shifted)) => {
+                max(original, prev_shifted + (original - prev_original))
+            }
+            None => original,
+        };
+        loop {
+            let before = offset;
+            if let Some(&(_, to)) = blocked
+                .iter()
+                .find(|(from, to)| (*from..*to).contains(&offset))
+            {
+                offset = to;
+            }
+            if let Some(cap) = rolling_cap {
+                while recent
+                    .front()
+                    .is_some_and(|&oldest| oldest <= offset - cap.minutes)
+                {
+                    recent.pop_front();
+                }
+                if recent.len() >= cap.starts {
+                    offset = recent[0] + cap.minutes;
+                }
+            }
+            if offset == before {
+                break;
+            }
+        }
+        let shifted = offset;
+        // snapping to an allowed minute is not passed on as a shift, or every
+        // start after would drift further from where it was placed
+        if restricted {
+            if let Some((prev_final, prev_window)) = prev_final {
+                let min_spacing = max(min_spacings[prev_window], min_spacings[competitor.window]);
+                offset = max(offset, prev_final + min_spacing);
+            }
+            loop {
+                let before = offset;
+                if let Some(allowed) = allowed_offsets.next_allowed(offset) {
+                    offset = allowed;
+                }
+                if let Some(&(_, to)) = blocked
+                    .iter()
+                    .find(|(from, to)| (*from..*to).contains(&offset))
+                {
+                    offset = to;
+                }
+                if let Some(cap) = rolling_cap {
+                    if recent.len() >= cap.starts
+                        && recent[recent.len() - cap.starts] > offset - cap.minutes
+                    {
+                        offset = recent[recent.len() - cap.starts] + cap.minutes;
+                    }
+                }
+                if offset == before {
+                    break;
+                }
+            }
+        }
+        if rolling_cap.is_some() {
+            recent.push_back(offset);
+        }
+        competitor.offset = offset;
+        prev = Some((original, shifted));
+        prev_final = Some((offset, competitor.window));
+    }
+}
+
+// starts inside a taper zone keep the tapered spacing from their neighbours:
+// each is pushed later past the start before it, then, where that ran a
+// window over its end, pulled back before the start after it; either way
+// past any blocked interval it lands in. Like re-laying a window, this
+// ignores the allowed offsets and the rolling cap
+fn apply_taper(
+    competitors: &mut [CompetitorWithOffset],
+    boundaries: &[(Minutes, Minutes)],
+    min_spacings: &[Minutes],
+    blocked: &[(Minutes, Minutes)],
+    zones: &[(Minutes, Minutes)],
+    config: &EventConfig,
+) {
+    competitors.sort_by_key(|c| c.offset);
+    // between starts in windows `a` and `b` at `from` and `to`
+    let required = |a: usize, from: Minutes, b: usize, to: Minutes| {
+        let min_spacing = max(min_spacings[a], min_spacings[b]);
+        max(
+            tapered_spacing(from, min_spacing, zones, config),
+            tapered_spacing(to, min_spacing, zones, config),
+        )
+    };
+    let blocked_at = |offset: Minutes| {
+        blocked
+            .iter()
+            .find(|(from, to)| (*from..*to).contains(&offset))
+            .copied()
+    };
+    for k in 1..competitors.len() {
+        let (prev_window, prev) = (competitors[k - 1].window, competitors[k - 1].offset);
+        let window = competitors[k].window;
+        loop {
+            let mut offset = competitors[k].offset;
+            offset = max(offset, prev + required(prev_window, prev, window, offset));
+            if let Some((_, to)) = blocked_at(offset) {
+                offset = to;
+            }
+            if offset == competitors[k].offset {
+                break;
+            }
+            competitors[k].offset = offset;
+        }
+    }
+    for k in (0..competitors.len()).rev() {
+        let window = competitors[k].window;
+        loop {
+            let mut offset = min(competitors[k].offset, boundaries[window].1 - 1);
+            if let Some(next) = competitors.get(k + 1) {
+                offset = min(
+                    offset,
+                    next.offset - required(window, offset, next.window, next.offset),
+                );
+            }
+            if offset >= competitors[k].offset {
+                break;
+            }
+            if let Some((from, _)) = blocked_at(offset) {
+                offset = from - 1;
+            }
+            competitors[k].offset = offset;
+        }
+    }
+}
+
+// pushing starts past a blocked interval or a pinned start can run a full
+// window over its end even when its starts would fit; such a window's starts
+// are laid out again, in the same order, spread over the stretches the
+// blocked intervals leave free
+fn fit_around_blocked(
+    competitors: &mut [CompetitorWithOffset],
+    boundaries: &[(Minutes, Minutes)],
+    min_spacings: &[Minutes],
+    blocked: &[(Minutes, Minutes)],
+) {
+    competitors.sort_by_key(|c| c.offset);
+    for (window, &(window_start, window_end)) in boundaries.iter().enumerate() {
+        let starts: Vec<usize> = (0..competitors.len())
+            .filter(|&i| competitors[i].window == window)
+            .collect();
+        if starts
+            .last()
+            .is_none_or(|&last| competitors[last].offset < window_end)
+        {
+            continue;
+        }
+        let min_spacing = min_spacings[window];
+        // keeps min_spacing from the neighbouring windows' starts too
+        let (first, last) = (starts[0], *starts.last().unwrap());
+        let lower = first.checked_sub(1).map_or(window_start, |prev| {
+            max(window_start, competitors[prev].offset + min_spacing)
+        });
+        let upper = competitors.get(last + 1).map_or(window_end, |next| {
+            min(window_end, next.offset - min_spacing + 1)
+        });
+
+        // [first, last] offsets outside every blocked interval
+        let mut stretches: Vec<(Minutes, Minutes)> = Vec::new();
+        let mut from = lower;
+        while from < upper {
+            match blocked
+                .iter()
+                .find(|(blocked_from, to)| (*blocked_from..*to).contains(&from))
+            {
+                Some(&(_, to)) => from = to,
+                None => {
+                    let to = blocked
+                        .iter()
+                        .map(|&(blocked_from, _)| blocked_from)
+                        .filter(|&blocked_from| blocked_from > from)
+                        .min()
+                        .map_or(upper, |blocked_from| blocked_from.min(upper));
+                    stretches.push((from, to - 1));
+                    from = to;
+                }
+            }
+        }
+        let capacities: Vec<usize> = stretches
+            .iter()
+            .map(|&(first, last)| ((last - first) / min_spacing + 1) as usize)
+            .collect();
+        if capacities.iter().sum::<usize>() < starts.len() {
+            continue;
+        }
+
+        // each start goes where it leaves the most room per start
+        let mut counts = vec![0usize; stretches.len()];
+        for _ in 0..starts.len() {
+            let (stretch, _) = stretches
+                .iter()
+                .enumerate()
+                .filter(|&(k, _)| counts[k] < capacities[k])
+                .map(|(k, &(first, last))| {
+                    (
+                        k,
+                        (last - first + min_spacing) as f64 / (counts[k] + 1) as f64,
+                    )
+                })
+                .fold((0, f64::NEG_INFINITY), |best, candidate| {
+                    if candidate.1 > best.1 {
+                        candidate
+                    } else {
+                        best
+                    }
+                });
+            counts[stretch] += 1;
+        }
+        let mut next = starts.iter();
+        for (&(first, last), count) in stretches.iter().zip(counts) {
+            for j in 0..count {
+                let offset = if count == 1 {
+                    first
+                } else {
+                    first + (j as Minutes * (last - first)) / (count - 1) as Minutes
+                };
+                competitors[*next.next().unwrap()].offset = offset;
+            }
+        }
+    }
+}
+
+fn smart_offset_assignments(
+    windows: Vec<Window>,
+    config: &EventConfig,
+    competitors_count: isize,
+    rng: &mut impl Rng,
+) -> Result<Vec<CompetitorWithOffset>, StartListError> {
+    let mut competitors = Vec::with_capacity(competitors_count as usize);
+    let mut curr_start = config.first_start_offset;
+    let mut windows_curr_start = 0;
+    // (offset, min_spacing) of the start placed last, in any window
+    let mut last_emitted: Option<(Minutes, Minutes)> = None;
+    for (window_index, mut window) in windows.into_iter().enumerate() {
+        let sweepers: Vec<&Sweeper> = config
+            .sweepers
+            .iter()
+            .filter(|sweeper| sweeper.window == window_index)
+            .collect();
+        if !window.competitors.is_empty() || !sweepers.is_empty() {
+            // windows left empty never moved the cursor up to this window
+            curr_start = max(curr_start, windows_curr_start);
+            let window_count = window.competitors.len() + sweepers.len();
+            let min_spacing = window.min_spacing(config);
+            let spacing_threshold = window.spacing_threshold(config);
+            let placed_before = competitors.len();
+            let window_last = windows_curr_start + window.duration - 1;
+            let mut rev_curr_start = window_last;
+            let mut top_competitors = Vec::with_capacity(window.competitors.len());
+
+            // like the moved-in block below, a top block that would reach down
+            // past the cursor (and so into the warm-up buffer, or over the rest
+            // of the window at min_spacing) is squeezed towards min_spacing
+            let top_origin_count = window
+                .competitors
+                .iter()
+                .rev()
+                .take_while(|c| c.origin > 0)
+                .count();
+            let top_count = (top_origin_count + sweepers.len()) as isize;
+            let top_floor =
+                curr_start + (window.competitors.len() - top_origin_count) as isize * min_spacing;
+            let top_spacing =
+                if top_count > 1 && window_last - (top_count - 1) * spacing_threshold < top_floor {
+                    max(min_spacing, (window_last - top_floor) / (top_count - 1))
+                } else {
+                    spacing_threshold
+                };
+            // sweepers take the final slots, so the top block ends before them
+            for sweeper in sweepers.iter().rev() {
+                top_competitors.push(CompetitorWithOffset {
+                    competitor: Competitor {
+                        sweeper: true,
+                        ..Competitor::new(sweeper.name.as_str())
+                    },
+                    offset: rev_curr_start,
+                    window: window_index,
+                });
+                rev_curr_start -= top_spacing;
+            }
+            let sweepers_count = top_competitors.len();
+            while !window.competitors.is_empty() {
+                if window.competitors[window.competitors.len() - 1].origin > 0 {
+                    top_competitors.push(CompetitorWithOffset {
+                        competitor: window.competitors.pop_back().unwrap(),
+                        offset: rev_curr_start,
+                        window: window_index,
+                    });
+                    rev_curr_start -= top_spacing;
+                } else {
+                    break;
+                }
+            }
+
+            // a moved-in block that would run into the window's own starts
+            // (each kept min_spacing below the top block), or past the window,
+            // is squeezed towards min_spacing; only then does it overflow
+            let bottom_count = window
+                .competitors
+                .iter()
+                .take_while(|c| c.origin < 0)
+                .count() as isize;
+            let has_bottom = bottom_count > 0;
+            let bottom_ceiling =
+                rev_curr_start - (window.competitors.len() as isize - bottom_count) * min_spacing;
+            let bottom_spacing = if bottom_count > 1
+                && curr_start + (bottom_count - 1) * spacing_threshold > bottom_ceiling
+            {
+                max(
+                    min_spacing,
+                    (bottom_ceiling - curr_start) / (bottom_count - 1),
+                )
+            } else {
+                spacing_threshold
+            };
+            for _ in 0..bottom_count {
+                competitors.push(CompetitorWithOffset {
+                    competitor: window.competitors.pop_front().unwrap(),
+                    offset: curr_start,
+                    window: window_index,
+                });
+                curr_start += bottom_spacing;
+            }
+            if has_bottom {
+                curr_start -= bottom_spacing;
+            }
+
+            let mut remaining_competitors = window.competitors.len() as isize;
+
+            if remaining_competitors != 0 {
+                let remaining_space = ((rev_curr_start - curr_start) as f64
+                    * window.spacing_multiplier)
+                    .round() as Minutes;
+                let (spacing, mut remainder) = (
+                    remaining_space / (remaining_competitors),
+                    remaining_space % (remaining_competitors),
+                );
+
+                let mut first_in_window = !has_bottom;
+                for comp in window.competitors {
+                    if comp.origin == 0 {
+                        if !first_in_window {
+                            if spacing >= min_spacing {
+                                if rng.gen_bool(remainder as f64 / remaining_competitors as f64) {
+                                    curr_start += 1;
+                                    remainder -= 1;
+                                }
+                                curr_start += spacing;
+                            } else {
+                                curr_start += min_spacing;
+                            }
+                        } else {
+                            first_in_window = false;
+                        }
+                        competitors.push(CompetitorWithOffset {
+                            competitor: comp,
+                            offset: curr_start,
+                            window: window_index,
+                        });
+                        remaining_competitors -= 1;
+                    } else {
+                        break;
+                    }
+                }
+            }
+
+            // an overflowing draw must not pass a sweeper; push them back in order
+            let mut last_drawn = competitors[placed_before..]
+                .iter()
+                .chain(top_competitors[sweepers_count..].iter())
+                .map(|c| c.offset)
+                .max();
+            for sweeper in top_competitors[..sweepers_count].iter_mut().rev() {
+                if let Some(last_drawn) = last_drawn {
+                    sweeper.offset = max(sweeper.offset, last_drawn + min_spacing);
+                }
+                last_drawn = Some(sweeper.offset);
+            }
+            competitors.extend(top_competitors.into_iter().rev());
+            // the loops above stop at the first competitor out of origin order
+            if competitors.len() - placed_before != window_count {
+                return Err(StartListError::PlacementMismatch {
+                    window: Some(window_index),
+                    entered: window_count,
+                    placed: competitors.len() - placed_before,
+                });
+            }
+
+            // the per-window arithmetic can put a top block before an
+            // overflowing middle, or a moved-in block too close to the last
+            // window's starts; each start is pushed past the one before it,
+            // and none starts inside the warm-up buffer
+            for competitor in competitors[placed_before..].iter_mut() {
+                competitor.offset = max(competitor.offset, config.first_start_offset);
+                if let Some((last, last_min_spacing)) = last_emitted {
+                    competitor.offset =
+                        max(competitor.offset, last + max(last_min_spacing, min_spacing));
+                }
+                last_emitted = Some((competitor.offset, min_spacing));
+            }
+
+            curr_start = if rev_curr_start == window_last {
+                max(curr_start + min_spacing, window_last + 1)
+            } else {
+                window_last + spacing_threshold
+            };
+            // whatever overflowed, the next window starts after the real last start
+            curr_start = max(curr_start, competitors.last().unwrap().offset + min_spacing);
+        }
+        windows_curr_start += window.duration;
+    }
+    Ok(competitors)
+}
+
+// brings the competitor chosen to be displaced to `end` of the deque; only
+// home competitors are chosen among, moved-in ones at the end go back as-is
+// whether `competitor` may join `target` without going over their club's cap
+fn under_club_cap(target: &Window, competitor: &Competitor, cap: Option<usize>) -> bool {
+    match (cap, competitor.club.as_deref()) {
+        (Some(cap), Some(club)) => club_count(&target.competitors, club) < cap,
+        _ => true,
+    }
+}
+
+// who may be displaced from the `end` of `competitors` into `target`, in
+// window order: whoever `selection` picks from, less anyone whose club fills
+// its cap there. A moved-in competitor at the end leaves first or nobody
+// does, so moved-in blocks stay in order
+fn displacement_candidates(
+    competitors: &VecDeque<Competitor>,
+    end: usize,
+    target: &Window,
+    selection: DisplacementSelection,
+    cap: Option<usize>,
+) -> Vec<usize> {
+    let fits = |&i: &usize| under_club_cap(target, &competitors[i], cap);
+    if competitors[end].origin != 0 {
+        return Some(end).filter(fits).into_iter().collect();
+    }
+    let home: Vec<usize> = (0..competitors.len())
+        .filter(|&i| competitors[i].origin == 0)
+        .filter(fits)
+        .collect();
+    match selection {
+        // the end itself, unless the cap passes over it
+        DisplacementSelection::DequeEnds => home
+            .into_iter()
+            .min_by_key(|&i| i.abs_diff(end))
+            .into_iter()
+            .collect(),
+        DisplacementSelection::Volunteer if home.iter().any(|&i| competitors[i].flexible) => home
+            .into_iter()
+            .filter(|&i| competitors[i].flexible)
+            .collect(),
+        _ => home,
+    }
+}
+
+// `cap` is None for undoing a move, which only ever returns someone home
+fn select_displaced(
+    windows: &mut [Window],
+    i: usize,
+    end: usize,
+    target: usize,
+    selection: DisplacementSelection,
+    cap: Option<usize>,
+    rng: &mut impl Rng,
+) {
+    let candidates = displacement_candidates(
+        &windows[i].competitors,
+        end,
+        &windows[target],
+        selection,
+        cap,
+    );
+    let chosen = if selection == DisplacementSelection::DequeEnds
+        || windows[i].competitors[end].origin != 0
+    {
+        candidates[0]
+    } else {
+        *candidates.choose(rng).unwrap()
+    };
+    windows[i].competitors.swap(end, chosen);
+}
+
+fn move_to_prev_window(
+    windows: &mut [Window],
+    i: usize,
+    selection: DisplacementSelection,
+    cap: Option<usize>,
+    rng: &mut impl Rng,
+) {
+    select_displaced(windows, i, 0, i - 1, selection, cap, rng);
+    let mut popped_competitor = windows[i].competitors.pop_front().unwrap();
+    popped_competitor.origin += 1;
+    windows[i - 1].competitors.push_back(popped_competitor);
+}
+
+fn move_to_next_window(
+    windows: &mut [Window],
+    i: usize,
+    selection: DisplacementSelection,
+    cap: Option<usize>,
+    rng: &mut impl Rng,
+) {
+    let last = windows[i].competitors.len() - 1;
+    select_displaced(windows, i, last, i + 1, selection, cap, rng);
+    let mut popped_competitor = windows[i].competitors.pop_back().unwrap();
+    popped_competitor.origin -= 1;
+    windows[i + 1].competitors.push_front(popped_competitor);
+}
+
+// `spacings` holds every window's spacing, in window order
+fn calculate_max_diff(windows: &[Window], spacings: &[f64]) -> f64 {
+    let (min, max) = spacings
+        .iter()
+        .zip(windows)
+        .filter(|(_, w)| !w.locked)
+        .fold((f64::INFINITY, f64::NEG_INFINITY), |(min, max), (&s, _)| {
+            (min.min(s), max.max(s))
+        });
+    max - min
+}
+
+// the threshold adaptive stabilization balances against: the mean spacing of
+// the windows it may still move competitors between
+fn mean_spacing(windows: &[Window], spacings: &[f64]) -> f64 {
+    let (sum, count) = spacings
+        .iter()
+        .zip(windows)
+        .filter(|(_, w)| !w.locked)
+        .fold((0.0, 0), |(sum, count), (&s, _)| (sum + s, count + 1));
+    sum / count as f64
+}
+
+// returns the ties the rng broke and every iteration's threshold, for the
+// draw witness
+fn stabilize_windows(
+    windows: &mut [Window],
+    config: &EventConfig,
+    rng: &mut impl Rng,
+) -> (Vec<TieBreak>, Vec<f64>) {
+    let mut tie_breaks = Vec::new();
+    let mut thresholds = Vec::new();
+    if windows.len() < 2 {
+        return (tie_breaks, thresholds);
+    }
+    let selection = config.displacement_selection;
+    let cap = config.max_per_club_per_window;
+
+    let mut last_movement = None::<((usize, f64), (usize, f64), f64)>;
+    let mut last_max_diff = f64::MAX;
+    // refilled every iteration; only the two windows of the last move change,
+    // but recomputing all keeps this simple and allocation free
+    let mut spacings = Vec::with_capacity(windows.len());
+    // the adjacent pairs to even out, refilled every iteration like spacings
+    let mut tied = Vec::with_capacity(windows.len() - 1);
+    // with per-window thresholds, spacings are compared as fractions of each
+    // window's own threshold; without any, as minutes like always
+    let relative = windows.iter().any(|w| w.spacing_threshold.is_some());
+    // a window partly inside the warm-up buffer has only the rest to fill
+    let usable = usable_durations(windows, config);
+    loop {
+        spacings.clear();
+        spacings.extend(windows.iter().zip(&usable).map(|(w, &usable)| {
+            if relative {
+                w.calculate_spacing(usable) / w.spacing_threshold(config) as f64
+            } else {
+                w.calculate_spacing(usable)
+            }
+        }));
+        let spacing_threshold = if config.adaptive_threshold {
+            mean_spacing(windows, &spacings)
+        } else if relative {
+            1.0
+        } else {
+            config.spacing_threshold as f64
+        };
+        thresholds.push(spacing_threshold);
+        tied.clear();
+        tied.extend(
+            spacings
+                .iter()
+                .copied()
+                .enumerate()
+                .map_windows(|[s1, s2]| (*s1, *s2, s1.1 - s2.1))
+                .filter(|(s1, s2, _)| s1.1 <= spacing_threshold || s2.1 <= spacing_threshold)
+                .filter(|(s1, s2, _)| !windows[s1.0].locked && !windows[s2.0].locked)
+                // an empty window has nobody to give away, whatever its spacing
+                // says, and one whose candidates all fill their club's cap in the
+                // neighbour has nobody it may give
+                .filter(|(s1, s2, diff)| {
+                    let (src, dst) = if *diff < 0.0 {
+                        (s1.0, s2.0)
+                    } else {
+                        (s2.0, s1.0)
+                    };
+                    let end = if src < dst {
+                        windows[src].competitors.len().wrapping_sub(1)
+                    } else {
+                        0
+                    };
+                    !windows[src].competitors.is_empty()
+                        && (cap.is_none()
+                            || !displacement_candidates(
+                                &windows[src].competitors,
+                                end,
+                                &windows[dst],
+                                selection,
+                                cap,
+                            )
+                            .is_empty())
+                }),
+        );
+        let Some(largest) = tied
+            .iter()
+            .map(|d| d.2.abs())
+            .max_by(|d1, d2| d1.partial_cmp(d2).unwrap())
+        else {
+            break;
+        };
+        tied.retain(|d| d.2.abs() == largest);
+        // among equally unbalanced pairs the one with the earlier windows wins,
+        // unless the config asks the rng to choose
+        let mut tie_break = None;
+        let curr_movement = if config.random_tie_break && tied.len() > 1 {
+            let chosen = tied[rng.gen_range(0..tied.len())];
+            tie_break = Some(TieBreak {
+                pairs: tied.iter().map(|d| d.0 .0).collect(),
+                chosen: chosen.0 .0,
+            });
+            chosen
+        } else {
+            tied[0]
+        };
+        let curr_max_diff = calculate_max_diff(windows, &spacings);
+        if (curr_max_diff > last_max_diff)
+            || last_movement.is_some_and(|(_, _, last_diff)| last_diff.abs() == curr_max_diff)
+        {
+            match last_movement {
+                Some(((_, _), (src, _), diff)) if diff < 0.0 => {
+                    move_to_prev_window(windows, src, selection, None, rng);
+                }
+                Some(((src, _), (_, _), diff)) if diff > 0.0 => {
+                    move_to_next_window(windows, src, selection, None, rng);
+                }
+                _ => {
+                    unreachable!();
+                }
+            }
+            break;
+        }
+        // undoing the last move without changing the overall spread would
+        // swing between the two forever; keep the better balanced of them
+        let swinging = curr_max_diff == last_max_diff
+            && last_movement.is_some_and(|((s1, _), (s2, _), last_diff)| {
+                (s1, s2) == (curr_movement.0 .0, curr_movement.1 .0)
+                    && last_diff.signum() != curr_movement.2.signum()
+            });
+        if swinging
+            && last_movement
+                .is_some_and(|(_, _, last_diff)| curr_movement.2.abs() <= last_diff.abs())
+        {
+            break;
+        }
+        match curr_movement {
+            ((src, _), (_, _), diff) if diff < 0.0 => {
+                move_to_next_window(windows, src, selection, cap, rng);
+            }
+            ((_, _), (src, _), diff) if diff > 0.0 => {
+                move_to_prev_window(windows, src, selection, cap, rng);
+            }
+            _ => {
+                break;
+            }
+        }
+        tie_breaks.extend(tie_break);
+        if swinging {
+            break;
+        }
+        last_movement.replace(curr_movement);
+        last_max_diff = curr_max_diff;
+    }
+    (tie_breaks, thresholds)
+}
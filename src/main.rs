@@ -1,281 +1,1224 @@
-#![feature(iter_map_windows)]
+use std::path::{Path, PathBuf};
 
-use std::cmp::max;
-use std::collections::VecDeque;
-use std::ops::{Add, Div};
+use chrono::{Local, NaiveTime, TimeZone, Utc};
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
 
-use chrono::Duration;
-use rand::prelude::SliceRandom;
-use rand::{thread_rng, Rng};
+use start_list_generator::allocation::{allocation_csv, allocation_html, allocation_text};
+use start_list_generator::announcer::{announcer_script, commentary_sheet, AnnouncerOptions};
+use start_list_generator::balance::balance_counts;
+use start_list_generator::cards::{start_cards_html, CardOptions};
+use start_list_generator::compare::compare_configs;
+use start_list_generator::config::ConstraintKind;
+use start_list_generator::config::EventConfig;
+use start_list_generator::config::OffsetPattern;
+use start_list_generator::constraints::{
+    Constraint, Constraints, FinalStartList, MinSpacing, StartList,
+};
+use start_list_generator::demo::{demo_windows, example_windows};
+use start_list_generator::duty::duty_conflicts;
+use start_list_generator::filter::Filter;
+use start_list_generator::import::{ImportOptions, ImporterRegistry};
+use start_list_generator::input::{
+    parse_event, start_times_from_csv, startlist_from_csv, EventInput,
+};
+use start_list_generator::output::{
+    export_csv_with_finish, export_draw, export_final_csv, export_final_html, export_final_iof_xml,
+    export_html, export_iof_xml, export_witness, verification_code,
+};
+use start_list_generator::provenance::{Embedding, Provenance};
+use start_list_generator::sync::sync_with_rng;
+use start_list_generator::timefmt::{
+    format_clock, format_offset, offset_of, parse_offset, ClockStyle, Resolution,
+};
+use start_list_generator::transport::transport_report;
+use start_list_generator::{
+    category_alternation, club_spacing_violations, exclusive_radius_violations,
+    reassign_offsets_relaxed_with_rng, reassign_offsets_with_rng, spacing_violations,
+    tapered_starts, try_generate_draw_with_rng, window_clock_ranges, CompetitorWithOffset, Minutes,
+    SavedDraw, Window,
+};
 
-type Minutes = isize;
-struct Competitor {
-    origin: isize, // positive->top, negative->bottom, zero->current
-    name: String,
-}
+use crate::cli::{
+    parse_ab_test_args, parse_args, parse_balance_args, parse_provenance_args, parse_retime_args,
+    parse_sync_args, parse_verify_args, version, Emit, OutputFormat, Render, AB_TEST_USAGE,
+    BALANCE_USAGE, PROVENANCE_USAGE, RETIME_USAGE, SYNC_USAGE, USAGE, VERIFY_USAGE,
+};
+
+mod cli;
 
-struct CompetitorWithOffset {
-    competitor: Competitor,
-    offset: Minutes,
+// reads the event file and the entries CSV it names, exiting on any error
+fn load_input(path: &str) -> EventInput {
+    let read = |path: &Path| {
+        std::fs::read_to_string(path).unwrap_or_else(|error| {
+            eprintln!("cannot read {}: {}", path.display(), error);
+            std::process::exit(2);
+        })
+    };
+    let mut event = parse_event(&read(Path::new(path))).unwrap_or_else(|error| {
+        eprintln!("{}: {}", path, error);
+        std::process::exit(2);
+    });
+    if let Some(entries) = entries_path(path, &event) {
+        if let Err(error) = event.add_entries_csv(&read(&entries)) {
+            eprintln!("{}: {}", entries.display(), error);
+            std::process::exit(2);
+        }
+    }
+    event
 }
 
-struct Window {
-    duration: Minutes,
-    competitors: VecDeque<Competitor>,
+// the entries CSV an event file names, relative to the file
+fn entries_path(path: &str, event: &EventInput) -> Option<PathBuf> {
+    let entries = event.entries.as_ref()?;
+    Some(
+        Path::new(path)
+            .parent()
+            .unwrap_or(Path::new(""))
+            .join(entries),
+    )
 }
 
-impl Window {
-    fn calculate_spacing(&self) -> f64 {
-        if self.competitors.len() == 0 {
-            return self.duration as f64;
-        }
+// the event file at `path` and the entries CSV it names
+fn event_files(path: &str, event: &EventInput) -> Vec<PathBuf> {
+    [PathBuf::from(path)]
+        .into_iter()
+        .chain(entries_path(path, event))
+        .collect()
+}
 
-        (self.duration as f64).div(self.competitors.len() as f64)
+// the provenance of a run's artifacts, hashing the files it read; a
+// no-entropy build dates them only by SOURCE_DATE_EPOCH, so two runs with
+// the same seed still write the same bytes
+fn run_provenance(seed: Option<u64>, read: &[PathBuf], full: bool) -> Provenance {
+    let contents: Vec<Vec<u8>> = read
+        .iter()
+        .filter_map(|path| std::fs::read(path).ok())
+        .collect();
+    let scenario: Vec<&[u8]> = contents.iter().map(Vec::as_slice).collect();
+    let mut provenance = Provenance::new(seed, &scenario);
+    provenance.generated_at = match std::env::var("SOURCE_DATE_EPOCH") {
+        Ok(epoch) => epoch
+            .trim()
+            .parse()
+            .ok()
+            .and_then(|seconds| Utc.timestamp_opt(seconds, 0).single()),
+        Err(_) if cfg!(feature = "no-entropy") => None,
+        Err(_) => Some(Utc::now()),
+    };
+    if full {
+        provenance.host = std::env::var("HOSTNAME")
+            .ok()
+            .or_else(|| std::fs::read_to_string("/etc/hostname").ok())
+            .map(|host| host.trim().to_string())
+            .filter(|host| !host.is_empty());
+        provenance.user = std::env::var("USER")
+            .or_else(|_| std::env::var("USERNAME"))
+            .ok();
     }
+    provenance
 }
 
-fn generate_startlist(
-    mut windows: Vec<Window>,
-    spacing_threshold: Minutes,
-    min_spacing: Minutes,
-) -> Vec<CompetitorWithOffset> {
-    let mut competitors_count: isize = 0;
+// a run without --seed draws from the OS, unless this build has no entropy
+// source to draw from
+fn unseeded_rng() -> StdRng {
+    #[cfg(not(feature = "no-entropy"))]
+    return StdRng::from_entropy();
+    #[cfg(feature = "no-entropy")]
+    {
+        eprintln!("this build has no entropy source; pass --seed N");
+        std::process::exit(2);
+    }
+}
 
-    for window in windows.iter_mut() {
-        window
-            .competitors
-            .make_contiguous()
-            .shuffle(&mut thread_rng());
-        competitors_count += window.competitors.len() as isize;
+fn balance_main(args: impl IntoIterator<Item = String>) {
+    let options = match parse_balance_args(args) {
+        Ok(options) => options,
+        Err(message) => {
+            eprintln!("{}\n{}", message, BALANCE_USAGE);
+            std::process::exit(2);
+        }
+    };
+    let result = match balance_counts(
+        &options.counts,
+        &options.durations,
+        options.spacing_threshold,
+    ) {
+        Ok(result) => result,
+        Err(error) => {
+            eprintln!("{}", error);
+            std::process::exit(2);
+        }
+    };
+    for i in 0..result.counts_before.len() {
+        println!(
+            "Window {}: {} -> {} competitors, spacing {:.2} -> {:.2}",
+            i + 1,
+            result.counts_before[i],
+            result.counts_after[i],
+            result.spacings_before[i],
+            result.spacings_after[i]
+        );
     }
-    if competitors_count <= 0 {
-        return vec![];
+    for (i, &moves) in result.boundary_moves.iter().enumerate() {
+        let direction = if moves < 0 { "back" } else { "forward" };
+        println!(
+            "Boundary {}-{}: {} moved {}",
+            i + 1,
+            i + 2,
+            moves.abs(),
+            direction
+        );
     }
-
-    stabilize_windows(&mut windows, spacing_threshold);
-    smart_offset_assignments(windows, spacing_threshold, min_spacing, competitors_count)
 }
 
-fn smart_offset_assignments(
-    windows: Vec<Window>,
-    spacing_threshold: Minutes,
-    min_spacing: Minutes,
-    competitors_count: isize,
-) -> Vec<CompetitorWithOffset> {
-    let mut competitors = Vec::with_capacity(competitors_count as usize);
-    let mut curr_start = 0;
-    let mut windows_curr_start = 0;
-    for mut window in windows.into_iter() {
-        if window.competitors.len() as i32 != 0 {
-            let mut has_bottom = false;
-            while window.competitors.len() > 0 {
-                if window.competitors[0].origin < 0 {
-                    has_bottom = true;
-                    competitors.push(CompetitorWithOffset {
-                        competitor: window.competitors.pop_front().unwrap(),
-                        offset: curr_start,
-                    });
-                    curr_start += spacing_threshold;
-                } else {
-                    break;
-                }
-            }
-            if has_bottom {
-                curr_start -= spacing_threshold;
-            }
+// the config an event file or a saved draw records, the given spacings
+// overriding its own
+fn draw_config(
+    draw: &EventInput,
+    min_spacing: Option<Minutes>,
+    spacing_threshold: Option<Minutes>,
+) -> EventConfig {
+    let start_time = draw
+        .start_time
+        .unwrap_or_else(|| NaiveTime::from_hms_opt(9, 0, 0).unwrap());
+    let unit = draw.time_unit.unwrap_or_default();
+    let mut builder = EventConfig::builder()
+        .spacing_threshold(spacing_threshold.or(draw.spacing_threshold).unwrap_or(3))
+        .min_spacing(min_spacing.or(draw.min_spacing).unwrap_or(2))
+        .start_time(start_time)
+        .time_unit(unit);
+    if let Some(spacing) = draw.same_club_min_spacing {
+        builder = builder.same_club_min_spacing(spacing);
+    }
+    if let Some(cap) = draw.max_per_club_per_window {
+        builder = builder.max_per_club_per_window(cap);
+    }
+    for (club, from, to) in &draw.duty_shifts {
+        builder = builder.duty_shift(
+            club,
+            offset_of(start_time, *from, unit),
+            offset_of(start_time, *to, unit),
+        );
+    }
+    if let Some(call_up) = draw.call_up {
+        builder = builder.call_up(call_up);
+    }
+    for estimate in &draw.finish_estimates {
+        builder =
+            builder.finish_estimate(&estimate.class, estimate.winning_time, estimate.spread_pct);
+    }
+    builder.build().unwrap_or_else(|error| {
+        eprintln!("{}", error);
+        std::process::exit(2);
+    })
+}
 
-            let mut rev_curr_start = windows_curr_start + window.duration - 1;
-            let mut top_competitors = Vec::with_capacity(window.competitors.len());
-            while window.competitors.len() > 0 {
-                if window.competitors[window.competitors.len() - 1].origin > 0 {
-                    top_competitors.push(CompetitorWithOffset {
-                        competitor: window.competitors.pop_back().unwrap(),
-                        offset: rev_curr_start,
-                    });
-                    rev_curr_start -= spacing_threshold;
-                } else {
-                    break;
+// draws the same entries with two event files' settings and compares them
+fn ab_test_main(args: impl IntoIterator<Item = String>) {
+    let options = match parse_ab_test_args(args) {
+        Ok(options) => options,
+        Err(message) => {
+            eprintln!("{}\n{}", message, AB_TEST_USAGE);
+            std::process::exit(2);
+        }
+    };
+    let (a, b) = (load_input(&options.a), load_input(&options.b));
+    let mut read = event_files(&options.a, &a);
+    read.extend(event_files(&options.b, &b));
+    read.extend(options.entries.iter().map(PathBuf::from));
+    let (config_a, config_b) = (draw_config(&a, None, None), draw_config(&b, None, None));
+    let entries = match &options.entries {
+        Some(path) => ImporterRegistry::default()
+            .import_path(Path::new(path), &a.import_options())
+            .and_then(|imported| {
+                for diagnostic in &imported.diagnostics {
+                    eprintln!("warning: {}: {}", path, diagnostic);
                 }
-            }
-
-            let mut remaining_competitors = window.competitors.len() as isize;
-
-            if remaining_competitors != 0 {
-                let remaining_space = rev_curr_start - curr_start;
-                let (spacing, mut remainder) = (
-                    remaining_space / (remaining_competitors),
-                    remaining_space % (remaining_competitors),
-                );
+                imported.into_windows(Some(a.windows.clone()))
+            })
+            .unwrap_or_else(|error| {
+                eprintln!("{}: {}", path, error);
+                std::process::exit(2);
+            }),
+        None => a.windows,
+    };
+    let seed = options.seed.unwrap_or_else(|| unseeded_rng().gen());
+    let report = compare_configs(&entries, &config_a, &config_b, seed).unwrap_or_else(|error| {
+        eprintln!("{}", error);
+        std::process::exit(2);
+    });
+    let provenance = run_provenance(Some(seed), &read, false);
+    if options.json {
+        print!("{}", provenance.embed(&report.to_json(), Embedding::Json));
+        return;
+    }
+    print!("{}", provenance.embed("", Embedding::Comments));
+    let style = ClockStyle {
+        unit: config_a.time_unit,
+        ..ClockStyle::default()
+    };
+    let clock = |config: &EventConfig, offset: Option<Minutes>| {
+        offset.map_or("-".to_string(), |offset| {
+            format_clock(config.start_time, offset, style)
+        })
+    };
+    let or_dash = |value: Option<String>| value.unwrap_or_else(|| "-".to_string());
+    println!("A: {}\nB: {}\nseed {}", options.a, options.b, report.seed);
+    println!("{:<28}{:>12}{:>12}", "", "A", "B");
+    let rows = [
+        (
+            "starts",
+            report.a.starts.to_string(),
+            report.b.starts.to_string(),
+        ),
+        (
+            "last start",
+            clock(&config_a, report.a.last_start),
+            clock(&config_b, report.b.last_start),
+        ),
+        (
+            "shortest gap",
+            or_dash(report.a.min_gap.map(|gap| gap.to_string())),
+            or_dash(report.b.min_gap.map(|gap| gap.to_string())),
+        ),
+        (
+            "mean gap",
+            or_dash(report.a.mean_gap.map(|gap| format!("{:.2}", gap))),
+            or_dash(report.b.mean_gap.map(|gap| format!("{:.2}", gap))),
+        ),
+        (
+            "longest gap",
+            or_dash(report.a.max_gap.map(|gap| gap.to_string())),
+            or_dash(report.b.max_gap.map(|gap| gap.to_string())),
+        ),
+        (
+            "moved out of their window",
+            report.a.moved.to_string(),
+            report.b.moved.to_string(),
+        ),
+        (
+            "below min_spacing",
+            report.a.spacing_violations.to_string(),
+            report.b.spacing_violations.to_string(),
+        ),
+        (
+            "clubmates too close",
+            report.a.club_spacing_violations.to_string(),
+            report.b.club_spacing_violations.to_string(),
+        ),
+        (
+            "during a duty shift",
+            report.a.duty_conflicts.to_string(),
+            report.b.duty_conflicts.to_string(),
+        ),
+        (
+            "in an exclusive radius",
+            report.a.exclusive_radius_violations.to_string(),
+            report.b.exclusive_radius_violations.to_string(),
+        ),
+    ];
+    for (label, a, b) in rows {
+        println!("{:<28}{:>12}{:>12}", label, a, b);
+    }
+    let changed: Vec<_> = report.changed().collect();
+    println!(
+        "{} of {} competitors start at another time",
+        changed.len(),
+        report.offsets.len()
+    );
+    for change in changed {
+        println!(
+            "  {}: {} -> {}",
+            change.name,
+            clock(&config_a, Some(change.a)),
+            clock(&config_b, Some(change.b))
+        );
+    }
+}
 
-                let mut rng = thread_rng();
-                let mut first_in_window = !has_bottom;
-                for comp in window.competitors {
-                    if comp.origin == 0 {
-                        if !first_in_window {
-                            if spacing >= min_spacing {
-                                if rng.gen_bool(remainder as f64 / remaining_competitors as f64) {
-                                    curr_start += 1;
-                                    remainder -= 1;
-                                }
-                                curr_start += spacing;
-                            } else {
-                                curr_start += min_spacing;
-                            }
-                        } else {
-                            first_in_window = false;
-                        }
-                        competitors.push(CompetitorWithOffset {
-                            competitor: comp,
-                            offset: curr_start,
-                        });
-                        remaining_competitors -= 1;
-                    } else {
-                        break;
-                    }
-                }
+// applies an updated entries CSV to a draw saved with --save-draw
+fn sync_main(args: impl IntoIterator<Item = String>) {
+    let options = match parse_sync_args(args) {
+        Ok(options) => options,
+        Err(message) => {
+            eprintln!("{}\n{}", message, SYNC_USAGE);
+            std::process::exit(2);
+        }
+    };
+    let draw = load_input(&options.draw);
+    let mut read = event_files(&options.draw, &draw);
+    read.push(PathBuf::from(&options.entries));
+    let provenance = run_provenance(options.seed, &read, false);
+    let config = draw_config(&draw, None, None);
+    let imported = ImporterRegistry::default()
+        .import_path(Path::new(&options.entries), &draw.import_options())
+        .unwrap_or_else(|error| {
+            eprintln!("{}: {}", options.entries, error);
+            std::process::exit(2);
+        });
+    if imported.windows.is_some() {
+        eprintln!(
+            "{}: expected a window,name[,club] CSV of entries for the draw's windows",
+            options.entries
+        );
+        std::process::exit(2);
+    }
+    // a row left out would read as a withdrawal
+    if !imported.diagnostics.is_empty() {
+        for diagnostic in &imported.diagnostics {
+            eprintln!("{}: {}", options.entries, diagnostic);
+        }
+        std::process::exit(2);
+    }
+    let mut saved = SavedDraw {
+        windows: draw.windows,
+        witnesses: Vec::new(),
+        tie_breaks: Vec::new(),
+        displacement_selection: None,
+        thresholds: Vec::new(),
+        diagnostics: Vec::new(),
+        revision: draw.revision,
+        log: draw.log,
+    };
+    let mut rng = match options.seed {
+        Some(seed) => StdRng::seed_from_u64(seed),
+        None => unseeded_rng(),
+    };
+    match sync_with_rng(
+        &mut saved,
+        &imported.competitors,
+        options.policy,
+        &config,
+        &mut rng,
+    ) {
+        Ok(operations) if operations.is_empty() => println!("the draw is up to date"),
+        Ok(operations) => {
+            println!("revision {}:", saved.revision);
+            for operation in &operations {
+                println!("  {}", operation);
             }
-
-            curr_start = if rev_curr_start == (windows_curr_start + window.duration - 1) {
-                max(
-                    curr_start + min_spacing,
-                    windows_curr_start + window.duration,
-                )
-            } else {
-                windows_curr_start + window.duration - 1 + spacing_threshold
-            };
-
-            competitors.extend(top_competitors.into_iter().rev());
+            write_file(
+                options.output.as_deref().unwrap_or(&options.draw),
+                &provenance.embed(&export_draw(&saved, &config), Embedding::Comments),
+            );
+        }
+        Err(error) => {
+            eprintln!("{}", error);
+            std::process::exit(2);
         }
-        windows_curr_start += window.duration;
     }
-    competitors
 }
 
-fn move_to_prev_window(windows: &mut Vec<Window>, i: usize) {
-    let mut popped_competitor = windows[i].competitors.pop_front().unwrap();
-    popped_competitor.origin += 1;
-    windows[i - 1].competitors.push_back(popped_competitor);
+// prints the provenance an artifact of this tool carries
+fn provenance_main(args: impl IntoIterator<Item = String>) {
+    let path = match parse_provenance_args(args) {
+        Ok(path) => path,
+        Err(message) => {
+            eprintln!("{}\n{}", message, PROVENANCE_USAGE);
+            std::process::exit(2);
+        }
+    };
+    let text = std::fs::read_to_string(&path).unwrap_or_else(|error| {
+        eprintln!("cannot read {}: {}", path, error);
+        std::process::exit(2);
+    });
+    match Provenance::extract(&text) {
+        Ok(provenance) => print!("{}", provenance),
+        Err(error) => {
+            eprintln!("{}: {}", path, error);
+            std::process::exit(1);
+        }
+    }
 }
 
-fn move_to_next_window(windows: &mut Vec<Window>, i: usize) {
-    let mut popped_competitor = windows[i].competitors.pop_back().unwrap();
-    popped_competitor.origin -= 1;
-    windows[i + 1].competitors.push_front(popped_competitor);
+// checks a start list against the constraints a saved draw records
+fn verify_main(args: impl IntoIterator<Item = String>) {
+    let options = match parse_verify_args(args) {
+        Ok(options) => options,
+        Err(message) => {
+            eprintln!("{}\n{}", message, VERIFY_USAGE);
+            std::process::exit(2);
+        }
+    };
+    let draw = load_input(&options.draw);
+    // a draw saved before the section existed still has its windows to go by
+    let constraints = draw
+        .constraints
+        .clone()
+        .unwrap_or_else(|| Constraints::of_draw(&draw.windows));
+    let text = std::fs::read_to_string(&options.list).unwrap_or_else(|error| {
+        eprintln!("cannot read {}: {}", options.list, error);
+        std::process::exit(2);
+    });
+    let list =
+        startlist_from_csv(&text, draw.time_unit.unwrap_or_default()).unwrap_or_else(|error| {
+            eprintln!("{}: {}", options.list, error);
+            std::process::exit(2);
+        });
+    let violations = constraints.check(&list);
+    if violations.is_empty() {
+        println!("all {} constraints hold", constraints.all().len());
+        return;
+    }
+    for violation in &violations {
+        println!("{}", violation);
+    }
+    std::process::exit(1);
 }
 
-fn calculate_max_diff(windows: &Vec<Window>) -> f64 {
-    let iter = windows.iter().map(|w| w.calculate_spacing());
-    iter.clone()
-        .max_by(|a, b| a.partial_cmp(b).unwrap())
-        .unwrap()
-        - iter.min_by(|a, b| a.partial_cmp(b).unwrap()).unwrap()
+// re-times a draw saved with --save-draw, keeping its order of starts
+fn retime_main(args: impl IntoIterator<Item = String>) {
+    let options = match parse_retime_args(args) {
+        Ok(options) => options,
+        Err(message) => {
+            eprintln!("{}\n{}", message, RETIME_USAGE);
+            std::process::exit(2);
+        }
+    };
+    let draw = load_input(&options.draw);
+    let provenance = run_provenance(options.seed, &event_files(&options.draw, &draw), false);
+    let config = draw_config(&draw, options.min_spacing, options.spacing_threshold);
+    let window_clocks = window_headers(&draw.windows, &config);
+    let saved = SavedDraw {
+        windows: draw.windows,
+        witnesses: Vec::new(),
+        tie_breaks: Vec::new(),
+        displacement_selection: None,
+        thresholds: Vec::new(),
+        diagnostics: Vec::new(),
+        revision: draw.revision,
+        log: draw.log,
+    };
+    let mut rng = match options.seed {
+        Some(seed) => StdRng::seed_from_u64(seed),
+        None => unseeded_rng(),
+    };
+    match reassign_offsets_with_rng(&saved, &config, &mut rng) {
+        Ok(result) => print!(
+            "{}",
+            provenance.embed(
+                &text_listing(&result, &window_clocks, &[], &config, None),
+                Embedding::Comments
+            )
+        ),
+        Err(error) => {
+            eprintln!("{}", error);
+            std::process::exit(2);
+        }
+    }
 }
 
-fn stabilize_windows(windows: &mut Vec<Window>, spacing_threshold: Minutes) {
-    if windows.len() < 2 {
+fn main() {
+    let mut args = std::env::args().skip(1).peekable();
+    if args.next_if(|arg| arg == "--version").is_some() {
+        println!("{}", version());
         return;
     }
-
-    let mut last_movement = None::<((usize, f64), (usize, f64), f64)>;
-    let mut last_max_diff = f64::MAX;
-    loop {
-        let diffs = (0..windows.len())
-            .map(|i| (i, windows[i].calculate_spacing()))
-            .map_windows(|[s1, s2]| (s1.clone(), s2.clone(), s1.1 - s2.1))
-            .filter(|(s1, s2, _)| {
-                s1.1 <= spacing_threshold as f64 || s2.1 <= spacing_threshold as f64
-            });
-        let curr_movement = diffs.max_by(|d1, d2| d1.2.abs().partial_cmp(&d2.2.abs()).unwrap());
-        if curr_movement.is_none() {
-            break;
-        }
-        let curr_movement = curr_movement.unwrap();
-        let curr_max_diff = calculate_max_diff(windows);
-        if (curr_max_diff > last_max_diff)
-            || last_movement.is_some_and(|(_, _, last_diff)| last_diff.abs() == curr_max_diff)
+    if args.next_if(|arg| arg == "balance").is_some() {
+        balance_main(args);
+        return;
+    }
+    if args.next_if(|arg| arg == "retime").is_some() {
+        retime_main(args);
+        return;
+    }
+    if args.next_if(|arg| arg == "verify").is_some() {
+        verify_main(args);
+        return;
+    }
+    if args.next_if(|arg| arg == "ab-test").is_some() {
+        ab_test_main(args);
+        return;
+    }
+    if args.next_if(|arg| arg == "sync").is_some() {
+        sync_main(args);
+        return;
+    }
+    if args.next_if(|arg| arg == "provenance").is_some() {
+        provenance_main(args);
+        return;
+    }
+    let mut options = match parse_args(args) {
+        Ok(options) => options,
+        Err(message) => {
+            eprintln!("{}\n{}", message, USAGE);
+            std::process::exit(2);
+        }
+    };
+    let input = options.input.as_deref().map(load_input);
+    // what the provenance hashes, read again once the seed is known
+    let mut read = match (&options.input, &input) {
+        (Some(path), Some(input)) => event_files(path, input),
+        _ => Vec::new(),
+    };
+    read.extend(options.entries.iter().map(PathBuf::from));
+    let start_time = input
+        .as_ref()
+        .and_then(|input| input.start_time)
+        .unwrap_or_else(|| NaiveTime::from_hms_opt(9, 0, 0).unwrap());
+    let unit = options
+        .time_unit
+        .or_else(|| input.as_ref().and_then(|i| i.time_unit))
+        .unwrap_or_default();
+    let mut builder = EventConfig::builder()
+        .time_unit(unit)
+        .spacing_threshold(
+            options
+                .spacing_threshold
+                .or_else(|| input.as_ref().and_then(|i| i.spacing_threshold))
+                .unwrap_or(3),
+        )
+        .min_spacing(
+            options
+                .min_spacing
+                .or_else(|| input.as_ref().and_then(|i| i.min_spacing))
+                .unwrap_or(2),
+        )
+        .start_time(start_time);
+    if options.alternate_categories {
+        builder = builder.alternate_categories(true);
+    }
+    if let Some(spacing) = options
+        .same_club_min_spacing
+        .or_else(|| input.as_ref().and_then(|i| i.same_club_min_spacing))
+    {
+        builder = builder.same_club_min_spacing(spacing);
+    }
+    if let Some(cap) = options
+        .max_per_club_per_window
+        .or_else(|| input.as_ref().and_then(|i| i.max_per_club_per_window))
+    {
+        builder = builder.max_per_club_per_window(cap);
+    }
+    if let Some(divisor) = options.spacing_divisor {
+        builder = builder.min_spacing_policy(move |expected_duration| expected_duration / divisor);
+    }
+    if let Some(value) = &options.first_start_offset {
+        let first_start_offset = parse_offset(value, unit).unwrap_or_else(|error| {
+            eprintln!("{}", error);
+            std::process::exit(2);
+        });
+        builder = builder.first_start_offset(first_start_offset);
+    }
+    if options.no_shuffle {
+        builder = builder.shuffle(false);
+    }
+    if options.reject_empty {
+        builder = builder.empty_event_is_error(true);
+    }
+    if let Some(threshold) = options.shuffle_alarm_threshold {
+        builder = builder.shuffle_alarm_threshold(threshold);
+    }
+    if let Some(selection) = options.displacement_selection {
+        builder = builder.displacement_selection(selection);
+    }
+    if options.adaptive_threshold {
+        builder = builder.adaptive_threshold(true);
+    }
+    if options.random_tie_break {
+        builder = builder.random_tie_break(true);
+    }
+    if let Some(cap) = options.rolling_cap {
+        builder = builder.rolling_cap(cap);
+    }
+    if let Some(taper) = options.taper {
+        builder = builder.taper(taper.zone, taper.factor);
+    }
+    for (from, to) in &options.blocked {
+        builder = builder.blocked_interval(
+            offset_of(start_time, *from, unit),
+            offset_of(start_time, *to, unit),
+        );
+    }
+    if !options.relaxation_order.is_empty() {
+        builder = builder.relaxation_order(options.relaxation_order.clone());
+    }
+    if let Some(pattern) = options.allowed_offsets.clone() {
+        builder = builder.allowed_offsets(pattern);
+    }
+    if let Some(path) = &options.avoid {
+        let text = std::fs::read_to_string(path).unwrap_or_else(|error| {
+            eprintln!("cannot read {}: {}", path, error);
+            std::process::exit(2);
+        });
+        let start_times = start_times_from_csv(&text).unwrap_or_else(|error| {
+            eprintln!("{}: {}", path, error);
+            std::process::exit(2);
+        });
+        builder = builder.allowed_offsets(OffsetPattern::avoiding(
+            start_times
+                .into_iter()
+                .map(|clock| offset_of(start_time, clock, unit)),
+            options.clearance,
+        ));
+    }
+    for (name, window) in &options.sweepers {
+        builder = builder.sweeper(name, window - 1);
+    }
+    if options.require_sweeper {
+        builder = builder.require_sweeper(true);
+    }
+    for (departure, travel) in &options.shuttles {
+        builder = builder.shuttle(offset_of(start_time, *departure, unit), *travel);
+    }
+    if !options.shuttles.is_empty() {
+        builder = builder
+            .transport_margin(options.shuttle_margin)
+            .repair_transport(options.repair_shuttles);
+    }
+    for (name, clock) in &options.ceremonial_starts {
+        builder = builder.ceremonial_start(name, offset_of(start_time, *clock, unit));
+    }
+    if let Some(input) = &input {
+        for (club, from, to) in &input.duty_shifts {
+            builder = builder.duty_shift(
+                club,
+                offset_of(start_time, *from, unit),
+                offset_of(start_time, *to, unit),
+            );
+        }
+        if let Some(call_up) = input.call_up {
+            builder = builder.call_up(call_up);
+        }
+        for estimate in &input.finish_estimates {
+            builder = builder.finish_estimate(
+                &estimate.class,
+                estimate.winning_time,
+                estimate.spread_pct,
+            );
+        }
+    }
+    builder = builder.repair_duty_shifts(options.repair_duty_shifts);
+    let config = match builder.build() {
+        Ok(config) => config,
+        Err(err) => {
+            eprintln!("{}", err);
+            std::process::exit(2);
+        }
+    };
+    let select = options.select.as_deref().map(|expression| {
+        let filter = Filter::parse(expression, &config).unwrap_or_else(|error| {
+            eprintln!(
+                "invalid --select: {}\n  {}\n  {}^",
+                error,
+                expression,
+                " ".repeat(error.position)
+            );
+            std::process::exit(2);
+        });
+        (filter, options.renumber)
+    });
+    let select = select
+        .as_ref()
+        .map(|(filter, renumber)| (filter, *renumber));
+    let mut time_windows = match (&options.entries, input, options.demo) {
+        (Some(path), input, _) => {
+            let import_options = ImportOptions {
+                max_skipped: options.max_skipped,
+                reuse_windows: options.reuse_windows,
+                ..input
+                    .as_ref()
+                    .map_or_else(ImportOptions::default, EventInput::import_options)
+            };
+            let imported = ImporterRegistry::default()
+                .import_path(Path::new(path), &import_options)
+                .unwrap_or_else(|error| {
+                    eprintln!("{}: {}", path, error);
+                    std::process::exit(2);
+                });
+            for diagnostic in &imported.diagnostics {
+                eprintln!("warning: {}: {}", path, diagnostic);
+            }
+            if imported.windows.is_some() && input.is_some() {
+                eprintln!("{} and --input both provide the windows, pick one", path);
+                std::process::exit(2);
+            }
+            imported
+                .into_windows(input.map(|input| input.windows))
+                .unwrap_or_else(|error| {
+                    eprintln!("{}: {}", path, error);
+                    std::process::exit(2);
+                })
+        }
+        (None, Some(input), _) => input.windows,
+        (None, None, Some(size)) => demo_windows(size),
+        (None, None, None) => example_windows(),
+    };
+    for window in options.locked_windows {
+        match time_windows.get_mut(window.wrapping_sub(1)) {
+            Some(window) => window.locked = true,
+            None => {
+                eprintln!(
+                    "--lock {} does not name one of the {} windows",
+                    window,
+                    time_windows.len()
+                );
+                std::process::exit(2);
+            }
+        }
+    }
+    for (name, clock, radius) in &options.pinned {
+        match time_windows
+            .iter_mut()
+            .flat_map(|window| window.competitors.iter_mut())
+            .find(|competitor| &competitor.name == name)
         {
-            match last_movement {
-                Some(((_, _), (src, _), diff)) if diff < 0.0 => {
-                    move_to_prev_window(windows, src);
-                }
-                Some(((src, _), (_, _), diff)) if diff > 0.0 => {
-                    move_to_next_window(windows, src);
-                }
-                _ => {
-                    unreachable!();
+            Some(competitor) => {
+                competitor.fixed_offset =
+                    Some(offset_of(config.start_time, *clock, config.time_unit));
+                competitor.exclusive_radius = *radius;
+            }
+            None => {
+                eprintln!("--pin {} does not name an entered competitor", name);
+                std::process::exit(2);
+            }
+        }
+    }
+    let event_end: Minutes = time_windows.iter().map(|w| w.duration).sum();
+    let window_clocks = window_headers(&time_windows, &config);
+    let list_style = ClockStyle {
+        resolution: Resolution::Seconds,
+        unit: config.time_unit,
+        ..ClockStyle::default()
+    };
+
+    let min_spacings: Vec<Minutes> = time_windows
+        .iter()
+        .map(|window| window.min_spacing(&config))
+        .collect();
+    // the pinned starts and locked windows, which the draw leaves as they are
+    let constraints = Constraints::of_draw(&time_windows);
+
+    // a witness is only worth having with the seed that reproduces it
+    let seed = options
+        .seed
+        .or_else(|| options.witness.is_some().then(|| unseeded_rng().gen()));
+    let mut rng = match seed {
+        Some(seed) => StdRng::seed_from_u64(seed),
+        None => unseeded_rng(),
+    };
+    let provenance = run_provenance(seed, &read, options.full_provenance);
+    let result = try_generate_draw_with_rng(time_windows, &config, &mut rng).and_then(|saved| {
+        for diagnostic in &saved.diagnostics {
+            eprintln!("warning: {}", diagnostic);
+        }
+        if let Some(path) = &options.witness {
+            write_file(
+                path,
+                &provenance.embed(&export_witness(&saved, seed), Embedding::Comments),
+            );
+        }
+        if let Some(path) = &options.save_draw {
+            write_file(
+                path,
+                &provenance.embed(&export_draw(&saved, &config), Embedding::Comments),
+            );
+        }
+        if let Some(path) = &options.allocation {
+            let (render, embedding): (fn(&SavedDraw, &EventConfig) -> String, _) =
+                match Path::new(path).extension().and_then(|e| e.to_str()) {
+                    Some("csv") => (allocation_csv, Embedding::Comments),
+                    Some("html" | "htm") => (allocation_html, Embedding::Html),
+                    _ => (allocation_text, Embedding::Comments),
+                };
+            write_file(path, &provenance.embed(&render(&saved, &config), embedding));
+        }
+        reassign_offsets_relaxed_with_rng(&saved, &config, &mut rng)
+    });
+    let relaxed = result.unwrap_or_else(|error| {
+        eprintln!("{}", error);
+        std::process::exit(2);
+    });
+    let (result, relaxations) = (relaxed.startlist, relaxed.relaxations_applied);
+    for kind in &relaxations {
+        eprintln!("warning: dropped {} to fit the draw", kind);
+    }
+    if config.taper.is_some() {
+        eprintln!(
+            "taper: {} starts keep the wider spacing",
+            tapered_starts(&result, event_end, &config)
+        );
+    }
+    for (earlier, later) in spacing_violations(&result, &min_spacings) {
+        eprintln!(
+            "warning: {} at {} and {} at {} start closer than min_spacing",
+            earlier.competitor.name,
+            format_clock(config.start_time, earlier.offset, list_style),
+            later.competitor.name,
+            format_clock(config.start_time, later.offset, list_style)
+        );
+    }
+    for competitor_with_offset in duty_conflicts(&result, &config) {
+        eprintln!(
+            "warning: {} of {} is called up or starts at {} during the club's duty shift",
+            competitor_with_offset.competitor.name,
+            competitor_with_offset
+                .competitor
+                .club
+                .as_deref()
+                .unwrap_or_default(),
+            format_clock(config.start_time, competitor_with_offset.offset, list_style)
+        );
+    }
+    for (pinned, other) in exclusive_radius_violations(&result) {
+        eprintln!(
+            "warning: {} at {} starts within the exclusive radius of {} at {}",
+            other.competitor.name,
+            format_clock(config.start_time, other.offset, list_style),
+            pinned.competitor.name,
+            format_clock(config.start_time, pinned.offset, list_style)
+        );
+    }
+    for (earlier, later) in club_spacing_violations(&result, config.same_club_min_spacing) {
+        eprintln!(
+            "warning: {} at {} and {} at {} of {} start closer than the same-club spacing",
+            earlier.competitor.name,
+            format_clock(config.start_time, earlier.offset, list_style),
+            later.competitor.name,
+            format_clock(config.start_time, later.offset, list_style),
+            earlier.competitor.club.as_deref().unwrap_or_default()
+        );
+    }
+    let transport = transport_report(&result, &config);
+    for (shuttle, riders) in config.transport.iter().zip(&transport.riders) {
+        eprintln!(
+            "shuttle at {}: {} competitors depend on it",
+            format_clock(config.start_time, shuttle.departure, list_style),
+            riders
+        );
+    }
+    for stranded in &transport.stranded {
+        eprintln!(
+            "warning: {} at {} starts too soon after every shuttle",
+            stranded.competitor.name,
+            format_clock(config.start_time, stranded.offset, list_style)
+        );
+    }
+    if let Some(last) = result.iter().map(|c| c.offset).max() {
+        if last >= event_end {
+            eprintln!(
+                "warning: last start at {} overruns the final window by {}",
+                format_clock(config.start_time, last, list_style),
+                format_offset(last - event_end + 1, config.time_unit, Resolution::Minutes)
+            );
+        }
+    }
+    // a final list is checked before anything is written, a draft only warns
+    let min_spacing = MinSpacing {
+        min_spacings: min_spacings.clone(),
+    };
+    let mut checks: Vec<&dyn Constraint> = constraints.all();
+    checks.push(&min_spacing);
+    let final_list = if options.final_variant {
+        let list = StartList::new(result.clone());
+        if options.force {
+            let (list, violations) = list.finalize_forced(&checks);
+            for violation in &violations {
+                eprintln!("warning: {} (written anyway with --force)", violation);
+            }
+            Some(list)
+        } else {
+            match list.finalize(&checks) {
+                Ok(list) => Some(list),
+                Err(violations) => {
+                    for violation in &violations {
+                        eprintln!("error: {}", violation);
+                    }
+                    eprintln!(
+                        "refusing to write a final list with {} findings, --force writes it anyway",
+                        violations.len()
+                    );
+                    std::process::exit(1);
                 }
             }
-            break;
         }
-        match curr_movement {
-            ((src, _), (_, _), diff) if diff < 0.0 => {
-                move_to_next_window(windows, src);
+    } else {
+        // min_spacing is warned about above
+        for violation in constraints.check(&result) {
+            eprintln!("warning: {}", violation);
+        }
+        None
+    };
+    let main_render = if let Some(cards) = options.cards.take() {
+        Render::Cards(cards)
+    } else if let Some(announcer) = options.announcer.take() {
+        Render::Announcer(announcer)
+    } else {
+        match options.format {
+            OutputFormat::Text => Render::Text,
+            OutputFormat::Csv => Render::Csv,
+            OutputFormat::IofXml => Render::IofXml { date: options.date },
+            OutputFormat::Html => Render::Html {
+                search: !options.no_js,
+            },
+            OutputFormat::Commentary => Render::Commentary,
+        }
+    };
+    if options.emits.is_empty() {
+        let embedding = main_render.embedding();
+        let rendered = render(
+            main_render,
+            &result,
+            final_list.as_ref(),
+            &window_clocks,
+            &relaxations,
+            &config,
+            select,
+        );
+        let rendered = provenance.embed(&rendered, embedding);
+        match &options.output {
+            Some(path) => write_file(path, &rendered),
+            None => print!("{}", rendered),
+        }
+        return;
+    }
+
+    // every artifact comes from this one list; a failed write does not stop
+    // the others, but all of them are reported
+    let mut emits = options.emits;
+    if let Some(path) = options.output {
+        emits.insert(
+            0,
+            Emit {
+                render: main_render,
+                path,
+            },
+        );
+    }
+    let code = verification_code(&result);
+    let mut failed = false;
+    for emit in emits {
+        let (name, embedding) = (emit.render.name(), emit.render.embedding());
+        let rendered = render(
+            emit.render,
+            &result,
+            final_list.as_ref(),
+            &window_clocks,
+            &relaxations,
+            &config,
+            select,
+        );
+        match std::fs::write(&emit.path, provenance.embed(&rendered, embedding)) {
+            Ok(()) => eprintln!(
+                "wrote {} to {} (verification code {})",
+                name, emit.path, code
+            ),
+            Err(error) => {
+                eprintln!("did not write {} to {}: {}", name, emit.path, error);
+                failed = true;
+            }
+        }
+    }
+    if failed {
+        std::process::exit(2);
+    }
+}
+
+// `select` is a --select filter and whether to renumber what it keeps; with
+// `final_list` the published formats are written from it
+fn render(
+    render: Render,
+    result: &[CompetitorWithOffset],
+    final_list: Option<&FinalStartList>,
+    window_clocks: &[(String, String, bool)],
+    relaxations: &[ConstraintKind],
+    config: &EventConfig,
+    select: Option<(&Filter, bool)>,
+) -> String {
+    if let Some(list) = final_list {
+        match &render {
+            Render::Csv => {
+                return export_final_csv(
+                    list,
+                    config.start_time,
+                    config.time_unit,
+                    &config.finish_estimates,
+                )
             }
-            ((_, _), (src, _), diff) if diff > 0.0 => {
-                move_to_prev_window(windows, src);
+            Render::IofXml { date } => {
+                return export_final_iof_xml(
+                    list,
+                    config.start_time,
+                    date.unwrap_or_else(|| Local::now().date_naive()),
+                    config.time_unit,
+                )
             }
-            _ => {
-                break;
+            Render::Html { search } => {
+                return export_final_html(
+                    list,
+                    config.start_time,
+                    config.time_unit,
+                    *search,
+                    &config.finish_estimates,
+                )
             }
+            _ => {}
         }
-        last_movement.replace(curr_movement);
-        last_max_diff = curr_max_diff;
+    }
+    // the formats without positions of their own number what they are given
+    let selected: Vec<CompetitorWithOffset>;
+    let starts = match select {
+        Some((filter, _)) => {
+            selected = result
+                .iter()
+                .filter(|c| filter.matches(c))
+                .cloned()
+                .collect();
+            &selected
+        }
+        None => result,
+    };
+    match render {
+        Render::Text => text_listing(result, window_clocks, relaxations, config, select),
+        Render::Csv => {
+            let (filter, renumber) =
+                select.map_or((None, false), |(filter, renumber)| (Some(filter), renumber));
+            export_csv_with_finish(
+                result,
+                |c| filter.is_none_or(|filter| filter.matches(c)),
+                renumber,
+                config.start_time,
+                config.time_unit,
+                &config.finish_estimates,
+            )
+        }
+        Render::IofXml { date } => export_iof_xml(
+            starts,
+            config.start_time,
+            date.unwrap_or_else(|| Local::now().date_naive()),
+            config.time_unit,
+        ),
+        Render::Html { search } => export_html(
+            starts,
+            config.start_time,
+            config.time_unit,
+            search,
+            &config.finish_estimates,
+        ),
+        Render::Commentary => commentary_sheet(
+            starts,
+            config.start_time,
+            config.time_unit,
+            &config.finish_estimates,
+        ),
+        Render::Cards(cards) => start_cards_html(
+            starts,
+            config.start_time,
+            &CardOptions {
+                time_unit: config.time_unit,
+                ..cards
+            },
+        ),
+        Render::Announcer(announcer) => announcer_script(
+            starts,
+            config.start_time,
+            &AnnouncerOptions {
+                time_unit: config.time_unit,
+                ..announcer
+            },
+        ),
     }
 }
 
-fn main() {
-    let spacing_threshold = 3;
-    let min_spacing = 2;
+// every window's clock range for the listing's headers, and whether it is
+// locked, as nobody is moved into or out of a locked window
+fn window_headers(windows: &[Window], config: &EventConfig) -> Vec<(String, String, bool)> {
+    window_clock_ranges(windows, config)
+        .into_iter()
+        .zip(windows)
+        .map(|((start, end), window)| (start, end, window.locked))
+        .collect()
+}
 
-    let mut time_windows = vec![];
+fn write_file(path: &str, contents: &str) {
+    if let Err(error) = std::fs::write(path, contents) {
+        eprintln!("cannot write {}: {}", path, error);
+        std::process::exit(2);
+    }
+}
 
-    time_windows.push(Window {
-        duration: 30,
-        competitors: {
-            let mut competitors = VecDeque::new();
-            for i in 0..2 {
-                competitors.push_front(Competitor {
-                    name: format!("1 Competitor {}", i),
-                    origin: 0,
-                })
-            }
-            competitors
-        },
-    });
-    time_windows.push(Window {
-        duration: 30,
-        competitors: {
-            let mut competitors = VecDeque::new();
-            for i in 0..15 {
-                competitors.push_front(Competitor {
-                    name: format!("2 Competitor {}", i),
-                    origin: 0,
-                })
-            }
-            competitors
-        },
-    });
-    time_windows.push(Window {
-        duration: 30,
-        competitors: {
-            let mut competitors = VecDeque::new();
-            for i in 0..4 {
-                competitors.push_front(Competitor {
-                    name: format!("3 Competitor {}", i),
-                    origin: 0,
-                })
+// with `select`, only the starts it keeps are listed, numbered as in the
+// full list unless renumbered; the verification code stays the full list's
+fn text_listing(
+    result: &[CompetitorWithOffset],
+    window_clocks: &[(String, String, bool)],
+    relaxations: &[ConstraintKind],
+    config: &EventConfig,
+    select: Option<(&Filter, bool)>,
+) -> String {
+    // the plain listing has always shown seconds
+    let list_style = ClockStyle {
+        resolution: Resolution::Seconds,
+        unit: config.time_unit,
+        ..ClockStyle::default()
+    };
+    let mut listing = String::new();
+    // above everything else, so nobody publishes a relaxed list unawares
+    if !relaxations.is_empty() {
+        let dropped: Vec<String> = relaxations.iter().map(|kind| kind.to_string()).collect();
+        listing.push_str(&format!(
+            "RELAXED: dropped {} to fit the draw\n",
+            dropped.join(", ")
+        ));
+    }
+    for (i, (start, end, locked)) in window_clocks.iter().enumerate() {
+        listing.push_str(&format!(
+            "Window {}: {}–{}{}\n",
+            i + 1,
+            start,
+            end,
+            if *locked { " (locked)" } else { "" }
+        ));
+    }
+    let listed: Vec<(usize, &CompetitorWithOffset)> = match select {
+        Some((filter, renumber)) => {
+            let kept = result.iter().enumerate().filter(|(_, c)| filter.matches(c));
+            if renumber {
+                kept.map(|(_, c)| c).enumerate().collect()
+            } else {
+                kept.collect()
             }
-            competitors
-        },
-    });
-    let result = generate_startlist(time_windows, spacing_threshold, min_spacing);
-    let start_time = chrono::naive::NaiveTime::from_hms_opt(9, 0, 0).unwrap();
-    for (i, competitor_with_offset) in result.iter().enumerate() {
-        println!(
-            "[{}] Competitor: {}, time: {}",
+        }
+        None => result.iter().enumerate().collect(),
+    };
+    for &(i, competitor_with_offset) in &listed {
+        listing.push_str(&format!(
+            "[{}] Competitor: {}, time: {}{}\n",
             i + 1,
             competitor_with_offset.competitor.name,
-            start_time.add(Duration::minutes(competitor_with_offset.offset as i64))
-        );
+            format_clock(config.start_time, competitor_with_offset.offset, list_style),
+            if competitor_with_offset.competitor.ceremonial {
+                " (ceremonial)"
+            } else if competitor_with_offset.competitor.sweeper {
+                " (sweeper)"
+            } else if competitor_with_offset.competitor.fixed_offset.is_some() {
+                " (pinned)"
+            } else {
+                ""
+            }
+        ));
+    }
+    if config.alternate_categories {
+        let (alternating, pairs) = category_alternation(result);
+        listing.push_str(&format!(
+            "category alternation: {} of {} consecutive starts alternate\n",
+            alternating, pairs
+        ));
+    }
+    if let Some(&(_, last)) = listed.last() {
+        listing.push_str(&format!(
+            "{} competitors, last start: {}\n",
+            listed.len(),
+            format_clock(config.start_time, last.offset, list_style)
+        ));
+    } else {
+        // before entries open the windows above are the whole schedule
+        listing.push_str("0 competitors\n");
     }
+    listing.push_str(&format!(
+        "verification code: {}\n",
+        verification_code(result)
+    ));
+    listing
 }
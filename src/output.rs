@@ -0,0 +1,680 @@
+use std::borrow::Cow;
+
+use chrono::{NaiveDate, NaiveTime};
+
+use crate::cards::escape_html;
+use crate::config::{expected_finish, EventConfig, FinishEstimate};
+use crate::constraints::{Constraint, Constraints, FinalStartList, CONSTRAINTS_VERSION};
+use crate::timefmt::{format_clock, format_datetime, ClockStyle, Resolution, TimeUnit};
+use crate::{Competitor, CompetitorWithOffset, SavedDraw};
+
+/// Writes one row per start in clock order:
+/// `position,name,start_time,offset_minutes,window_index,flag,club,class`,
+/// with the 0-based window index, the start time as HH:MM:SS and the
+/// category as the class. Offsets counted in
+/// seconds go in an `offset_seconds` column instead. Ceremonial starts and
+/// sweepers are flagged `ceremonial` or `sweeper` and have no position, so
+/// positions count the drawn field like the IOF export's bib numbers.
+pub fn export_csv(
+    competitors: &[CompetitorWithOffset],
+    start_time: NaiveTime,
+    unit: TimeUnit,
+) -> String {
+    export_csv_selected(competitors, |_| true, false, start_time, unit)
+}
+
+/// Like [`export_csv`], with only the starts `selected` keeps. Each keeps
+/// the position it has in the full list, so a club's own export still reads
+/// like the published one, unless `renumber` counts positions among the
+/// selected starts instead.
+pub fn export_csv_selected(
+    competitors: &[CompetitorWithOffset],
+    selected: impl Fn(&CompetitorWithOffset) -> bool,
+    renumber: bool,
+    start_time: NaiveTime,
+    unit: TimeUnit,
+) -> String {
+    export_csv_with_finish(competitors, selected, renumber, start_time, unit, &[])
+}
+
+/// Like [`export_csv_selected`], with `expected_finish_from` and
+/// `expected_finish_to` columns at the end when there are `estimates`, as
+/// HH:MM:SS, blank for a start whose class has no estimate.
+pub fn export_csv_with_finish(
+    competitors: &[CompetitorWithOffset],
+    selected: impl Fn(&CompetitorWithOffset) -> bool,
+    renumber: bool,
+    start_time: NaiveTime,
+    unit: TimeUnit,
+    estimates: &[FinishEstimate],
+) -> String {
+    let style = ClockStyle {
+        resolution: Resolution::Seconds,
+        unit,
+        ..ClockStyle::default()
+    };
+    let mut csv = format!(
+        "position,name,start_time,{},window_index,flag,club,class{}\n",
+        offset_column(unit),
+        if estimates.is_empty() {
+            ""
+        } else {
+            ",expected_finish_from,expected_finish_to"
+        }
+    );
+    let mut position = 0;
+    for competitor_with_offset in in_start_order(competitors) {
+        let keep = selected(competitor_with_offset);
+        let flag = start_flag(&competitor_with_offset.competitor);
+        let shown_position = match flag {
+            Some(_) => String::new(),
+            None => {
+                if keep || !renumber {
+                    position += 1;
+                }
+                position.to_string()
+            }
+        };
+        if !keep {
+            continue;
+        }
+        let competitor = &competitor_with_offset.competitor;
+        csv.push_str(&format!(
+            "{},{},{},{},{},{},{},{}",
+            shown_position,
+            csv_field(&competitor.name),
+            format_clock(start_time, competitor_with_offset.offset, style),
+            competitor_with_offset.offset,
+            competitor_with_offset.window,
+            flag.unwrap_or_default(),
+            csv_field(competitor.club.as_deref().unwrap_or_default()),
+            csv_field(competitor.category.as_deref().unwrap_or_default())
+        ));
+        if !estimates.is_empty() {
+            let (from, to) = finish_clocks(estimates, competitor_with_offset, start_time, style);
+            csv.push_str(&format!(",{},{}", from, to));
+        }
+        csv.push('\n');
+    }
+    csv
+}
+
+/// The published CSV of a list [`crate::constraints::StartList::finalize`]
+/// passed: every start, with the expected finish when there are `estimates`.
+pub fn export_final_csv(
+    list: &FinalStartList,
+    start_time: NaiveTime,
+    unit: TimeUnit,
+    estimates: &[FinishEstimate],
+) -> String {
+    export_csv_with_finish(list.starts(), |_| true, false, start_time, unit, estimates)
+}
+
+/// [`export_iof_xml`] of a finalized list.
+pub fn export_final_iof_xml(
+    list: &FinalStartList,
+    start_time: NaiveTime,
+    date: NaiveDate,
+    unit: TimeUnit,
+) -> String {
+    export_iof_xml(list.starts(), start_time, date, unit)
+}
+
+/// [`export_html`] of a finalized list.
+pub fn export_final_html(
+    list: &FinalStartList,
+    start_time: NaiveTime,
+    unit: TimeUnit,
+    search: bool,
+    estimates: &[FinishEstimate],
+) -> String {
+    export_html(list.starts(), start_time, unit, search, estimates)
+}
+
+// the expected finish window as clock times, blank without an estimate
+fn finish_clocks(
+    estimates: &[FinishEstimate],
+    start: &CompetitorWithOffset,
+    start_time: NaiveTime,
+    style: ClockStyle,
+) -> (String, String) {
+    match expected_finish(estimates, start) {
+        Some((from, to)) => (
+            format_clock(start_time, from, style),
+            format_clock(start_time, to, style),
+        ),
+        None => (String::new(), String::new()),
+    }
+}
+
+/// Writes an IOF XML 3.0 `StartList`, one `ClassStart` per category in order
+/// of first start (uncategorised competitors form the class "Open"). IOF
+/// start times are full date-times, so the event `date` is needed as well.
+/// Bib numbers run from 1 in clock order across all classes, matching the
+/// CSV's `position`; a competitor's club becomes their `Organisation`.
+/// Ceremonial starts and sweepers are in no class's results and are left out.
+pub fn export_iof_xml(
+    competitors: &[CompetitorWithOffset],
+    start_time: NaiveTime,
+    date: NaiveDate,
+    unit: TimeUnit,
+) -> String {
+    let mut ordered = in_start_order(competitors);
+    ordered.retain(|c| start_flag(&c.competitor).is_none());
+    let mut classes: Vec<&str> = Vec::new();
+    for competitor_with_offset in &ordered {
+        let class = class_name(competitor_with_offset);
+        if !classes.contains(&class) {
+            classes.push(class);
+        }
+    }
+
+    let mut xml = String::from("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+    xml.push_str(
+        "<StartList xmlns=\"http://www.orienteering.org/datastandard/3.0\" \
+         iofVersion=\"3.0\" creator=\"start-list-generator\">\n",
+    );
+    xml.push_str(&format!(
+        "  <Event>\n    <Id type=\"verification-code\">{}</Id>\n    <Name>Start list</Name>\n  </Event>\n",
+        verification_code(competitors)
+    ));
+    for class in classes {
+        xml.push_str(&format!(
+            "  <ClassStart>\n    <Class>\n      <Name>{}</Name>\n    </Class>\n",
+            escape_html(class)
+        ));
+        for (bib, competitor_with_offset) in ordered
+            .iter()
+            .enumerate()
+            .filter(|(_, c)| class_name(c) == class)
+        {
+            let (given, family) = split_name(&competitor_with_offset.competitor.name);
+            xml.push_str("    <PersonStart>\n      <Person>\n        <Name>\n");
+            xml.push_str(&format!(
+                "          <Family>{}</Family>\n",
+                escape_html(family)
+            ));
+            if let Some(given) = given {
+                xml.push_str(&format!(
+                    "          <Given>{}</Given>\n",
+                    escape_html(given)
+                ));
+            }
+            xml.push_str("        </Name>\n      </Person>\n");
+            if let Some(club) = &competitor_with_offset.competitor.club {
+                xml.push_str(&format!(
+                    "      <Organisation>\n        <Name>{}</Name>\n      </Organisation>\n",
+                    escape_html(club)
+                ));
+            }
+            xml.push_str("      <Start>\n");
+            xml.push_str(&format!(
+                "        <StartTime>{}</StartTime>\n        <BibNumber>{}</BibNumber>\n",
+                format_datetime(date, start_time, competitor_with_offset.offset, unit),
+                bib + 1
+            ));
+            xml.push_str("      </Start>\n    </PersonStart>\n");
+        }
+        xml.push_str("  </ClassStart>\n");
+    }
+    xml.push_str("</StartList>\n");
+    xml
+}
+
+const LIST_STYLE: &str = "\
+body { font-family: sans-serif; margin: 1em; }
+table { border-collapse: collapse; width: 100%; }
+th, td { text-align: left; padding: 0.2em 0.6em; border-bottom: 1px solid #ddd; }
+thead th { position: sticky; top: 0; background: #fff; border-bottom: 2px solid #444; }
+.search { margin-bottom: 0.6em; padding: 0.3em; width: 20em; max-width: 100%; }
+@media print {
+  .search { display: none; }
+  tr[hidden] { display: table-row; }
+  thead { display: table-header-group; }
+}
+";
+
+// fills in the search box; names and clubs are compared without case or
+// diacritics, so "linne" finds "OK Linné"
+const LIST_SCRIPT: &str = r#"(function () {
+  function fold(text) {
+    return text.normalize('NFD').replace(/[\u0300-\u036f]/g, '').toLowerCase();
+  }
+  var rows = Array.prototype.slice.call(document.querySelectorAll('tbody tr'));
+  var search = document.createElement('input');
+  search.type = 'search';
+  search.className = 'search';
+  search.placeholder = 'Search name or club';
+  search.setAttribute('aria-label', 'Search name or club');
+  search.addEventListener('input', function () {
+    var query = fold(search.value.trim());
+    rows.forEach(function (row) {
+      row.hidden = query !== '' && fold(row.getAttribute('data-search')).indexOf(query) < 0;
+    });
+  });
+  var table = document.querySelector('table');
+  table.parentNode.insertBefore(search, table);
+})();
+"#;
+
+/// Renders the start list as a single HTML page to publish: one table row
+/// per start in clock order with the position, start time, name, club and
+/// class, numbered like the CSV, under a header that stays in view while
+/// scrolling, and the expected finish window when there are `estimates`,
+/// blank for a class without one. With `search` an inline script adds a box that filters the
+/// rows by name or club, ignoring case and diacritics; the table itself is
+/// complete without it, so the page reads and prints the same with scripts
+/// off, and `search` false leaves the script out altogether.
+pub fn export_html(
+    competitors: &[CompetitorWithOffset],
+    start_time: NaiveTime,
+    unit: TimeUnit,
+    search: bool,
+    estimates: &[FinishEstimate],
+) -> String {
+    let style = ClockStyle {
+        resolution: Resolution::Seconds,
+        unit,
+        ..ClockStyle::default()
+    };
+    let mut html = format!(
+        "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n\
+         <meta name=\"verification-code\" content=\"{}\">\n<title>Start list</title>\n\
+         <style>\n{}</style>\n</head>\n<body>\n<h1>Start list</h1>\n\
+         <table>\n<thead>\n<tr><th>Pos</th><th>Start</th><th>Name</th><th>Club</th>\
+         <th>Class</th>{}</tr>\n</thead>\n<tbody>\n",
+        verification_code(competitors),
+        LIST_STYLE,
+        if estimates.is_empty() {
+            ""
+        } else {
+            "<th>Expected finish</th>"
+        }
+    );
+    let mut position = 0;
+    for competitor_with_offset in in_start_order(competitors) {
+        let competitor = &competitor_with_offset.competitor;
+        let shown_position = match start_flag(competitor) {
+            Some(_) => String::new(),
+            None => {
+                position += 1;
+                position.to_string()
+            }
+        };
+        let club = competitor.club.as_deref().unwrap_or_default();
+        html.push_str(&format!(
+            "<tr data-search=\"{} {}\"><td>{}</td><td>{}</td><td>{}</td><td>{}</td><td>{}</td>",
+            escape_html(&competitor.name),
+            escape_html(club),
+            shown_position,
+            format_clock(start_time, competitor_with_offset.offset, style),
+            escape_html(&competitor.name),
+            escape_html(club),
+            escape_html(competitor.category.as_deref().unwrap_or_default())
+        ));
+        if !estimates.is_empty() {
+            let (from, to) = finish_clocks(estimates, competitor_with_offset, start_time, style);
+            if from.is_empty() {
+                html.push_str("<td></td>");
+            } else {
+                html.push_str(&format!("<td>{}–{}</td>", from, to));
+            }
+        }
+        html.push_str("</tr>\n");
+    }
+    html.push_str("</tbody>\n</table>\n");
+    if search {
+        html.push_str(&format!("<script>\n{}</script>\n", LIST_SCRIPT));
+    }
+    html.push_str("</body>\n</html>\n");
+    html
+}
+
+/// Writes a saved draw as an event file that `input::parse_event` reads back:
+/// the config's start time, time unit and spacings, then each window's
+/// competitors in start order, with `from_earlier` / `from_later` counting the
+/// ones stabilization moved in at either end. Pinned competitors are listed
+/// apart as `pinned = ["NAME@OFFSET"]`. Every other window setting is kept,
+/// and competitors' clubs and categories as lists beside their names. A draw
+/// updated by `sync::sync` keeps its revision and log. A `[constraints]`
+/// section at the end restates the pinned starts, exclusive radii and locked
+/// windows for tooling that edits the start list, see
+/// [`crate::constraints::Constraints`].
+pub fn export_draw(saved: &SavedDraw, config: &EventConfig) -> String {
+    let mut text = String::from("# a drawn running order, competitors in start order\n");
+    text.push_str(&format!(
+        "start_time = \"{}\"\n",
+        format_clock(config.start_time, 0, ClockStyle::default())
+    ));
+    if config.time_unit == TimeUnit::Seconds {
+        text.push_str("time_unit = \"seconds\"\n");
+    }
+    text.push_str(&format!(
+        "spacing_threshold = {}\nmin_spacing = {}\n",
+        config.spacing_threshold, config.min_spacing
+    ));
+    if saved.revision > 0 {
+        text.push_str(&format!("revision = {}\n", saved.revision));
+    }
+    if !saved.log.is_empty() {
+        text.push_str("log = [\n");
+        for (revision, operation) in &saved.log {
+            text.push_str(&format!(
+                "    {},\n",
+                toml_string(&format!("{}: {}", revision, operation))
+            ));
+        }
+        text.push_str("]\n");
+    }
+    for window in &saved.windows {
+        text.push_str(&format!("\n[[window]]\nduration = {}\n", window.duration));
+        if let Some(min_spacing) = window.min_spacing {
+            text.push_str(&format!("min_spacing = {}\n", min_spacing));
+        }
+        if let Some(spacing_threshold) = window.spacing_threshold {
+            text.push_str(&format!("spacing_threshold = {}\n", spacing_threshold));
+        }
+        if window.locked {
+            text.push_str("locked = true\n");
+        }
+        if window.spacing_multiplier != 1.0 {
+            text.push_str(&format!(
+                "spacing_multiplier = {}\n",
+                window.spacing_multiplier
+            ));
+        }
+        if let Some(expected_duration) = window.expected_duration {
+            text.push_str(&format!("expected_duration = {}\n", expected_duration));
+        }
+        let from_earlier = window.competitors.iter().filter(|c| c.origin < 0).count();
+        let from_later = window.competitors.iter().filter(|c| c.origin > 0).count();
+        if from_earlier > 0 {
+            text.push_str(&format!("from_earlier = {}\n", from_earlier));
+        }
+        if from_later > 0 {
+            text.push_str(&format!("from_later = {}\n", from_later));
+        }
+        text.push_str("competitors = [\n");
+        for competitor in window
+            .competitors
+            .iter()
+            .filter(|c| c.fixed_offset.is_none())
+        {
+            text.push_str(&format!("    {},\n", toml_string(&competitor.name)));
+        }
+        text.push_str("]\n");
+        let pinned: Vec<String> = window
+            .competitors
+            .iter()
+            .filter_map(|c| {
+                let offset = c.fixed_offset?;
+                Some(toml_string(&if c.exclusive_radius > 0 {
+                    format!("{}@{}~{}", c.name, offset, c.exclusive_radius)
+                } else {
+                    format!("{}@{}", c.name, offset)
+                }))
+            })
+            .collect();
+        if !pinned.is_empty() {
+            text.push_str(&format!("pinned = [{}]\n", pinned.join(", ")));
+        }
+        // in the order parse_event reads them: the drawn, then the pinned
+        let listed: Vec<&Competitor> = window
+            .competitors
+            .iter()
+            .filter(|c| c.fixed_offset.is_none())
+            .chain(
+                window
+                    .competitors
+                    .iter()
+                    .filter(|c| c.fixed_offset.is_some()),
+            )
+            .collect();
+        for (key, field) in [
+            (
+                "clubs",
+                (|c: &Competitor| c.club.as_deref()) as fn(&Competitor) -> Option<&str>,
+            ),
+            ("categories", |c| c.category.as_deref()),
+        ] {
+            if listed.iter().any(|c| field(c).is_some()) {
+                let entries: Vec<String> = listed
+                    .iter()
+                    .map(|c| toml_string(field(c).unwrap_or("")))
+                    .collect();
+                text.push_str(&format!("{} = [{}]\n", key, entries.join(", ")));
+            }
+        }
+    }
+    let constraints = Constraints::of_draw(&saved.windows);
+    if !constraints.is_empty() {
+        text.push_str(&format!(
+            "\n[constraints]\nversion = {}\n",
+            CONSTRAINTS_VERSION
+        ));
+        for (key, entries) in [
+            ("fixed_slots", constraint_entries(&constraints.fixed_slots)),
+            (
+                "exclusive_radii",
+                constraint_entries(&constraints.exclusive_radii),
+            ),
+            (
+                "locked_windows",
+                constraint_entries(&constraints.locked_windows),
+            ),
+            (
+                "locked_entries",
+                constraints
+                    .locked_windows
+                    .iter()
+                    .flat_map(|locked| {
+                        locked.names.iter().map(move |name| {
+                            toml_string(&format!("{}:{}", locked.window + 1, name))
+                        })
+                    })
+                    .collect(),
+            ),
+        ] {
+            if !entries.is_empty() {
+                text.push_str(&format!("{} = [{}]\n", key, entries.join(", ")));
+            }
+        }
+    }
+    text
+}
+
+fn constraint_entries(constraints: &[impl Constraint]) -> Vec<String> {
+    constraints
+        .iter()
+        .map(|constraint| toml_string(&constraint.to_string()))
+        .collect()
+}
+
+/// Writes every window's draw witness as two columns side by side, the entry
+/// order and the drawn order, with the 1-based entry position each drawn
+/// competitor came from, under the window's rank correlation with its entry
+/// order when the window is large enough to have one. The seed heads the file
+/// when it is known, followed by who stabilization displaced and the threshold
+/// each of its iterations balanced against, and any stabilization ties the
+/// rng broke close it.
+pub fn export_witness(saved: &SavedDraw, seed: Option<u64>) -> String {
+    let mut text = match seed {
+        Some(seed) => format!("# draw witness, seed {}\n", seed),
+        None => String::from("# draw witness, seed not recorded\n"),
+    };
+    if let Some(selection) = saved.displacement_selection {
+        text.push_str(&format!("displacement selection: {}\n", selection));
+    }
+    if !saved.thresholds.is_empty() {
+        let thresholds: Vec<String> = saved
+            .thresholds
+            .iter()
+            .map(|threshold| format!("{:.2}", threshold))
+            .collect();
+        text.push_str(&format!(
+            "stabilization thresholds: {}\n",
+            thresholds.join(", ")
+        ));
+    }
+    for (i, witness) in saved.witnesses.iter().enumerate() {
+        text.push_str(&format!("\nWindow {}\n", i + 1));
+        if let Some(correlation) = witness.correlation {
+            text.push_str(&format!(
+                "rank correlation with entry order: {:.2}\n",
+                correlation
+            ));
+        }
+        let numbered = |names: &[String]| -> Vec<String> {
+            names
+                .iter()
+                .enumerate()
+                .map(|(j, name)| format!("{}. {}", j + 1, name))
+                .collect()
+        };
+        let (entries, drawn) = (
+            numbered(&witness.entry_order),
+            numbered(&witness.drawn_order),
+        );
+        let width = |column: &[String], title: &str| {
+            column
+                .iter()
+                .map(|cell| cell.chars().count())
+                .chain([title.chars().count()])
+                .max()
+                .unwrap()
+        };
+        let (entry_width, drawn_width) =
+            (width(&entries, "entry order"), width(&drawn, "drawn order"));
+        text.push_str(&format!(
+            "{} | {} | from entry\n",
+            pad("entry order", entry_width),
+            pad("drawn order", drawn_width)
+        ));
+        for ((entry, drawn), from) in entries.iter().zip(&drawn).zip(&witness.permutation) {
+            text.push_str(&format!(
+                "{} | {} | {}\n",
+                pad(entry, entry_width),
+                pad(drawn, drawn_width),
+                from + 1
+            ));
+        }
+    }
+    if !saved.tie_breaks.is_empty() {
+        text.push_str("\nTie-breaks\n");
+    }
+    for tie_break in &saved.tie_breaks {
+        let pair = |w: usize| format!("{}/{}", w + 1, w + 2);
+        let tied: Vec<String> = tie_break.pairs.iter().map(|&w| pair(w)).collect();
+        text.push_str(&format!(
+            "windows {} tied, drew {}\n",
+            tied.join(", "),
+            pair(tie_break.chosen)
+        ));
+    }
+    text
+}
+
+// pads to `width` characters, not bytes, so accented names line up
+fn pad(cell: &str, width: usize) -> String {
+    format!("{}{}", cell, " ".repeat(width - cell.chars().count()))
+}
+
+/// A short fingerprint of who starts when: the same for every artifact
+/// written from one generated list, whatever its format, and different as
+/// soon as a name or an offset differs.
+pub fn verification_code(competitors: &[CompetitorWithOffset]) -> String {
+    // 64-bit FNV-1a, stable across builds and platforms unlike DefaultHasher
+    let mut hash: u64 = 0xcbf2_9ce4_8422_2325;
+    for competitor_with_offset in in_start_order(competitors) {
+        let entry = format!(
+            "{}\t{}\n",
+            competitor_with_offset.competitor.name, competitor_with_offset.offset
+        );
+        for byte in entry.bytes() {
+            hash ^= byte as u64;
+            hash = hash.wrapping_mul(0x0100_0000_01b3);
+        }
+    }
+    format!("{:08X}", hash >> 32)
+}
+
+/// The name of the CSV column holding offsets counted in `unit`.
+pub fn offset_column(unit: TimeUnit) -> &'static str {
+    match unit {
+        TimeUnit::Minutes => "offset_minutes",
+        TimeUnit::Seconds => "offset_seconds",
+    }
+}
+
+fn in_start_order(competitors: &[CompetitorWithOffset]) -> Vec<&CompetitorWithOffset> {
+    let mut ordered: Vec<&CompetitorWithOffset> = competitors.iter().collect();
+    ordered.sort_by_key(|c| c.offset);
+    ordered
+}
+
+/// How a start that is not part of the draw is flagged in the outputs.
+pub fn start_flag(competitor: &Competitor) -> Option<&'static str> {
+    if competitor.ceremonial {
+        Some("ceremonial")
+    } else if competitor.sweeper {
+        Some("sweeper")
+    } else {
+        None
+    }
+}
+
+fn class_name(competitor_with_offset: &CompetitorWithOffset) -> &str {
+    competitor_with_offset
+        .competitor
+        .category
+        .as_deref()
+        .unwrap_or("Open")
+}
+
+// the last word is taken as the family name, as entry lists mostly write
+// "Given Family"
+fn split_name(name: &str) -> (Option<&str>, &str) {
+    match name.trim().rsplit_once(char::is_whitespace) {
+        Some((given, family)) => (Some(given.trim_end()), family),
+        None => (None, name.trim()),
+    }
+}
+
+pub(crate) fn csv_field(text: &str) -> Cow<'_, str> {
+    if text.contains([',', '"', '\n']) {
+        Cow::Owned(format!("\"{}\"", text.replace('"', "\"\"")))
+    } else {
+        Cow::Borrowed(text)
+    }
+}
+
+pub(crate) fn json_string(text: &str) -> String {
+    let mut quoted = String::from('"');
+    for c in text.chars() {
+        match c {
+            '"' => quoted.push_str("\\\""),
+            '\\' => quoted.push_str("\\\\"),
+            '\n' => quoted.push_str("\\n"),
+            '\t' => quoted.push_str("\\t"),
+            c if (c as u32) < 0x20 => quoted.push_str(&format!("\\u{:04x}", c as u32)),
+            _ => quoted.push(c),
+        }
+    }
+    quoted.push('"');
+    quoted
+}
+
+fn toml_string(text: &str) -> String {
+    let mut quoted = String::from('"');
+    for c in text.chars() {
+        match c {
+            '"' => quoted.push_str("\\\""),
+            '\\' => quoted.push_str("\\\\"),
+            '\n' => quoted.push_str("\\n"),
+            '\t' => quoted.push_str("\\t"),
+            _ => quoted.push(c),
+        }
+    }
+    quoted.push('"');
+    quoted
+}
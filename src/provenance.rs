@@ -0,0 +1,321 @@
+use std::fmt;
+
+use chrono::{DateTime, Utc};
+
+use crate::output::json_string;
+
+/// The version of the drawing algorithm: bumped whenever the same entries,
+/// settings and seed may give a different list, so an artifact tells which
+/// draw made it.
+pub const ALGORITHM_VERSION: u32 = 1;
+
+// the namespace of the provenance attributes on an XML artifact's root
+const XML_NAMESPACE: &str = "urn:start-list-generator:provenance";
+
+/// Where an artifact came from, embedded in every file the binary writes so
+/// a list found on a club's web page can be traced back to its draw.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Provenance {
+    pub crate_version: String,
+    pub algorithm_version: u32,
+    pub seed: Option<u64>, // None for a draw from the OS
+    // a hash of the event file and entries read, None when nothing was read
+    pub scenario_hash: Option<String>,
+    pub generated_at: Option<DateTime<Utc>>,
+    // only with --provenance full, as they say who ran the draw where
+    pub host: Option<String>,
+    pub user: Option<String>,
+}
+
+/// How an artifact's format carries the provenance block.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Embedding {
+    // `# provenance.KEY: VALUE` lines at the top, for CSV, text and TOML
+    Comments,
+    // a `provenance` object, the first field of the top-level one
+    Json,
+    // `provenance:KEY` attributes on the root element
+    Xml,
+    // `<meta name="provenance.KEY">` elements in the head
+    Html,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ProvenanceError {
+    // the text has no provenance block, or one without a crate version
+    Missing,
+    Invalid { key: &'static str, value: String },
+}
+
+impl fmt::Display for ProvenanceError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ProvenanceError::Missing => write!(f, "no provenance found"),
+            ProvenanceError::Invalid { key, value } => {
+                write!(f, "invalid provenance {} '{}'", key, value)
+            }
+        }
+    }
+}
+
+impl std::error::Error for ProvenanceError {}
+
+const KEYS: [&str; 7] = [
+    "crate_version",
+    "algorithm_version",
+    "seed",
+    "scenario_hash",
+    "generated_at",
+    "host",
+    "user",
+];
+
+impl Provenance {
+    /// The provenance of a draw from `seed` of the `scenario` files' contents,
+    /// by this build, without a time or an identity.
+    pub fn new(seed: Option<u64>, scenario: &[&[u8]]) -> Provenance {
+        Provenance {
+            crate_version: env!("CARGO_PKG_VERSION").to_string(),
+            algorithm_version: ALGORITHM_VERSION,
+            seed,
+            scenario_hash: (!scenario.is_empty()).then(|| scenario_hash(scenario)),
+            generated_at: None,
+            host: None,
+            user: None,
+        }
+    }
+
+    /// The fields that are set, in a fixed order, as they are embedded.
+    pub fn fields(&self) -> Vec<(&'static str, String)> {
+        let values = [
+            Some(self.crate_version.clone()),
+            Some(self.algorithm_version.to_string()),
+            self.seed.map(|seed| seed.to_string()),
+            self.scenario_hash.clone(),
+            self.generated_at
+                .map(|time| time.format("%Y-%m-%dT%H:%M:%SZ").to_string()),
+            self.host.clone(),
+            self.user.clone(),
+        ];
+        KEYS.into_iter()
+            .zip(values)
+            .filter_map(|(key, value)| Some((key, value?)))
+            .collect()
+    }
+
+    /// `artifact` with the provenance block added in the way `embedding`
+    /// says. An HTML artifact without a `<head>`, or an XML or JSON one
+    /// without a root, gets it as comments at the top instead.
+    pub fn embed(&self, artifact: &str, embedding: Embedding) -> String {
+        let fields = self.fields();
+        let insert =
+            |at: usize, block: &str| format!("{}{}{}", &artifact[..at], block, &artifact[at..]);
+        match embedding {
+            Embedding::Html => {
+                if let Some(head) = artifact.find("<head>") {
+                    let at = head + "<head>".len();
+                    let meta: String = fields
+                        .iter()
+                        .map(|(key, value)| {
+                            format!(
+                                "\n<meta name=\"provenance.{}\" content=\"{}\">",
+                                key,
+                                escape_xml(value)
+                            )
+                        })
+                        .collect();
+                    return insert(at, &meta);
+                }
+            }
+            Embedding::Xml => {
+                if let Some(end) = root_tag_end(artifact) {
+                    let mut attributes = format!(" xmlns:provenance=\"{}\"", XML_NAMESPACE);
+                    for (key, value) in &fields {
+                        attributes.push_str(&format!(
+                            " provenance:{}=\"{}\"",
+                            key,
+                            escape_xml(value)
+                        ));
+                    }
+                    return insert(end, &attributes);
+                }
+            }
+            Embedding::Json => {
+                if let Some(open) = artifact.find('{') {
+                    let object: Vec<String> = fields
+                        .iter()
+                        .map(|(key, value)| match *key {
+                            "algorithm_version" | "seed" => format!("\"{}\": {}", key, value),
+                            _ => format!("\"{}\": {}", key, json_string(value)),
+                        })
+                        .collect();
+                    let rest = artifact[open + 1..].trim_start();
+                    let separator = if rest.starts_with('}') { "" } else { "," };
+                    return insert(
+                        open + 1,
+                        &format!("\n  \"provenance\": {{{}}}{}", object.join(", "), separator),
+                    );
+                }
+            }
+            Embedding::Comments => {}
+        }
+        let comments: String = fields
+            .iter()
+            .map(|(key, value)| format!("# provenance.{}: {}\n", key, value))
+            .collect();
+        format!("{}{}", comments, artifact)
+    }
+
+    /// Reads back the block [`Provenance::embed`] put in `artifact`, in
+    /// whichever of the embeddings it finds it.
+    pub fn extract(artifact: &str) -> Result<Provenance, ProvenanceError> {
+        let value = |key: &'static str| find_value(artifact, key);
+        let number = |key: &'static str| -> Result<Option<u64>, ProvenanceError> {
+            value(key)
+                .map(|value| {
+                    value
+                        .parse()
+                        .map_err(|_| ProvenanceError::Invalid { key, value })
+                })
+                .transpose()
+        };
+        let crate_version = value("crate_version").ok_or(ProvenanceError::Missing)?;
+        let algorithm_version = number("algorithm_version")?.ok_or(ProvenanceError::Missing)?;
+        let generated_at = value("generated_at")
+            .map(|value| {
+                DateTime::parse_from_rfc3339(&value)
+                    .map(|time| time.with_timezone(&Utc))
+                    .map_err(|_| ProvenanceError::Invalid {
+                        key: "generated_at",
+                        value,
+                    })
+            })
+            .transpose()?;
+        Ok(Provenance {
+            crate_version,
+            algorithm_version: u32::try_from(algorithm_version).map_err(|_| {
+                ProvenanceError::Invalid {
+                    key: "algorithm_version",
+                    value: algorithm_version.to_string(),
+                }
+            })?,
+            seed: number("seed")?,
+            scenario_hash: value("scenario_hash"),
+            generated_at,
+            host: value("host"),
+            user: value("user"),
+        })
+    }
+}
+
+impl fmt::Display for Provenance {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (key, value) in self.fields() {
+            writeln!(f, "{}: {}", key, value)?;
+        }
+        Ok(())
+    }
+}
+
+// 64-bit FNV-1a over every file in turn, each ended by a zero byte so moving
+// bytes from one file to the next changes the hash
+fn scenario_hash(scenario: &[&[u8]]) -> String {
+    let mut hash: u64 = 0xcbf2_9ce4_8422_2325;
+    for &file in scenario {
+        for &byte in file.iter().chain(&[0]) {
+            hash ^= byte as u64;
+            hash = hash.wrapping_mul(0x0100_0000_01b3);
+        }
+    }
+    format!("{:016x}", hash)
+}
+
+// the position of the '>' ending the first element's start tag, past the
+// XML declaration and any comments
+fn root_tag_end(xml: &str) -> Option<usize> {
+    let mut from = 0;
+    loop {
+        let start = from + xml[from..].find('<')?;
+        let rest = &xml[start..];
+        if rest.starts_with("<?") || rest.starts_with("<!") {
+            from = start + rest.find('>')? + 1;
+            continue;
+        }
+        let end = start + rest.find('>')?;
+        return Some(if xml[..end].ends_with('/') {
+            end - 1
+        } else {
+            end
+        });
+    }
+}
+
+fn find_value(text: &str, key: &str) -> Option<String> {
+    let comment = format!("# provenance.{}: ", key);
+    if let Some(line) = text.lines().find_map(|line| line.strip_prefix(&comment)) {
+        return Some(line.trim_end().to_string());
+    }
+    for prefix in [
+        format!("<meta name=\"provenance.{}\" content=\"", key),
+        format!(" provenance:{}=\"", key),
+    ] {
+        if let Some(start) = text.find(&prefix) {
+            let rest = &text[start + prefix.len()..];
+            return Some(unescape_xml(&rest[..rest.find('"')?]));
+        }
+    }
+    let object = &text[text.find("\"provenance\": {")?..];
+    let object = &object[..object.find('}')?];
+    let field = format!("\"{}\": ", key);
+    let rest = &object[object.find(&field)? + field.len()..];
+    match rest.strip_prefix('"') {
+        Some(string) => Some(unescape_json(string)),
+        None => Some(
+            rest.split([',', '}'])
+                .next()
+                .unwrap_or_default()
+                .trim()
+                .to_string(),
+        ),
+    }
+}
+
+fn escape_xml(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+fn unescape_xml(text: &str) -> String {
+    text.replace("&quot;", "\"")
+        .replace("&#39;", "'")
+        .replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&amp;", "&")
+}
+
+// a JSON string up to its closing quote, as `output::json_string` writes it
+fn unescape_json(text: &str) -> String {
+    let mut unescaped = String::new();
+    let mut chars = text.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '"' => break,
+            '\\' => match chars.next() {
+                Some('n') => unescaped.push('\n'),
+                Some('t') => unescaped.push('\t'),
+                Some('u') => {
+                    let code: String = chars.by_ref().take(4).collect();
+                    if let Some(c) = u32::from_str_radix(&code, 16).ok().and_then(char::from_u32) {
+                        unescaped.push(c);
+                    }
+                }
+                Some(c) => unescaped.push(c),
+                None => break,
+            },
+            c => unescaped.push(c),
+        }
+    }
+    unescaped
+}
@@ -0,0 +1,620 @@
+use std::cmp::{max, min};
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+use std::str::FromStr;
+
+use rand::prelude::SliceRandom;
+#[cfg(not(feature = "no-entropy"))]
+use rand::thread_rng;
+use rand::Rng;
+
+use crate::config::EventConfig;
+use crate::{
+    assign_offsets, pinned_reach, stabilize_windows, window_boundaries, Competitor, Minutes,
+    SavedDraw, StartListError, Window,
+};
+
+/// One change an update made to a saved draw, as kept in its log. Windows are
+/// 0-based here and 1-based in the text form.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DrawOperation {
+    Removed {
+        name: String,
+        window: usize,
+    },
+    // a late entry, drawn into the window's order
+    Added {
+        name: String,
+        window: usize,
+    },
+    // the entry changed windows; `from` is the one entered in before
+    Moved {
+        name: String,
+        from: usize,
+        to: usize,
+    },
+    // the window's own starts were shuffled again and rebalanced with its
+    // neighbours, see SyncPolicy::Redraw
+    Redrawn {
+        window: usize,
+    },
+    // a rolling draw's entry, pinned at a slot drawn from the `pool` free ones
+    Assigned {
+        name: String,
+        window: usize,
+        offset: Minutes,
+        pool: usize,
+    },
+}
+
+impl fmt::Display for DrawOperation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DrawOperation::Removed { name, window } => {
+                write!(f, "removed {} from window {}", name, window + 1)
+            }
+            DrawOperation::Added { name, window } => {
+                write!(f, "added {} to window {}", name, window + 1)
+            }
+            DrawOperation::Moved { name, from, to } => write!(
+                f,
+                "moved {} from window {} to window {}",
+                name,
+                from + 1,
+                to + 1
+            ),
+            DrawOperation::Redrawn { window } => write!(f, "redrew window {}", window + 1),
+            DrawOperation::Assigned {
+                name,
+                window,
+                offset,
+                pool,
+            } => write!(
+                f,
+                "assigned {} to window {} at {} of {} free slots",
+                name,
+                window + 1,
+                offset,
+                pool
+            ),
+        }
+    }
+}
+
+impl FromStr for DrawOperation {
+    type Err = String;
+
+    /// Reads the text form back, the name being everything between the verb
+    /// and the last window reference.
+    fn from_str(text: &str) -> Result<Self, Self::Err> {
+        let invalid = || format!("invalid draw operation '{}'", text);
+        let window = |number: &str| {
+            number
+                .trim()
+                .parse::<usize>()
+                .ok()
+                .and_then(|number| number.checked_sub(1))
+                .ok_or_else(invalid)
+        };
+        if let Some(rest) = text.strip_prefix("removed ") {
+            let (name, number) = rest.rsplit_once(" from window ").ok_or_else(invalid)?;
+            return Ok(DrawOperation::Removed {
+                name: name.to_string(),
+                window: window(number)?,
+            });
+        }
+        if let Some(rest) = text.strip_prefix("added ") {
+            let (name, number) = rest.rsplit_once(" to window ").ok_or_else(invalid)?;
+            return Ok(DrawOperation::Added {
+                name: name.to_string(),
+                window: window(number)?,
+            });
+        }
+        if let Some(rest) = text.strip_prefix("moved ") {
+            let (rest, to) = rest.rsplit_once(" to window ").ok_or_else(invalid)?;
+            let (name, from) = rest.rsplit_once(" from window ").ok_or_else(invalid)?;
+            return Ok(DrawOperation::Moved {
+                name: name.to_string(),
+                from: window(from)?,
+                to: window(to)?,
+            });
+        }
+        if let Some(rest) = text.strip_prefix("assigned ") {
+            let (rest, pool) = rest
+                .strip_suffix(" free slots")
+                .and_then(|rest| rest.rsplit_once(" of "))
+                .ok_or_else(invalid)?;
+            let (rest, offset) = rest.rsplit_once(" at ").ok_or_else(invalid)?;
+            let (name, number) = rest.rsplit_once(" to window ").ok_or_else(invalid)?;
+            return Ok(DrawOperation::Assigned {
+                name: name.to_string(),
+                window: window(number)?,
+                offset: offset.trim().parse().map_err(|_| invalid())?,
+                pool: pool.trim().parse().map_err(|_| invalid())?,
+            });
+        }
+        if let Some(number) = text.strip_prefix("redrew window ") {
+            return Ok(DrawOperation::Redrawn {
+                window: window(number)?,
+            });
+        }
+        Err(invalid())
+    }
+}
+
+/// What [`sync`] does when the updated entries no longer fit a window at
+/// min_spacing.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum SyncPolicy {
+    // leave the draw as it was and report the window
+    #[default]
+    Error,
+    // redraw the overfull window and let stabilization move its surplus into
+    // its neighbours; the rest of the draw is kept
+    Redraw,
+}
+
+#[derive(Debug)]
+pub enum SyncError {
+    // an entry names a window the draw does not have
+    UnknownWindow {
+        name: String,
+        window: usize,
+        window_count: usize,
+    },
+    // the entries list a competitor twice, so there is no telling which to keep
+    DuplicateEntry(String),
+    // a window the entries oversubscribe, after a redraw if the policy allows one
+    Oversubscribed(StartListError),
+    // a rolling draw's entry found every slot in their windows taken
+    NoFreeSlot(String),
+}
+
+impl fmt::Display for SyncError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SyncError::UnknownWindow {
+                name,
+                window,
+                window_count,
+            } => write!(
+                f,
+                "{} is entered in window {}, but the draw has {} windows",
+                name,
+                window + 1,
+                window_count
+            ),
+            SyncError::DuplicateEntry(name) => write!(f, "{} is entered twice", name),
+            SyncError::Oversubscribed(error) => write!(f, "{}", error),
+            SyncError::NoFreeSlot(name) => {
+                write!(f, "there is no free slot left for {}", name)
+            }
+        }
+    }
+}
+
+impl std::error::Error for SyncError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            SyncError::Oversubscribed(error) => Some(error),
+            _ => None,
+        }
+    }
+}
+
+/// Brings a saved draw up to date with `new_entries`, the full updated list of
+/// `(window, competitor)` entries: withdrawn competitors leave their window,
+/// late entries are drawn into theirs like [`DrawOperation::Added`], and an
+/// entry that changed windows leaves the old one and is drawn into the new
+/// one. Everyone else keeps their place, and their club and category are
+/// taken from the entry. Pinned starts are not entries and are left alone.
+///
+/// Returns what changed, which is also added to `saved.log` under a new
+/// revision; with nothing to change the revision stays. On an error the draw
+/// is left as it was.
+#[cfg(not(feature = "no-entropy"))]
+pub fn sync(
+    saved: &mut SavedDraw,
+    new_entries: &[(usize, Competitor)],
+    policy: SyncPolicy,
+    config: &EventConfig,
+) -> Result<Vec<DrawOperation>, SyncError> {
+    sync_with_rng(saved, new_entries, policy, config, &mut thread_rng())
+}
+
+pub fn sync_with_rng(
+    saved: &mut SavedDraw,
+    new_entries: &[(usize, Competitor)],
+    policy: SyncPolicy,
+    config: &EventConfig,
+    rng: &mut impl Rng,
+) -> Result<Vec<DrawOperation>, SyncError> {
+    let window_count = saved.windows.len();
+    let mut entered: HashMap<&str, (usize, &Competitor)> = HashMap::new();
+    for (window, competitor) in new_entries {
+        if *window >= window_count {
+            return Err(SyncError::UnknownWindow {
+                name: competitor.name.clone(),
+                window: *window,
+                window_count,
+            });
+        }
+        if entered
+            .insert(&competitor.name, (*window, competitor))
+            .is_some()
+        {
+            return Err(SyncError::DuplicateEntry(competitor.name.clone()));
+        }
+    }
+
+    let mut windows = saved.windows.clone();
+    let mut operations = Vec::new();
+    let mut kept = HashSet::new();
+    let mut moved = Vec::new();
+    for (i, window) in windows.iter_mut().enumerate() {
+        let mut j = 0;
+        while j < window.competitors.len() {
+            let competitor = &window.competitors[j];
+            if competitor.fixed_offset.is_some() {
+                kept.insert(competitor.name.clone());
+                j += 1;
+                continue;
+            }
+            // the window the competitor was entered in, before stabilization
+            let home = (i as isize + competitor.origin) as usize;
+            match entered.get(competitor.name.as_str()) {
+                None => {
+                    operations.push(DrawOperation::Removed {
+                        name: competitor.name.clone(),
+                        window: home,
+                    });
+                    window.competitors.remove(j);
+                }
+                Some(&(entry_window, _)) if entry_window != home => {
+                    let competitor = window.competitors.remove(j).unwrap();
+                    operations.push(DrawOperation::Moved {
+                        name: competitor.name.clone(),
+                        from: home,
+                        to: entry_window,
+                    });
+                    moved.push(competitor.name);
+                }
+                Some(&(_, entry)) => {
+                    let competitor = &mut window.competitors[j];
+                    competitor.club = entry.club.clone();
+                    competitor.category = entry.category.clone();
+                    kept.insert(competitor.name.clone());
+                    j += 1;
+                }
+            }
+        }
+    }
+    for (window, entry) in new_entries {
+        if kept.contains(&entry.name) {
+            continue;
+        }
+        if !moved.contains(&entry.name) {
+            operations.push(DrawOperation::Added {
+                name: entry.name.clone(),
+                window: *window,
+            });
+        }
+        insert_late_entry(&mut windows[*window], entry.clone(), rng);
+    }
+    if operations.is_empty() {
+        return Ok(operations);
+    }
+
+    let (mut tie_breaks, mut thresholds) = (Vec::new(), Vec::new());
+    let mut redrawn = Vec::new();
+    loop {
+        match assign_offsets(windows.clone(), config, &[], rng, true) {
+            Err(StartListError::WindowOverfull { window, .. })
+                if policy == SyncPolicy::Redraw && !redrawn.contains(&window) =>
+            {
+                let (window_tie_breaks, window_thresholds) =
+                    redraw_window(&mut windows, window, config, rng);
+                tie_breaks.extend(window_tie_breaks);
+                thresholds.extend(window_thresholds);
+                operations.push(DrawOperation::Redrawn { window });
+                redrawn.push(window);
+            }
+            Err(error @ StartListError::WindowOverfull { .. }) => {
+                return Err(SyncError::Oversubscribed(error))
+            }
+            // anything else is for the re-timing to report, not the update
+            _ => break,
+        }
+    }
+
+    saved.windows = windows;
+    saved.tie_breaks.extend(tie_breaks);
+    saved.thresholds.extend(thresholds);
+    saved.revision += 1;
+    saved
+        .log
+        .extend(operations.iter().map(|op| (saved.revision, op.clone())));
+    Ok(operations)
+}
+
+// a late entry takes a random place among the window's own drawn starts, as
+// if they had been in the shuffle; moved-in and pinned starts keep theirs
+fn insert_late_entry(window: &mut Window, competitor: Competitor, rng: &mut impl Rng) {
+    let first = window
+        .competitors
+        .iter()
+        .take_while(|c| c.origin < 0)
+        .count();
+    let last = window.competitors.len()
+        - window
+            .competitors
+            .iter()
+            .rev()
+            .take_while(|c| c.origin > 0)
+            .count();
+    let at = rng.gen_range(first..=last);
+    window.competitors.insert(
+        at,
+        Competitor {
+            origin: 0,
+            fixed_offset: None,
+            ..competitor
+        },
+    );
+}
+
+// reshuffles the window's own starts and stabilizes it against its
+// neighbours only: every other window is locked for the while
+fn redraw_window(
+    windows: &mut [Window],
+    window: usize,
+    config: &EventConfig,
+    rng: &mut impl Rng,
+) -> (Vec<crate::TieBreak>, Vec<f64>) {
+    let locks: Vec<bool> = windows.iter().map(|w| w.locked).collect();
+    let mut pinned = Vec::with_capacity(windows.len());
+    for (i, w) in windows.iter_mut().enumerate() {
+        if i + 1 < window || i > window + 1 {
+            w.locked = true;
+        }
+        let (window_pinned, drawn): (Vec<Competitor>, Vec<Competitor>) = w
+            .competitors
+            .drain(..)
+            .partition(|c| c.fixed_offset.is_some());
+        w.competitors.extend(drawn);
+        pinned.push(window_pinned);
+    }
+    let own: Vec<usize> = (0..windows[window].competitors.len())
+        .filter(|&j| windows[window].competitors[j].origin == 0)
+        .collect();
+    let mut shuffled: Vec<Competitor> = own
+        .iter()
+        .map(|&j| windows[window].competitors[j].clone())
+        .collect();
+    shuffled.shuffle(rng);
+    for (&j, competitor) in own.iter().zip(shuffled) {
+        windows[window].competitors[j] = competitor;
+    }
+    let stabilized = stabilize_windows(windows, config, rng);
+    for ((w, window_pinned), locked) in windows.iter_mut().zip(pinned).zip(locks) {
+        w.locked = locked;
+        let at = w.competitors.iter().take_while(|c| c.origin < 0).count();
+        for competitor in window_pinned.into_iter().rev() {
+            w.competitors.insert(at, competitor);
+        }
+    }
+    stabilized
+}
+
+/// Pins each of a rolling draw's `batch` of `(window, competitor)` entries at
+/// a slot drawn uniformly from every free one left in their windows: their
+/// own, and the unlocked neighbours too if they are flexible. So an entry
+/// sent in late has the same chances as an early one of any slot still free.
+///
+/// Slots are laid out min_spacing apart from the start of each window, or
+/// from the spacing after the window before's last one, at allowed offsets
+/// outside the blocked intervals; pinned starts and their reach are taken
+/// out. A window keeps a slot for each of its drawn competitors and sweepers.
+/// Every assignment goes to `saved.log` with the size of the pool it was
+/// drawn from, under a new revision. On an error the draw is left as it was.
+pub fn rolling_assign(
+    saved: &mut SavedDraw,
+    batch: Vec<(usize, Competitor)>,
+    config: &EventConfig,
+    rng: &mut impl Rng,
+) -> Result<Vec<DrawOperation>, SyncError> {
+    let window_count = saved.windows.len();
+    let mut names: HashSet<&str> = saved
+        .windows
+        .iter()
+        .flat_map(|w| w.competitors.iter().map(|c| c.name.as_str()))
+        .collect();
+    for (window, competitor) in &batch {
+        if *window >= window_count {
+            return Err(SyncError::UnknownWindow {
+                name: competitor.name.clone(),
+                window: *window,
+                window_count,
+            });
+        }
+        if !names.insert(&competitor.name) {
+            return Err(SyncError::DuplicateEntry(competitor.name.clone()));
+        }
+    }
+
+    let mut windows = saved.windows.clone();
+    let mut pool = SlotPool::new(&windows, config);
+    let mut operations = Vec::with_capacity(batch.len());
+    for (window, competitor) in batch {
+        let flexible = competitor.flexible && !windows[window].locked;
+        let allowed: Vec<usize> = (window.saturating_sub(1)..=window + 1)
+            .filter(|&w| w < window_count)
+            .filter(|&w| w == window || (flexible && !windows[w].locked))
+            .collect();
+        let (w, offset, free) = pool
+            .draw(&allowed, &competitor, rng)
+            .ok_or_else(|| SyncError::NoFreeSlot(competitor.name.clone()))?;
+        pool.take(w, offset, pinned_reach(&competitor, pool.min_spacings[w]));
+        operations.push(DrawOperation::Assigned {
+            name: competitor.name.clone(),
+            window: w,
+            offset,
+            pool: free,
+        });
+        let at = windows[w]
+            .competitors
+            .iter()
+            .take_while(|c| c.origin < 0)
+            .count();
+        windows[w].competitors.insert(
+            at,
+            Competitor {
+                origin: 0,
+                fixed_offset: Some(offset),
+                ..competitor
+            },
+        );
+    }
+    if operations.is_empty() {
+        return Ok(operations);
+    }
+
+    saved.windows = windows;
+    saved.revision += 1;
+    saved
+        .log
+        .extend(operations.iter().map(|op| (saved.revision, op.clone())));
+    Ok(operations)
+}
+
+// the free slots of every window, ascending; a drawn slot and those within
+// its reach are taken out as it goes, not laid out again
+struct SlotPool {
+    free: Vec<Vec<Minutes>>,
+    // slots each window keeps for the competitors the draw places itself
+    reserved: Vec<usize>,
+    min_spacings: Vec<Minutes>,
+    // every pinned start, with its reach, for entries with a radius of their own
+    pinned: Vec<(Minutes, Minutes)>,
+}
+
+impl SlotPool {
+    fn new(windows: &[Window], config: &EventConfig) -> Self {
+        let blocked: Vec<(Minutes, Minutes)> = config.blocked_intervals(windows).collect();
+        let min_spacings: Vec<Minutes> = windows.iter().map(|w| w.min_spacing(config)).collect();
+        let pinned: Vec<(Minutes, Minutes)> = windows
+            .iter()
+            .zip(&min_spacings)
+            .flat_map(|(w, &min_spacing)| {
+                w.competitors
+                    .iter()
+                    .filter_map(move |c| Some((c.fixed_offset?, pinned_reach(c, min_spacing))))
+            })
+            .collect();
+        let mut free = Vec::with_capacity(windows.len());
+        let mut last: Option<(Minutes, Minutes)> = None;
+        for (i, (start, end)) in window_boundaries(windows).into_iter().enumerate() {
+            let min_spacing = min_spacings[i];
+            let mut next = max(start, config.first_start_offset);
+            if let Some((last, last_min_spacing)) = last {
+                next = max(next, last + max(last_min_spacing, min_spacing));
+            }
+            let mut slots = Vec::new();
+            while let Some(offset) = config.allowed_offsets.next_allowed(next) {
+                if offset >= end {
+                    break;
+                }
+                if let Some(&(_, to)) = blocked
+                    .iter()
+                    .find(|&&(from, to)| from <= offset && offset < to)
+                {
+                    next = to;
+                    continue;
+                }
+                slots.push(offset);
+                next = offset + min_spacing;
+            }
+            if let Some(&slot) = slots.last() {
+                last = Some((slot, min_spacing));
+            }
+            // no slot within a pinned start's reach, nor its min_spacing
+            slots.retain(|&slot| {
+                pinned
+                    .iter()
+                    .all(|&(offset, reach)| (slot - offset).abs() > max(reach, min_spacing - 1))
+            });
+            free.push(slots);
+        }
+        let reserved = windows
+            .iter()
+            .enumerate()
+            .map(|(i, w)| {
+                w.competitors
+                    .iter()
+                    .filter(|c| c.fixed_offset.is_none())
+                    .count()
+                    + config.sweepers.iter().filter(|s| s.window == i).count()
+            })
+            .collect();
+        SlotPool {
+            free,
+            reserved,
+            min_spacings,
+            pinned,
+        }
+    }
+
+    // a uniformly drawn free slot of the `allowed` windows with room to
+    // spare, and how many there were to draw from
+    fn draw(
+        &self,
+        allowed: &[usize],
+        competitor: &Competitor,
+        rng: &mut impl Rng,
+    ) -> Option<(usize, Minutes, usize)> {
+        let open: Vec<usize> = allowed
+            .iter()
+            .copied()
+            .filter(|&w| self.free[w].len() > self.reserved[w])
+            .collect();
+        // a radius of their own rules out slots near other pinned starts
+        if competitor.exclusive_radius > 0 {
+            let fits: Vec<(usize, Minutes)> = open
+                .iter()
+                .flat_map(|&w| self.free[w].iter().map(move |&slot| (w, slot)))
+                .filter(|&(_, slot)| {
+                    self.pinned
+                        .iter()
+                        .all(|&(offset, _)| (slot - offset).abs() > competitor.exclusive_radius)
+                })
+                .collect();
+            let &(w, slot) = fits.choose(rng)?;
+            return Some((w, slot, fits.len()));
+        }
+        let total: usize = open.iter().map(|&w| self.free[w].len()).sum();
+        if total == 0 {
+            return None;
+        }
+        let mut index = rng.gen_range(0..total);
+        for &w in &open {
+            if index < self.free[w].len() {
+                return Some((w, self.free[w][index], total));
+            }
+            index -= self.free[w].len();
+        }
+        unreachable!()
+    }
+
+    // takes the slot and every free one within `reach` of it, which only
+    // the windows either side may hold
+    fn take(&mut self, window: usize, offset: Minutes, reach: Minutes) {
+        for w in window.saturating_sub(1)..=min(window + 1, self.free.len() - 1) {
+            let reach = max(reach, self.min_spacings[w] - 1);
+            let slots = &mut self.free[w];
+            let from = slots.partition_point(|&slot| slot < offset - reach);
+            let to = slots.partition_point(|&slot| slot <= offset + reach);
+            slots.drain(from..to);
+        }
+        self.pinned.push((offset, reach));
+    }
+}
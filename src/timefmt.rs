@@ -0,0 +1,199 @@
+use std::str::FromStr;
+
+use chrono::{Duration, NaiveDate, NaiveTime, Timelike};
+
+use crate::Minutes;
+
+const SECONDS_PER_DAY: i64 = 24 * 60 * 60;
+
+/// What one unit of an offset, duration or spacing stands for. Events count
+/// in whole minutes unless they need sub-minute intervals, e.g. sprints
+/// starting every 30 seconds.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum TimeUnit {
+    #[default]
+    Minutes,
+    Seconds,
+}
+
+impl TimeUnit {
+    pub fn seconds(self) -> i64 {
+        match self {
+            TimeUnit::Minutes => 60,
+            TimeUnit::Seconds => 1,
+        }
+    }
+
+    /// `minutes` counted in this unit.
+    pub fn from_minutes(self, minutes: Minutes) -> Minutes {
+        minutes * (60 / self.seconds()) as Minutes
+    }
+}
+
+impl FromStr for TimeUnit {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "minutes" => Ok(TimeUnit::Minutes),
+            "seconds" => Ok(TimeUnit::Seconds),
+            _ => Err(format!(
+                "unknown time unit '{}', expected minutes or seconds",
+                s
+            )),
+        }
+    }
+}
+
+/// How finely times are written out. Seconds that are not zero are always
+/// shown; `Seconds` shows them even when they are, for layouts that expect
+/// it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Resolution {
+    Minutes,
+    Seconds,
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct ClockStyle {
+    pub resolution: Resolution,
+    pub separator: char, // ':' by default, '.' where that is the local habit
+    pub unit: TimeUnit,  // what the offsets being written count
+}
+
+impl Default for ClockStyle {
+    fn default() -> Self {
+        ClockStyle {
+            resolution: Resolution::Minutes,
+            separator: ':',
+            unit: TimeUnit::Minutes,
+        }
+    }
+}
+
+/// Writes an offset as a signed duration, e.g. "0:59", "1:00", "-0:01",
+/// "24:01", or "0:00:30" for half a minute. Hours are not wrapped, so
+/// multi-day offsets stay unambiguous.
+pub fn format_offset(offset: Minutes, unit: TimeUnit, resolution: Resolution) -> String {
+    let sign = if offset < 0 { "-" } else { "" };
+    let seconds = offset.unsigned_abs() as i64 * unit.seconds();
+    let minutes = seconds / 60;
+    if resolution == Resolution::Seconds || seconds % 60 != 0 {
+        format!(
+            "{}{}:{:02}:{:02}",
+            sign,
+            minutes / 60,
+            minutes % 60,
+            seconds % 60
+        )
+    } else {
+        format!("{}{}:{:02}", sign, minutes / 60, minutes % 60)
+    }
+}
+
+/// Writes the wall-clock time `offset` after `zero`, e.g. "09:03". Times on
+/// another day than `zero` get a day suffix: "00:01 +1d", "23:59 -1d".
+pub fn format_clock(zero: NaiveTime, offset: Minutes, style: ClockStyle) -> String {
+    let total = zero.num_seconds_from_midnight() as i64 + offset as i64 * style.unit.seconds();
+    let day = total.div_euclid(SECONDS_PER_DAY);
+    let second_of_day = total.rem_euclid(SECONDS_PER_DAY);
+
+    let mut clock = format!(
+        "{:02}{}{:02}",
+        second_of_day / 3600,
+        style.separator,
+        second_of_day / 60 % 60
+    );
+    if style.resolution == Resolution::Seconds || second_of_day % 60 != 0 {
+        clock.push(style.separator);
+        clock.push_str(&format!("{:02}", second_of_day % 60));
+    }
+    if day != 0 {
+        clock.push_str(&format!(" {:+}d", day));
+    }
+    clock
+}
+
+/// Reads "HH:MM", "HH:MM:SS", or the same with '.' as the separator.
+pub fn parse_clock(value: &str) -> Result<NaiveTime, String> {
+    let invalid = || format!("invalid clock time '{}', expected HH:MM", value);
+    let separator = if value.contains(':') { ':' } else { '.' };
+    let parts: Vec<&str> = value.split(separator).collect();
+    let number = |part: &str| -> Result<u32, String> {
+        if part.is_empty() || part.len() > 2 || !part.bytes().all(|b| b.is_ascii_digit()) {
+            return Err(invalid());
+        }
+        part.parse().map_err(|_| invalid())
+    };
+    let (hours, minutes, seconds) = match parts.as_slice() {
+        [hours, minutes] => (number(hours)?, number(minutes)?, 0),
+        [hours, minutes, seconds] => (number(hours)?, number(minutes)?, number(seconds)?),
+        _ => return Err(invalid()),
+    };
+    NaiveTime::from_hms_opt(hours, minutes, seconds).ok_or_else(invalid)
+}
+
+/// Reads an offset written by `format_offset` ("-0:01", "24:01", "0:00:30")
+/// or as a plain count of `unit` ("90"), counted in `unit`. A time between two
+/// units, e.g. "0:00:30" for an event counting minutes, is an error.
+pub fn parse_offset(value: &str, unit: TimeUnit) -> Result<Minutes, String> {
+    let invalid = || {
+        format!(
+            "invalid offset '{}', expected [-]H:MM[:SS] or a count",
+            value
+        )
+    };
+    let (negative, unsigned) = match value.strip_prefix('-') {
+        Some(rest) => (true, rest),
+        None => (false, value),
+    };
+    let digits = |part: &str| -> Result<Minutes, String> {
+        if part.is_empty() || !part.bytes().all(|b| b.is_ascii_digit()) {
+            return Err(invalid());
+        }
+        part.parse().map_err(|_| invalid())
+    };
+    // minutes and seconds are two digits below 60, as format_offset writes them
+    let sexagesimal = |part: &str| -> Result<Minutes, String> {
+        match digits(part)? {
+            below_60 if part.len() == 2 && below_60 < 60 => Ok(below_60),
+            _ => Err(invalid()),
+        }
+    };
+    let parts: Vec<&str> = unsigned.split(':').collect();
+    let count = match parts.as_slice() {
+        [count] => digits(count)?,
+        [hours, minutes] | [hours, minutes, _] => {
+            let mut seconds = (digits(hours)? * 60 + sexagesimal(minutes)?) * 60;
+            if let [_, _, part] = parts.as_slice() {
+                seconds += sexagesimal(part)?;
+            }
+            if seconds % unit.seconds() as Minutes != 0 {
+                return Err(format!(
+                    "offset '{}' is not a whole number of minutes",
+                    value
+                ));
+            }
+            seconds / unit.seconds() as Minutes
+        }
+        _ => return Err(invalid()),
+    };
+    Ok(if negative { -count } else { count })
+}
+
+/// The offset of `clock` from `zero` in `unit`, on the same day.
+pub fn offset_of(zero: NaiveTime, clock: NaiveTime, unit: TimeUnit) -> Minutes {
+    ((clock - zero).num_seconds() / unit.seconds()) as Minutes
+}
+
+/// The ISO 8601 local date-time `offset` after `zero` on `date`, e.g.
+/// "2024-05-18T09:03:00", rolling over into the next day if need be.
+pub fn format_datetime(
+    date: NaiveDate,
+    zero: NaiveTime,
+    offset: Minutes,
+    unit: TimeUnit,
+) -> String {
+    let datetime = date.and_time(zero) + Duration::seconds(offset as i64 * unit.seconds());
+    datetime.format("%Y-%m-%dT%H:%M:%S").to_string()
+}
@@ -0,0 +1,87 @@
+use std::cmp::{max, min};
+
+use crate::config::EventConfig;
+use crate::{CompetitorWithOffset, Minutes};
+
+/// Who depends on which shuttle to reach the start.
+pub struct TransportReport<'a> {
+    // per shuttle in config order, the starts it is the latest shuttle for
+    // that arrives at least transport_margin before them
+    pub riders: Vec<usize>,
+    // starts no shuttle reaches transport_margin ahead of, in clock order
+    pub stranded: Vec<&'a CompetitorWithOffset>,
+}
+
+/// Matches every start to the latest shuttle that gets the competitor there
+/// at least `config.transport_margin` before it. Without any shuttles nobody
+/// is stranded.
+pub fn transport_report<'a>(
+    competitors: &'a [CompetitorWithOffset],
+    config: &EventConfig,
+) -> TransportReport<'a> {
+    let mut report = TransportReport {
+        riders: vec![0; config.transport.len()],
+        stranded: Vec::new(),
+    };
+    if config.transport.is_empty() {
+        return report;
+    }
+    let mut ordered: Vec<&CompetitorWithOffset> = competitors.iter().collect();
+    ordered.sort_by_key(|c| c.offset);
+    for competitor_with_offset in ordered {
+        match latest_shuttle(config, competitor_with_offset.offset) {
+            Some(shuttle) => report.riders[shuttle] += 1,
+            None => report.stranded.push(competitor_with_offset),
+        }
+    }
+    report
+}
+
+fn latest_shuttle(config: &EventConfig, offset: Minutes) -> Option<usize> {
+    config
+        .transport
+        .iter()
+        .enumerate()
+        .filter(|(_, shuttle)| shuttle.arrival() + config.transport_margin <= offset)
+        .max_by_key(|(_, shuttle)| shuttle.arrival())
+        .map(|(i, _)| i)
+}
+
+// moves starts too early for every shuttle later in their window, pushing
+// the starts after them along at min_spacing; nobody passes the window's last
+// minute, so a window too short for the delay keeps some stranded starts
+pub(crate) fn repair_transport(
+    competitors: &mut [CompetitorWithOffset],
+    boundaries: &[(Minutes, Minutes)],
+    min_spacings: &[Minutes],
+    config: &EventConfig,
+) {
+    let Some(earliest) = config
+        .transport
+        .iter()
+        .map(|shuttle| shuttle.arrival() + config.transport_margin)
+        .min()
+    else {
+        return;
+    };
+    competitors.sort_by_key(|c| c.offset);
+    for (window, &(_, window_end)) in boundaries.iter().enumerate() {
+        let starts: Vec<usize> = (0..competitors.len())
+            .filter(|&i| competitors[i].window == window)
+            .collect();
+        if starts.iter().all(|&i| competitors[i].offset >= earliest) {
+            continue;
+        }
+        let min_spacing = min_spacings[window];
+        let mut prev: Option<Minutes> = None;
+        for (k, &i) in starts.iter().enumerate() {
+            // leaves room for everyone after at min_spacing
+            let latest = window_end - 1 - (starts.len() - 1 - k) as Minutes * min_spacing;
+            let wanted = max(earliest, prev.map_or(Minutes::MIN, |p| p + min_spacing));
+            let offset = max(competitors[i].offset, min(wanted, latest));
+            competitors[i].offset = offset;
+            prev = Some(offset);
+        }
+    }
+    competitors.sort_by_key(|c| c.offset);
+}
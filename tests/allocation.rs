@@ -0,0 +1,141 @@
+mod common;
+
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+use start_list_generator::allocation::{allocation_csv, allocation_html, allocation_text};
+use start_list_generator::input::windows_from_csv;
+use start_list_generator::{
+    try_generate_draw_with_rng, try_generate_startlist_with_rng, Competitor, SavedDraw, Window,
+};
+
+use common::config;
+
+const NAMES: [&str; 12] = [
+    "Maja", "Erik", "alva", "Oskar", "Ebba", "Lukas", "Wilma", "Hugo", "Saga", "Axel", "Freja",
+    "Nils",
+];
+
+// stabilization moves some of the crowded first window into the second
+fn draw() -> SavedDraw {
+    let mut first = Window::new(20, NAMES[..9].iter().map(|&name| Competitor::new(name)));
+    first.competitors[0].club = Some("OK Linné".to_string());
+    let mut pinned = Competitor::new("Pinned");
+    pinned.fixed_offset = Some(26);
+    let mut second = Window::new(30, NAMES[9..].iter().map(|&name| Competitor::new(name)));
+    second.competitors.push_back(pinned);
+    try_generate_draw_with_rng(
+        vec![first, second],
+        &config(),
+        &mut StdRng::seed_from_u64(8),
+    )
+    .unwrap()
+}
+
+fn sorted(names: impl IntoIterator<Item = String>) -> Vec<String> {
+    let mut names: Vec<String> = names.into_iter().collect();
+    names.sort_by_key(|name| name.to_lowercase());
+    names
+}
+
+#[test]
+fn the_allocation_lists_windows_alphabetically_not_in_draw_order() {
+    let saved = draw();
+    let drawn: Vec<Vec<String>> = saved
+        .windows
+        .iter()
+        .map(|w| w.competitors.iter().map(|c| c.name.clone()).collect())
+        .collect();
+    let alphabetical: Vec<Vec<String>> = drawn.iter().cloned().map(sorted).collect();
+    assert_ne!(drawn[0], alphabetical[0], "pick a seed that shuffles");
+
+    let text = allocation_text(&saved, &config());
+    let mut listed: Vec<Vec<String>> = Vec::new();
+    for line in text.lines() {
+        if line.starts_with("Window ") {
+            listed.push(Vec::new());
+        } else {
+            let name = line.trim().split([' ', ',']).next().unwrap();
+            listed.last_mut().unwrap().push(name.to_string());
+        }
+    }
+    assert_eq!(listed, alphabetical);
+    assert!(text.starts_with(&format!(
+        "Window 1: 09:00–09:20, {} competitors\n",
+        drawn[0].len()
+    )));
+    assert!(text.contains("Window 2: 09:20–09:50"));
+    assert!(text.contains("Maja (OK Linné)"));
+    assert!(text.contains("Pinned, starts 09:26"));
+
+    let html = allocation_html(&saved, &config());
+    let positions: Vec<usize> = alphabetical[0]
+        .iter()
+        .map(|name| html.find(&format!("<li>{}", name)).unwrap())
+        .collect();
+    assert!(positions.windows(2).all(|pair| pair[0] < pair[1]));
+    assert!(html.contains(&format!("{} competitors", drawn[1].len())));
+    assert!(html.contains("OK Linné"));
+}
+
+#[test]
+fn the_allocation_csv_reads_back_for_the_finalize_phase() {
+    let saved = draw();
+    let csv = allocation_csv(&saved, &config());
+    let rows: Vec<&str> = csv.lines().skip(1).collect();
+    let first_names: Vec<String> = rows
+        .iter()
+        .filter(|row| row.starts_with("1,"))
+        .map(|row| row.split(',').nth(5).unwrap().to_string())
+        .collect();
+    assert_eq!(first_names, sorted(first_names.clone()));
+
+    let windows = windows_from_csv(&csv).unwrap();
+    assert_eq!(windows.len(), 2);
+    for (read, drawn) in windows.iter().zip(&saved.windows) {
+        assert!(read.locked);
+        assert_eq!(read.duration, drawn.duration);
+        assert_eq!(
+            sorted(read.competitors.iter().map(|c| c.name.clone())),
+            sorted(drawn.competitors.iter().map(|c| c.name.clone()))
+        );
+    }
+    let maja = windows[0]
+        .competitors
+        .iter()
+        .chain(&windows[1].competitors)
+        .find(|c| c.name == "Maja")
+        .unwrap();
+    assert_eq!(maja.club.as_deref(), Some("OK Linné"));
+    let pinned = windows[1]
+        .competitors
+        .iter()
+        .find(|c| c.name == "Pinned")
+        .unwrap();
+    assert_eq!(pinned.fixed_offset, Some(26));
+
+    // finalizing draws the times without moving anyone out of their window
+    let result =
+        try_generate_startlist_with_rng(windows.clone(), &config(), &mut StdRng::seed_from_u64(2))
+            .unwrap();
+    for start in &result {
+        assert!(windows[start.window]
+            .competitors
+            .iter()
+            .any(|c| c.name == start.competitor.name));
+    }
+}
+
+#[test]
+fn an_empty_window_is_kept_in_the_allocation() {
+    let windows = vec![
+        Window::new(20, [Competitor::new("Anna")]),
+        Window::new(20, []),
+    ];
+    let saved =
+        try_generate_draw_with_rng(windows, &config(), &mut StdRng::seed_from_u64(1)).unwrap();
+    let text = allocation_text(&saved, &config());
+    assert!(text.contains("Window 2: 09:20–09:40, 0 competitors"));
+    let read = windows_from_csv(&allocation_csv(&saved, &config())).unwrap();
+    assert_eq!(read.len(), 2);
+    assert!(read[1].competitors.is_empty());
+}
@@ -0,0 +1,100 @@
+use chrono::NaiveTime;
+use start_list_generator::config::{ConfigError, EventConfig, OffsetPattern};
+use start_list_generator::input::start_times_from_csv;
+use start_list_generator::timefmt::{offset_of, TimeUnit};
+use start_list_generator::{generate_startlist_seeded, Competitor, Minutes, Window};
+
+fn windows() -> Vec<Window> {
+    [(30, 2), (30, 12), (30, 4)]
+        .into_iter()
+        .enumerate()
+        .map(|(number, (duration, count))| {
+            Window::new(
+                duration,
+                (0..count).map(|i| Competitor::new(format!("{} Competitor {}", number, i))),
+            )
+        })
+        .collect()
+}
+
+fn offsets(config: &EventConfig, seed: u64) -> Vec<Minutes> {
+    let mut offsets: Vec<Minutes> = generate_startlist_seeded(windows(), config, seed)
+        .iter()
+        .map(|c| c.offset)
+        .collect();
+    offsets.sort();
+    offsets
+}
+
+#[test]
+fn parity_keeps_every_start_on_odd_minutes() {
+    let config = EventConfig::builder()
+        .allowed_offsets(OffsetPattern::odd())
+        .build()
+        .unwrap();
+    config.check_allowed_offsets(&windows()).unwrap();
+
+    for seed in 0..20 {
+        let offsets = offsets(&config, seed);
+        assert!(
+            offsets.iter().all(|offset| offset % 2 == 1),
+            "{:?}",
+            offsets
+        );
+        assert!(offsets.windows(2).all(|pair| pair[1] - pair[0] >= 2));
+    }
+}
+
+#[test]
+fn starts_keep_clear_of_an_imported_start_list() {
+    let start_time = NaiveTime::from_hms_opt(9, 0, 0).unwrap();
+    let partner =
+        start_times_from_csv("position,name,start_time\n1,X,09:00\n2,Y,09:20\n3,Z,09:41\n")
+            .unwrap();
+    let taken: Vec<Minutes> = partner
+        .into_iter()
+        .map(|clock| offset_of(start_time, clock, TimeUnit::Minutes))
+        .collect();
+    let config = EventConfig::builder()
+        .allowed_offsets(OffsetPattern::avoiding(taken.iter().copied(), 1))
+        .build()
+        .unwrap();
+
+    for seed in 0..20 {
+        let offsets = offsets(&config, seed);
+        assert!(
+            offsets
+                .iter()
+                .all(|offset| taken.iter().all(|taken| (offset - taken).abs() > 1)),
+            "{:?}",
+            offsets
+        );
+        assert!(offsets.windows(2).all(|pair| pair[1] - pair[0] >= 2));
+    }
+}
+
+#[test]
+fn too_few_allowed_minutes_are_reported() {
+    let config = EventConfig::builder()
+        .allowed_offsets(OffsetPattern::Every { n: 10, phase: 0 })
+        .build()
+        .unwrap();
+    let error = config.check_allowed_offsets(&windows()).err().unwrap();
+    let ConfigError::TooFewAllowedOffsets {
+        slots: 9,
+        entries: 18,
+        ref per_window,
+        ..
+    } = error
+    else {
+        panic!("{:?}", error);
+    };
+    let rows: Vec<_> = per_window
+        .iter()
+        .map(|row| (row.window, row.slots, row.entries))
+        .collect();
+    assert_eq!(rows, [(0, 3, 2), (1, 3, 12), (2, 3, 4)]);
+    assert!(error
+        .to_string()
+        .contains("\n  window 2: room for 3, 12 entered"));
+}
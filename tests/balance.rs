@@ -0,0 +1,78 @@
+use start_list_generator::balance::{balance_counts, BalanceError};
+
+#[test]
+fn a_dense_window_hands_competitors_forward() {
+    let result = balance_counts(&[12, 2], &[20, 30], 3).unwrap();
+
+    assert_eq!(result.counts_before, [12, 2]);
+    assert_eq!(result.counts_after.iter().sum::<usize>(), 14);
+    assert!(result.counts_after[0] < 12);
+    assert_eq!(
+        result.boundary_moves,
+        [12 - result.counts_after[0] as isize]
+    );
+    assert!(result.spacings_after[0] > result.spacings_before[0]);
+    assert_eq!(result.spacings_before, [20.0 / 12.0, 15.0]);
+}
+
+#[test]
+fn a_dense_window_hands_competitors_back() {
+    let result = balance_counts(&[2, 12], &[30, 20], 3).unwrap();
+
+    assert!(result.counts_after[1] < 12);
+    assert_eq!(
+        result.boundary_moves,
+        [result.counts_after[1] as isize - 12]
+    );
+    assert!(result.boundary_moves[0] < 0);
+}
+
+#[test]
+fn moves_are_netted_per_boundary() {
+    // the middle window gives to both neighbours
+    let result = balance_counts(&[1, 16, 1], &[30, 20, 30], 3).unwrap();
+
+    let [first, middle, last] = result.counts_after[..] else {
+        panic!("{:?}", result.counts_after);
+    };
+    assert_eq!(first + middle + last, 18);
+    assert_eq!(
+        result.boundary_moves,
+        [1 - first as isize, last as isize - 1]
+    );
+    assert!(result.boundary_moves[0] < 0 && result.boundary_moves[1] > 0);
+}
+
+#[test]
+fn balanced_windows_stay_as_they_are() {
+    let result = balance_counts(&[5, 5, 5], &[30, 30, 30], 3).unwrap();
+    assert_eq!(result.counts_after, [5, 5, 5]);
+    assert_eq!(result.boundary_moves, [0, 0]);
+    assert_eq!(result.spacings_after, result.spacings_before);
+
+    let result = balance_counts(&[40], &[30], 3).unwrap();
+    assert_eq!(result.counts_after, [40]);
+    assert!(result.boundary_moves.is_empty());
+}
+
+#[test]
+fn counts_and_durations_of_different_lengths_are_an_error() {
+    let error = balance_counts(&[3, 4], &[30], 3).err().unwrap();
+    assert!(matches!(
+        error,
+        BalanceError::LengthMismatch {
+            counts: 2,
+            durations: 1
+        }
+    ));
+    assert_eq!(
+        error.to_string(),
+        "2 counts but 1 durations; every window needs both"
+    );
+}
+
+#[test]
+fn an_invalid_threshold_is_a_config_error() {
+    let error = balance_counts(&[3], &[30], 0).err().unwrap();
+    assert!(matches!(error, BalanceError::Config(_)));
+}
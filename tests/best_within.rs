@@ -0,0 +1,68 @@
+use std::time::Duration;
+
+use start_list_generator::config::EventConfig;
+use start_list_generator::{
+    generate_best_within, generate_startlist_seeded, Competitor, CompetitorWithOffset,
+    StartListError, Window,
+};
+
+fn windows() -> Vec<Window> {
+    vec![
+        Window::new(
+            30,
+            (0..8).map(|i| Competitor::new(format!("1 Competitor {}", i))),
+        ),
+        Window::new(
+            30,
+            (0..8).map(|i| Competitor::new(format!("2 Competitor {}", i))),
+        ),
+    ]
+}
+
+// the earlier Competitor 0 of the first window starts, the better
+fn score(startlist: &[CompetitorWithOffset]) -> f64 {
+    let first = startlist
+        .iter()
+        .find(|c| c.competitor.name == "1 Competitor 0")
+        .unwrap();
+    -(first.offset as f64)
+}
+
+#[test]
+fn the_best_list_found_is_redrawn_from_its_seed() {
+    let config = EventConfig::builder().build().unwrap();
+    let best = generate_best_within(&windows(), &config, Duration::from_millis(50), score).unwrap();
+    assert!(best.tried > 1);
+    for seed in 0..best.tried.min(50) {
+        assert!(score(&generate_startlist_seeded(windows(), &config, seed)) <= best.score);
+    }
+
+    let again = generate_startlist_seeded(windows(), &config, best.seed);
+    assert_eq!(score(&again), best.score);
+    let names = |list: &[CompetitorWithOffset]| -> Vec<(String, isize)> {
+        list.iter()
+            .map(|c| (c.competitor.name.clone(), c.offset))
+            .collect()
+    };
+    assert_eq!(names(&again), names(&best.startlist));
+}
+
+#[test]
+fn a_spent_budget_still_draws_one_seed() {
+    let config = EventConfig::builder().build().unwrap();
+    let best = generate_best_within(&windows(), &config, Duration::ZERO, score).unwrap();
+    assert_eq!((best.seed, best.tried), (0, 1));
+}
+
+#[test]
+fn when_every_seed_fails_the_error_is_returned() {
+    let config = EventConfig::builder().build().unwrap();
+    let windows = vec![Window::new(
+        4,
+        (0..5).map(|i| Competitor::new(format!("Competitor {}", i))),
+    )];
+    assert!(matches!(
+        generate_best_within(&windows, &config, Duration::from_millis(5), score),
+        Err(StartListError::WindowOverfull { .. })
+    ));
+}
@@ -0,0 +1,84 @@
+mod common;
+
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+use start_list_generator::config::EventConfig;
+use start_list_generator::{
+    spacing_violations, try_generate_startlist_with_rng, CompetitorWithOffset, Minutes,
+    StartListError,
+};
+
+use common::window;
+
+fn draw(
+    counts: [usize; 2],
+    blocked: (Minutes, Minutes),
+    seed: u64,
+) -> Result<Vec<CompetitorWithOffset>, StartListError> {
+    let config = EventConfig::builder()
+        .spacing_threshold(3)
+        .min_spacing(2)
+        .blocked_interval(blocked.0, blocked.1)
+        .build()
+        .unwrap();
+    let windows = vec![window(30, 1, counts[0]), window(30, 2, counts[1])];
+    try_generate_startlist_with_rng(windows, &config, &mut StdRng::seed_from_u64(seed))
+}
+
+// every start avoids the block, stays in its own window and keeps min_spacing
+fn assert_clear_of(result: &[CompetitorWithOffset], (from, to): (Minutes, Minutes)) {
+    for c in result {
+        assert!(
+            !(from..to).contains(&c.offset),
+            "{} at {}",
+            c.competitor.name,
+            c.offset
+        );
+        let (start, end) = if c.window == 0 { (0, 30) } else { (30, 60) };
+        assert!(
+            (start..end).contains(&c.offset),
+            "{} at {}",
+            c.competitor.name,
+            c.offset
+        );
+    }
+    assert!(spacing_violations(result, &[2, 2]).is_empty());
+}
+
+#[test]
+fn a_block_across_a_window_boundary_is_kept_clear() {
+    for seed in 0..20 {
+        let result = draw([10, 10], (28, 33), seed).unwrap();
+        assert_eq!(result.len(), 20);
+        assert_clear_of(&result, (28, 33));
+    }
+}
+
+#[test]
+fn a_block_opening_a_window_delays_its_first_start() {
+    for seed in 0..20 {
+        let result = draw([8, 8], (30, 34), seed).unwrap();
+        assert_clear_of(&result, (30, 34));
+        let first = result.iter().find(|c| c.window == 1).unwrap();
+        assert!(first.offset >= 34);
+    }
+}
+
+#[test]
+fn a_block_closing_the_event_keeps_the_last_starts_before_it() {
+    for seed in 0..20 {
+        let result = draw([8, 12], (54, 60), seed).unwrap();
+        assert_eq!(result.len(), 20);
+        assert_clear_of(&result, (54, 60));
+    }
+}
+
+#[test]
+fn a_block_leaving_too_little_of_a_window_is_an_overrun() {
+    // the second window keeps minutes 45 to 59, room for eight starts
+    let error = draw([12, 12], (28, 45), 1).unwrap_err();
+    assert!(matches!(
+        error,
+        StartListError::WindowOverrun { window: 1, .. }
+    ));
+}
@@ -0,0 +1,128 @@
+use chrono::NaiveTime;
+use start_list_generator::cards::{start_cards_html, CardOptions};
+use start_list_generator::timefmt::TimeUnit;
+use start_list_generator::{Competitor, CompetitorWithOffset, Minutes};
+
+fn start(
+    name: &str,
+    category: Option<&str>,
+    offset: Minutes,
+    window: usize,
+) -> CompetitorWithOffset {
+    let mut competitor = Competitor::new(name);
+    competitor.category = category.map(str::to_string);
+    CompetitorWithOffset {
+        competitor,
+        offset,
+        window,
+    }
+}
+
+// handed over out of clock order, as a caller may
+fn start_list() -> Vec<CompetitorWithOffset> {
+    vec![
+        start("Cilla", Some("W21"), 40, 1),
+        start("Anna", Some("W21"), 0, 0),
+        start("Dan", None, 33, 1),
+        start("Bo", Some("M21"), 3, 0),
+    ]
+}
+
+fn nine() -> NaiveTime {
+    NaiveTime::from_hms_opt(9, 0, 0).unwrap()
+}
+
+fn cards(html: &str) -> Vec<&str> {
+    html.split("<div class=\"card\">").skip(1).collect()
+}
+
+#[test]
+fn every_competitor_gets_a_card_in_clock_order() {
+    let html = start_cards_html(&start_list(), nine(), &CardOptions::default());
+    let cards = cards(&html);
+
+    assert_eq!(cards.len(), 4);
+    for (card, (name, clock)) in cards.iter().zip([
+        ("Anna", "09:00"),
+        ("Bo", "09:03"),
+        ("Dan", "09:33"),
+        ("Cilla", "09:40"),
+    ]) {
+        assert!(card.contains(&format!(">{}<", name)), "{}", card);
+        assert!(
+            card.contains(&format!("<div class=\"start\">{}<", clock)),
+            "{}",
+            card
+        );
+    }
+}
+
+#[test]
+fn cards_fill_a_sheet_of_eight_before_the_next() {
+    let result: Vec<CompetitorWithOffset> = (0..9)
+        .map(|i| start(&format!("Runner {}", i), None, i * 2, 0))
+        .collect();
+    let html = start_cards_html(&result, nine(), &CardOptions::default());
+
+    assert_eq!(html.matches("<div class=\"sheet\">").count(), 2);
+    assert_eq!(cards(&html).len(), 9);
+}
+
+#[test]
+fn a_card_shows_class_bib_call_up_and_map_scale() {
+    let options = CardOptions {
+        call_up: Some(5),
+        map_scale: Some(10000),
+        ..CardOptions::default()
+    };
+    let html = start_cards_html(&start_list(), nine(), &options);
+    let cards = cards(&html);
+
+    assert!(cards[1].contains("<span class=\"class\">M21</span>"));
+    assert!(cards[1].contains("<span class=\"bib\">Bib 2</span>"));
+    assert!(cards[1].contains("<span class=\"map-scale\">Map 1:10000</span>"));
+    assert!(cards[1].contains("Call-up 08:58"));
+    // no category, no class
+    assert!(!cards[2].contains("class=\"class\""));
+    assert!(!start_cards_html(&start_list(), nine(), &CardOptions::default()).contains("Map 1:"));
+}
+
+#[test]
+fn one_windows_cards_keep_their_bibs_from_the_whole_list() {
+    let options = CardOptions {
+        only_window: Some(1),
+        ..CardOptions::default()
+    };
+    let html = start_cards_html(&start_list(), nine(), &options);
+    let cards = cards(&html);
+
+    assert_eq!(cards.len(), 2);
+    assert!(cards[0].contains(">Dan<") && cards[0].contains("Bib 3"));
+    assert!(cards[1].contains(">Cilla<") && cards[1].contains("Bib 4"));
+}
+
+#[test]
+fn names_and_classes_are_escaped() {
+    let result = vec![start("<b>Bo</b> & \"Ek\" O'Neil", Some("H<21>"), 0, 0)];
+    let html = start_cards_html(&result, nine(), &CardOptions::default());
+
+    assert!(html.contains("&lt;b&gt;Bo&lt;/b&gt; &amp; &quot;Ek&quot; O&#39;Neil"));
+    assert!(html.contains("H&lt;21&gt;"));
+    assert!(!html.contains("<b>"));
+}
+
+#[test]
+fn the_call_up_counts_the_time_unit_and_defaults_to_three_minutes() {
+    let result = vec![start("Anna", None, 600, 0)];
+    let seconds = CardOptions {
+        time_unit: TimeUnit::Seconds,
+        ..CardOptions::default()
+    };
+    assert!(start_cards_html(&result, nine(), &seconds).contains("Call-up 09:07<"));
+
+    let options = CardOptions {
+        call_up: Some(30),
+        ..seconds
+    };
+    assert!(start_cards_html(&result, nine(), &options).contains("Call-up 09:09:30<"));
+}
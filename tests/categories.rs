@@ -0,0 +1,148 @@
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+use start_list_generator::config::EventConfig;
+use start_list_generator::{
+    category_alternation, try_generate_draw_with_rng, try_generate_startlist_with_rng, Competitor,
+    CompetitorWithOffset, Window,
+};
+
+// `men` then `women`, entered in that order
+fn window(men: usize, women: usize, others: usize) -> Window {
+    let entrant = |name: String, category: &str| {
+        let mut competitor = Competitor::new(name);
+        competitor.category = Some(category.to_string());
+        competitor
+    };
+    Window::new(
+        90,
+        (0..men)
+            .map(move |i| entrant(format!("M{}", i), "M"))
+            .chain((0..women).map(move |i| entrant(format!("W{}", i), "W")))
+            .chain((0..others).map(move |i| entrant(format!("J{}", i), "J"))),
+    )
+}
+
+fn config() -> EventConfig {
+    EventConfig::builder()
+        .spacing_threshold(3)
+        .min_spacing(2)
+        .alternate_categories(true)
+        .build()
+        .unwrap()
+}
+
+fn categories(window: &Window) -> String {
+    window
+        .competitors
+        .iter()
+        .map(|c| c.category.as_deref().unwrap())
+        .collect()
+}
+
+#[test]
+fn two_equal_categories_alternate_throughout() {
+    for seed in 0..5 {
+        let saved = try_generate_draw_with_rng(
+            vec![window(4, 4, 0)],
+            &config(),
+            &mut StdRng::seed_from_u64(seed),
+        )
+        .unwrap();
+        let order = categories(&saved.windows[0]);
+        assert!(order == "MWMWMWMW" || order == "WMWMWMWM", "{}", order);
+    }
+}
+
+#[test]
+fn the_larger_category_leads_and_its_remainder_starts_last() {
+    for seed in 0..5 {
+        let saved = try_generate_draw_with_rng(
+            vec![window(2, 5, 0)],
+            &config(),
+            &mut StdRng::seed_from_u64(seed),
+        )
+        .unwrap();
+        assert_eq!(categories(&saved.windows[0]), "WMWMWWW");
+    }
+}
+
+#[test]
+fn each_category_keeps_its_shuffled_order() {
+    let saved = try_generate_draw_with_rng(
+        vec![window(3, 5, 0)],
+        &config(),
+        &mut StdRng::seed_from_u64(4),
+    )
+    .unwrap();
+    let drawn = &saved.witnesses[0].drawn_order;
+    for category in ["M", "W"] {
+        let shuffled: Vec<&String> = drawn
+            .iter()
+            .filter(|name| name.starts_with(category))
+            .collect();
+        let alternated: Vec<&String> = saved.windows[0]
+            .competitors
+            .iter()
+            .map(|c| &c.name)
+            .filter(|name| name.starts_with(category))
+            .collect();
+        assert_eq!(alternated, shuffled);
+    }
+}
+
+#[test]
+fn a_window_with_more_than_two_categories_keeps_the_shuffle() {
+    let saved = try_generate_draw_with_rng(
+        vec![window(3, 3, 2)],
+        &config(),
+        &mut StdRng::seed_from_u64(4),
+    )
+    .unwrap();
+    let names: Vec<&String> = saved.windows[0]
+        .competitors
+        .iter()
+        .map(|c| &c.name)
+        .collect();
+    assert_eq!(
+        names,
+        saved.witnesses[0].drawn_order.iter().collect::<Vec<_>>()
+    );
+}
+
+#[test]
+fn the_alternation_is_counted_over_the_start_list() {
+    let alternation = |men, women| {
+        let result = try_generate_startlist_with_rng(
+            vec![window(men, women, 0)],
+            &config(),
+            &mut StdRng::seed_from_u64(2),
+        )
+        .unwrap();
+        category_alternation(&result)
+    };
+    assert_eq!(alternation(4, 4), (7, 7));
+    // W M W M W W W
+    assert_eq!(alternation(2, 5), (4, 6));
+    assert_eq!(alternation(1, 0), (0, 0));
+}
+
+#[test]
+fn uncategorised_competitors_count_as_one_category() {
+    let start = |name: &str, category: Option<&str>, offset| {
+        let mut competitor = Competitor::new(name);
+        competitor.category = category.map(str::to_string);
+        CompetitorWithOffset {
+            competitor,
+            offset,
+            window: 0,
+        }
+    };
+    // counted in clock order, not slice order
+    let result = [
+        start("C", None, 6),
+        start("A", None, 0),
+        start("B", Some("W"), 3),
+        start("D", None, 9),
+    ];
+    assert_eq!(category_alternation(&result), (2, 3));
+}
@@ -0,0 +1,111 @@
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+use start_list_generator::config::{ConfigError, DisplacementSelection, EventConfig};
+use start_list_generator::{try_generate_draw_with_rng, Competitor, Diagnostic, Window};
+
+// the twenty entries of one club, with others, over three windows
+fn windows() -> Vec<Window> {
+    [(6, 10), (4, 4), (10, 2)]
+        .into_iter()
+        .enumerate()
+        .map(|(number, (bus, others))| {
+            let bus = (0..bus).map(move |i| {
+                let mut competitor = Competitor::new(format!("{} Bus {}", number + 1, i));
+                competitor.club = Some("Bus".to_string());
+                competitor
+            });
+            let others =
+                (0..others).map(move |i| Competitor::new(format!("{} Other {}", number + 1, i)));
+            Window::new(30, bus.chain(others))
+        })
+        .collect()
+}
+
+fn config(cap: Option<usize>, selection: DisplacementSelection) -> EventConfig {
+    let builder = EventConfig::builder()
+        .spacing_threshold(3)
+        .min_spacing(1)
+        .displacement_selection(selection);
+    match cap {
+        Some(cap) => builder.max_per_club_per_window(cap),
+        None => builder,
+    }
+    .build()
+    .unwrap()
+}
+
+fn bus_counts(windows: &[Window]) -> Vec<usize> {
+    windows
+        .iter()
+        .map(|w| {
+            w.competitors
+                .iter()
+                .filter(|c| c.club.as_deref() == Some("Bus"))
+                .count()
+        })
+        .collect()
+}
+
+#[test]
+fn stabilization_moves_nobody_past_their_club_cap() {
+    for selection in [
+        DisplacementSelection::DequeEnds,
+        DisplacementSelection::Random,
+        DisplacementSelection::Volunteer,
+    ] {
+        for seed in 0..20 {
+            let config = config(Some(6), selection);
+            let saved =
+                try_generate_draw_with_rng(windows(), &config, &mut StdRng::seed_from_u64(seed))
+                    .unwrap();
+            let counts = bus_counts(&saved.windows);
+            assert!(counts[0] <= 6 && counts[1] <= 6, "{:?}", counts);
+            // the window entered above the cap only ever gives competitors away
+            assert!(counts[2] <= 10, "{:?}", counts);
+            // the crowded first window still gives some away
+            assert!(saved.windows[0].competitors.len() < 16);
+        }
+    }
+}
+
+#[test]
+fn without_a_cap_the_club_crowds_a_window() {
+    let saved = try_generate_draw_with_rng(
+        windows(),
+        &config(None, DisplacementSelection::DequeEnds),
+        &mut StdRng::seed_from_u64(3),
+    )
+    .unwrap();
+    assert!(bus_counts(&saved.windows).iter().any(|&count| count > 6));
+    assert!(saved.diagnostics.is_empty());
+}
+
+#[test]
+fn entries_above_the_cap_get_a_suggested_redistribution() {
+    let config = config(Some(6), DisplacementSelection::DequeEnds);
+    let saved =
+        try_generate_draw_with_rng(windows(), &config, &mut StdRng::seed_from_u64(1)).unwrap();
+    // the second window has room for two more, the first for none
+    assert_eq!(
+        saved.diagnostics,
+        [Diagnostic::ClubCapExceeded {
+            window: 2,
+            club: "Bus".to_string(),
+            entries: 10,
+            cap: 6,
+            suggestion: vec![(1, 2)],
+        }]
+    );
+    assert_eq!(
+        saved.diagnostics[0].to_string(),
+        "window 3 has 10 entries from Bus, above the cap of 6; move 2 to window 2"
+    );
+}
+
+#[test]
+fn a_cap_of_zero_is_rejected() {
+    assert!(matches!(
+        EventConfig::builder().max_per_club_per_window(0).build(),
+        Err(ConfigError::ZeroClubCap)
+    ));
+}
@@ -0,0 +1,112 @@
+mod common;
+
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+use start_list_generator::config::EventConfig;
+use start_list_generator::input::windows_from_csv;
+use start_list_generator::{
+    club_spacing_violations, try_generate_startlist_with_rng, Competitor, Window,
+};
+
+use common::builder;
+
+fn member(name: &str, club: &str) -> Competitor {
+    let mut competitor = Competitor::new(name);
+    competitor.club = Some(club.to_string());
+    competitor
+}
+
+fn config(same_club_min_spacing: isize) -> EventConfig {
+    builder()
+        .same_club_min_spacing(same_club_min_spacing)
+        .build()
+        .unwrap()
+}
+
+#[test]
+fn clubmates_are_swapped_apart_in_the_shuffled_order() {
+    // 6 starts 3 minutes apart; clubmates must not be neighbours
+    let clubs = ["OK Ravinen", "IFK Lidingö"];
+    for seed in 0..30 {
+        let windows = vec![Window::new(
+            18,
+            (0..6).map(|i| member(&format!("Runner {}", i), clubs[i % 2])),
+        )];
+        let result =
+            try_generate_startlist_with_rng(windows, &config(4), &mut StdRng::seed_from_u64(seed))
+                .unwrap();
+
+        assert_eq!(result.len(), 6);
+        assert!(
+            club_spacing_violations(&result, 4).is_empty(),
+            "seed {}",
+            seed
+        );
+    }
+}
+
+#[test]
+fn a_window_dominated_by_one_club_reports_what_it_cannot_separate() {
+    let windows = vec![Window::new(
+        10,
+        vec![
+            member("Anna", "OK Ravinen"),
+            member("Bo", "OK Ravinen"),
+            member("Cecilia", "OK Ravinen"),
+            member("David", "OK Ravinen"),
+            member("Erik", "IFK Lidingö"),
+        ],
+    )];
+    let result =
+        try_generate_startlist_with_rng(windows, &config(3), &mut StdRng::seed_from_u64(1))
+            .unwrap();
+
+    assert_eq!(result.len(), 5);
+    let violations = club_spacing_violations(&result, 3);
+    assert!(!violations.is_empty());
+    assert!(violations
+        .iter()
+        .all(|(a, b)| a.competitor.club.as_deref() == Some("OK Ravinen")
+            && b.competitor.club == a.competitor.club));
+}
+
+#[test]
+fn the_draw_is_unchanged_without_clubs_or_spacing() {
+    let windows = || {
+        vec![
+            Window::new(20, (0..8).map(|i| Competitor::new(format!("Runner {}", i)))),
+            Window::new(
+                10,
+                (8..12).map(|i| Competitor::new(format!("Runner {}", i))),
+            ),
+        ]
+    };
+    let draw = |same_club_min_spacing| {
+        try_generate_startlist_with_rng(
+            windows(),
+            &config(same_club_min_spacing),
+            &mut StdRng::seed_from_u64(7),
+        )
+        .unwrap()
+        .into_iter()
+        .map(|c| (c.competitor.name, c.offset))
+        .collect::<Vec<_>>()
+    };
+    assert_eq!(draw(0), draw(5));
+}
+
+#[test]
+fn clubs_come_from_the_windows_csv() {
+    let windows = windows_from_csv(
+        "window_id,duration,competitor_name,club\n\
+         a,20,Anna Berg,OK Ravinen\n\
+         a,20,Bo Ek,\n",
+    )
+    .unwrap();
+    let clubs: Vec<Option<&str>> = windows[0]
+        .competitors
+        .iter()
+        .map(|c| c.club.as_deref())
+        .collect();
+    assert_eq!(clubs, [Some("OK Ravinen"), None]);
+}
@@ -0,0 +1,22 @@
+// helpers shared by the integration tests; each test crate uses only some
+#![allow(dead_code)]
+
+use start_list_generator::config::{EventConfig, EventConfigBuilder};
+use start_list_generator::{Competitor, Minutes, Window};
+
+/// `count` competitors named "NUMBER Competitor I", in entry order.
+pub fn window(duration: Minutes, number: usize, count: usize) -> Window {
+    Window::new(
+        duration,
+        (0..count).map(|i| Competitor::new(format!("{} Competitor {}", number, i))),
+    )
+}
+
+/// The binary's default spacing: a threshold of 3 and min_spacing 2.
+pub fn builder() -> EventConfigBuilder {
+    EventConfig::builder().spacing_threshold(3).min_spacing(2)
+}
+
+pub fn config() -> EventConfig {
+    builder().build().unwrap()
+}
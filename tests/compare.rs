@@ -0,0 +1,65 @@
+mod common;
+
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+use start_list_generator::compare::compare_configs;
+use start_list_generator::config::EventConfig;
+use start_list_generator::{try_generate_startlist_with_rng, Minutes, Window};
+
+use common::window;
+
+fn interval(minutes: Minutes) -> EventConfig {
+    EventConfig::builder()
+        .spacing_threshold(minutes)
+        .min_spacing(minutes)
+        .build()
+        .unwrap()
+}
+
+// 25 starts fit the first hour at 2 minute intervals, but only 20 at 3
+fn entries() -> Vec<Window> {
+    vec![window(60, 1, 25), window(60, 2, 0)]
+}
+
+fn last_start(config: &EventConfig, seed: u64) -> Minutes {
+    try_generate_startlist_with_rng(entries(), config, &mut StdRng::seed_from_u64(seed))
+        .unwrap()
+        .iter()
+        .map(|c| c.offset)
+        .max()
+        .unwrap()
+}
+
+#[test]
+fn the_report_captures_the_last_start_difference() {
+    let (two, three) = (interval(2), interval(3));
+    let report = compare_configs(&entries(), &two, &three, 5).unwrap();
+    assert_eq!(report.seed, 5);
+    assert_eq!(report.a.last_start, Some(last_start(&two, 5)));
+    assert_eq!(report.b.last_start, Some(last_start(&three, 5)));
+    let difference = report.last_start_difference().unwrap();
+    assert!(difference > 0, "{:?}", report);
+
+    assert_eq!((report.a.starts, report.b.starts), (25, 25));
+    assert!(report.a.min_gap.unwrap() >= 2);
+    assert!(report.b.min_gap.unwrap() >= 3);
+    assert_eq!(report.a.moved, 0);
+    assert!(report.b.moved >= 5, "{:?}", report.b);
+    assert_eq!(report.a.spacing_violations + report.b.spacing_violations, 0);
+    // the same seed draws the same order, so only the times differ
+    assert_eq!(report.offsets.len(), 25);
+    assert!(report.changed().count() > 0);
+
+    let json = report.to_json();
+    assert!(json.contains(&format!("\"last_start\": {}", report.a.last_start.unwrap())));
+    assert!(json.contains(&format!("\"last_start\": {}", report.b.last_start.unwrap())));
+    assert!(json.contains("{\"name\": \"1 Competitor"));
+}
+
+#[test]
+fn the_same_config_twice_changes_nothing() {
+    let report = compare_configs(&entries(), &interval(2), &interval(2), 9).unwrap();
+    assert_eq!(report.a, report.b);
+    assert_eq!(report.last_start_difference(), Some(0));
+    assert_eq!(report.changed().count(), 0);
+}
@@ -0,0 +1,175 @@
+mod common;
+
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+use start_list_generator::constraints::{
+    Constraint, Constraints, ExclusiveRadius, FixedSlot, LockedWindow, MinSpacing, StartList,
+};
+use start_list_generator::input::{parse_event, startlist_from_csv};
+use start_list_generator::output::{export_csv, export_draw, export_final_csv};
+use start_list_generator::timefmt::TimeUnit;
+use start_list_generator::{
+    reassign_offsets_with_rng, try_generate_draw_with_rng, Competitor, CompetitorWithOffset,
+    SavedDraw, Window,
+};
+
+use common::config;
+
+// a pinned start with an exclusive radius, and a locked window
+fn draw() -> SavedDraw {
+    let mut pinned = Competitor::new("Pinned");
+    pinned.fixed_offset = Some(10);
+    pinned.exclusive_radius = 4;
+    let mut early = Window::new(
+        30,
+        ["Anna", "Bo", "Cecilia"].into_iter().map(Competitor::new),
+    );
+    early.competitors.push_back(pinned);
+    let mut late = Window::new(20, ["Dan", "Eva"].into_iter().map(Competitor::new));
+    late.locked = true;
+    try_generate_draw_with_rng(vec![early, late], &config(), &mut StdRng::seed_from_u64(4)).unwrap()
+}
+
+fn start<'a>(list: &'a mut [CompetitorWithOffset], name: &str) -> &'a mut CompetitorWithOffset {
+    list.iter_mut().find(|c| c.competitor.name == name).unwrap()
+}
+
+#[test]
+fn a_saved_draw_records_its_constraints_and_reads_them_back() {
+    let saved = draw();
+    let constraints = Constraints::of_draw(&saved.windows);
+    assert_eq!(
+        constraints.fixed_slots,
+        [FixedSlot {
+            name: "Pinned".to_string(),
+            offset: 10
+        }]
+    );
+    assert_eq!(
+        constraints.exclusive_radii,
+        [ExclusiveRadius {
+            name: "Pinned".to_string(),
+            offset: 10,
+            radius: 4
+        }]
+    );
+    assert_eq!(constraints.locked_windows.len(), 1);
+    let locked: &LockedWindow = &constraints.locked_windows[0];
+    assert_eq!((locked.window, locked.from, locked.to), (1, 30, 50));
+    let mut names = locked.names.clone();
+    names.sort();
+    assert_eq!(names, ["Dan", "Eva"]);
+    assert_eq!(
+        constraints
+            .all()
+            .iter()
+            .map(|c| c.to_string())
+            .collect::<Vec<_>>(),
+        ["Pinned@10", "Pinned@10~4", "2@30-50"]
+    );
+
+    let text = export_draw(&saved, &config());
+    assert!(text.contains("[constraints]\nversion = 1\n"));
+    assert_eq!(parse_event(&text).unwrap().constraints, Some(constraints));
+}
+
+#[test]
+fn an_unknown_constraints_version_is_an_error() {
+    let text = export_draw(&draw(), &config()).replace("version = 1", "version = 2");
+    let error = parse_event(&text).err().unwrap();
+    assert!(error.to_string().contains("version 2"), "{}", error);
+}
+
+#[test]
+fn an_edited_list_is_checked_against_every_kind() {
+    let saved = draw();
+    let constraints = Constraints::of_draw(&saved.windows);
+    let result =
+        reassign_offsets_with_rng(&saved, &config(), &mut StdRng::seed_from_u64(1)).unwrap();
+    // as the timing company would send it back
+    let csv = export_csv(&result, config().start_time, TimeUnit::Minutes);
+    let mut list = startlist_from_csv(&csv, TimeUnit::Minutes).unwrap();
+    assert!(constraints.check(&list).is_empty());
+
+    start(&mut list, "Pinned").offset = 12;
+    let next_to_pinned = list
+        .iter()
+        .filter(|c| c.window == 0 && c.competitor.name != "Pinned")
+        .map(|c| c.competitor.name.clone())
+        .next()
+        .unwrap();
+    start(&mut list, &next_to_pinned).offset = 8;
+    start(&mut list, "Dan").offset = 28;
+
+    let messages: Vec<String> = constraints
+        .check(&list)
+        .iter()
+        .map(|v| v.to_string())
+        .collect();
+    assert_eq!(
+        messages,
+        [
+            "Pinned@10: Pinned starts at 12".to_string(),
+            format!("Pinned@10~4: {} starts at 8", next_to_pinned),
+            "2@30-50: Dan starts outside it, at 28".to_string(),
+        ]
+    );
+
+    let mut list = startlist_from_csv(&csv, TimeUnit::Minutes).unwrap();
+    start(&mut list, &next_to_pinned).offset = 40;
+    let violations = constraints.locked_windows[0].check(&list);
+    assert_eq!(violations.len(), 1);
+    assert_eq!(
+        violations[0].message,
+        format!("{} was moved in, at 40", next_to_pinned)
+    );
+}
+
+#[test]
+fn a_list_breaking_its_checks_is_refused_as_final() {
+    let saved = draw();
+    let constraints = Constraints::of_draw(&saved.windows);
+    let min_spacing = MinSpacing {
+        min_spacings: vec![2, 2],
+    };
+    let mut checks = constraints.all();
+    checks.push(&min_spacing);
+    let mut result =
+        reassign_offsets_with_rng(&saved, &config(), &mut StdRng::seed_from_u64(1)).unwrap();
+    start(&mut result, "Eva").offset = start(&mut result, "Dan").offset + 1;
+
+    let violations = StartList::new(result.clone())
+        .finalize(&checks)
+        .err()
+        .unwrap();
+    assert_eq!(violations.len(), 1);
+    assert_eq!(violations[0].constraint, "min_spacing");
+    assert!(
+        violations[0].message.ends_with(" start 1 apart"),
+        "{}",
+        violations[0]
+    );
+
+    // forced, it is final all the same, and says what it broke
+    let (list, forced) = StartList::new(result.clone()).finalize_forced(&checks);
+    assert_eq!(forced, violations);
+    assert_eq!(
+        export_final_csv(&list, config().start_time, TimeUnit::Minutes, &[]),
+        export_csv(&result, config().start_time, TimeUnit::Minutes)
+    );
+}
+
+#[test]
+fn a_list_keeping_its_checks_is_final_as_drafted() {
+    let saved = draw();
+    let constraints = Constraints::of_draw(&saved.windows);
+    let result =
+        reassign_offsets_with_rng(&saved, &config(), &mut StdRng::seed_from_u64(1)).unwrap();
+    let draft = StartList::new(result.clone());
+    let list = draft.finalize(&constraints.all()).unwrap();
+    assert_eq!(list.starts().len(), result.len());
+    assert_eq!(
+        export_final_csv(&list, config().start_time, TimeUnit::Minutes, &[]),
+        export_csv(&result, config().start_time, TimeUnit::Minutes)
+    );
+}
@@ -0,0 +1,44 @@
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+use start_list_generator::config::EventConfig;
+use start_list_generator::demo::{demo_windows, DemoSize};
+use start_list_generator::{
+    spacing_violations, try_generate_startlist_with_rng, window_boundaries,
+};
+
+#[test]
+fn every_demo_size_draws_a_list_that_verifies() {
+    // the binary's default spacing, which the demo layouts are sized for
+    let config = EventConfig::builder()
+        .spacing_threshold(3)
+        .min_spacing(2)
+        .build()
+        .unwrap();
+    for (size, count) in [
+        (DemoSize::Small, 20),
+        (DemoSize::Medium, 200),
+        (DemoSize::Large, 2000),
+    ] {
+        let windows = demo_windows(size);
+        let entered: usize = windows.iter().map(|w| w.competitors.len()).sum();
+        assert_eq!(entered, count, "{:?}", size);
+        let boundaries = window_boundaries(&windows);
+        let min_spacings: Vec<_> = windows.iter().map(|w| w.min_spacing(&config)).collect();
+
+        let result =
+            try_generate_startlist_with_rng(windows, &config, &mut StdRng::seed_from_u64(1))
+                .unwrap_or_else(|error| panic!("{:?}: {}", size, error));
+        assert_eq!(result.len(), count, "{:?}", size);
+        assert!(
+            spacing_violations(&result, &min_spacings).is_empty(),
+            "{:?}",
+            size
+        );
+        let event_end = boundaries.last().unwrap().1;
+        assert!(
+            result.iter().all(|c| (0..event_end).contains(&c.offset)),
+            "{:?}",
+            size
+        );
+    }
+}
@@ -0,0 +1,114 @@
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+use start_list_generator::config::EventConfig;
+use start_list_generator::duty::duty_conflicts;
+use start_list_generator::input::parse_event;
+use start_list_generator::{
+    spacing_violations, try_generate_startlist_with_rng, Competitor, CompetitorWithOffset, Window,
+};
+
+// ten starts three minutes apart, one of them from the duty club
+fn window(norr: usize) -> Window {
+    let mut window = Window::new(
+        30,
+        (0..10).map(|i| {
+            let mut competitor = Competitor::new(format!("Competitor {}", i));
+            if i < norr {
+                competitor.club = Some("OK Norr".to_string());
+            }
+            competitor
+        }),
+    );
+    window.locked = true;
+    window
+}
+
+// OK Norr staffs the start for the first ten minutes
+fn config(repair: bool) -> EventConfig {
+    EventConfig::builder()
+        .spacing_threshold(3)
+        .min_spacing(2)
+        .duty_shift("OK Norr", 0, 10)
+        .call_up(2)
+        .repair_duty_shifts(repair)
+        .build()
+        .unwrap()
+}
+
+fn draw(norr: usize, config: &EventConfig, seed: u64) -> Vec<CompetitorWithOffset> {
+    try_generate_startlist_with_rng(vec![window(norr)], config, &mut StdRng::seed_from_u64(seed))
+        .unwrap()
+}
+
+#[test]
+fn starts_and_call_ups_inside_the_shift_are_found() {
+    let config = config(false);
+    for seed in 0..20 {
+        let result = draw(1, &config, seed);
+        let norr = result.iter().find(|c| c.competitor.club.is_some()).unwrap();
+        // called up two minutes before a start at minute 11 is still on duty
+        let expected = norr.offset < 12;
+        assert_eq!(duty_conflicts(&result, &config).len(), expected as usize);
+    }
+}
+
+#[test]
+fn a_conflict_is_swapped_out_of_the_shift() {
+    let (plain, repairing) = (config(false), config(true));
+    let mut repaired_any = false;
+    for seed in 0..20 {
+        let before = draw(1, &plain, seed);
+        let after = draw(1, &repairing, seed);
+        assert!(
+            duty_conflicts(&after, &repairing).is_empty(),
+            "seed {}",
+            seed
+        );
+        repaired_any |= !duty_conflicts(&before, &plain).is_empty();
+        // the offsets are the same, only who starts in them changed
+        let offsets = |list: &[CompetitorWithOffset]| -> Vec<isize> {
+            list.iter().map(|c| c.offset).collect()
+        };
+        assert_eq!(offsets(&before), offsets(&after));
+        assert!(spacing_violations(&after, &[2]).is_empty());
+    }
+    assert!(repaired_any);
+}
+
+#[test]
+fn a_window_of_only_the_duty_club_stays_a_warning() {
+    let config = config(true);
+    let result = draw(10, &config, 1);
+    // minutes 0 to 11 hold four starts, and nobody can take them
+    assert_eq!(duty_conflicts(&result, &config).len(), 4);
+}
+
+#[test]
+fn the_event_file_lists_the_duty_shifts() {
+    let event = parse_event(
+        "start_time = \"09:00\"\n\
+         duty_shifts = [\"OK Norr@10:00-11:00\", \"IFK Lidingö@09:30-10:15\"]\n\
+         call_up = 3\n\
+         [[window]]\n\
+         duration = 30\n",
+    )
+    .unwrap();
+    let shifts: Vec<(&str, String, String)> = event
+        .duty_shifts
+        .iter()
+        .map(|(club, from, to)| (club.as_str(), from.to_string(), to.to_string()))
+        .collect();
+    assert_eq!(
+        shifts,
+        [
+            ("OK Norr", "10:00:00".to_string(), "11:00:00".to_string()),
+            (
+                "IFK Lidingö",
+                "09:30:00".to_string(),
+                "10:15:00".to_string()
+            )
+        ]
+    );
+    assert_eq!(event.call_up, Some(3));
+    assert!(parse_event("duty_shifts = [\"OK Norr\"]\n[[window]]\nduration = 30\n").is_err());
+}
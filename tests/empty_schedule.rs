@@ -0,0 +1,112 @@
+// the seedless API is left out of no-entropy builds
+#![cfg(not(feature = "no-entropy"))]
+
+use chrono::{NaiveDate, NaiveTime};
+use start_list_generator::announcer::{announcer_script, AnnouncerOptions};
+use start_list_generator::cards::{start_cards_html, CardOptions};
+use start_list_generator::config::EventConfig;
+use start_list_generator::output::{export_csv, export_iof_xml};
+use start_list_generator::timefmt::TimeUnit;
+use start_list_generator::{
+    category_alternation, generate_startlist, spacing_violations, try_generate_draw,
+    try_generate_startlist, window_boundaries, Competitor, Diagnostic, StartListError, Window,
+};
+
+fn nine() -> NaiveTime {
+    NaiveTime::from_hms_opt(9, 0, 0).unwrap()
+}
+
+fn empty_windows() -> Vec<Window> {
+    vec![Window::new(30, []), Window::new(20, [])]
+}
+
+fn config_with_opening() -> EventConfig {
+    EventConfig::builder()
+        .ceremonial_start("Opening", 10)
+        .build()
+        .unwrap()
+}
+
+#[test]
+fn no_entries_still_keeps_the_ceremonial_starts() {
+    for result in [
+        generate_startlist(empty_windows(), &config_with_opening()),
+        try_generate_startlist(empty_windows(), &config_with_opening()).unwrap(),
+    ] {
+        assert_eq!(result.len(), 1);
+        assert!(result[0].competitor.ceremonial);
+        assert_eq!((result[0].offset, result[0].window), (10, 0));
+    }
+}
+
+#[test]
+fn no_entries_at_all_gives_an_empty_list_and_the_timeline() {
+    let windows = empty_windows();
+    assert_eq!(window_boundaries(&windows), [(0, 30), (30, 50)]);
+    let result = try_generate_startlist(windows, &EventConfig::builder().build().unwrap()).unwrap();
+    assert!(result.is_empty());
+    assert!(spacing_violations(&result, &[2, 2]).is_empty());
+    assert_eq!(category_alternation(&result), (0, 0));
+}
+
+#[test]
+fn renderers_handle_a_schedule_without_entries() {
+    let result = try_generate_startlist(empty_windows(), &config_with_opening()).unwrap();
+
+    let html = start_cards_html(&result, nine(), &CardOptions::default());
+    assert_eq!(html.matches("class=\"card\"").count(), 1);
+    assert!(html.contains(">Opening<") && html.contains(">09:10<"));
+    let script = announcer_script(&result, nine(), &AnnouncerOptions::default());
+    assert!(script.contains("Opening"));
+    let csv = export_csv(&result, nine(), TimeUnit::Minutes);
+    assert_eq!(csv.lines().count(), 2);
+    let xml = export_iof_xml(
+        &result,
+        nine(),
+        NaiveDate::from_ymd_opt(2024, 5, 18).unwrap(),
+        TimeUnit::Minutes,
+    );
+    // the ceremonial start is in no class's results
+    assert_eq!(xml.matches("<PersonStart>").count(), 0);
+
+    let html = start_cards_html(&[], nine(), &CardOptions::default());
+    assert!(html.ends_with("</body>\n</html>\n"));
+    assert_eq!(
+        export_csv(&[], nine(), TimeUnit::Minutes).lines().count(),
+        1
+    );
+}
+
+#[test]
+fn an_empty_event_is_drawn_with_a_diagnostic() {
+    let saved = try_generate_draw(empty_windows(), &config_with_opening()).unwrap();
+    assert_eq!(saved.diagnostics, [Diagnostic::EmptyEvent]);
+
+    let config = EventConfig::builder()
+        .sweeper("Sweeper", 1)
+        .build()
+        .unwrap();
+    let saved = try_generate_draw(empty_windows(), &config).unwrap();
+    assert!(saved.diagnostics.is_empty());
+}
+
+#[test]
+fn an_empty_event_can_be_made_an_error() {
+    let config = EventConfig::builder()
+        .ceremonial_start("Opening", 10)
+        .empty_event_is_error(true)
+        .build()
+        .unwrap();
+    for result in [
+        try_generate_startlist(empty_windows(), &config).map(|_| ()),
+        try_generate_draw(empty_windows(), &config).map(|_| ()),
+    ] {
+        assert!(matches!(result, Err(StartListError::NoCompetitors)));
+    }
+
+    let windows = vec![
+        Window::new(30, [Competitor::new("Anna")]),
+        Window::new(20, []),
+    ];
+    assert_eq!(try_generate_startlist(windows, &config).unwrap().len(), 2);
+}
@@ -0,0 +1,52 @@
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+use start_list_generator::config::EventConfig;
+use start_list_generator::demo::example_windows;
+use start_list_generator::{try_generate_startlist_with_rng, Minutes};
+
+// the example the binary draws without arguments, at its default spacing;
+// a change to this list changes every seeded draw, so it should be on purpose
+const SNAPSHOT: [(&str, Minutes); 21] = [
+    ("1 Competitor 0", 0),
+    ("1 Competitor 1", 9),
+    ("2 Competitor 0", 20),
+    ("2 Competitor 7", 23),
+    ("2 Competitor 5", 26),
+    ("2 Competitor 14", 29),
+    ("2 Competitor 6", 32),
+    ("2 Competitor 3", 35),
+    ("2 Competitor 8", 38),
+    ("2 Competitor 2", 41),
+    ("2 Competitor 10", 44),
+    ("2 Competitor 12", 47),
+    ("2 Competitor 1", 50),
+    ("2 Competitor 13", 53),
+    ("2 Competitor 9", 56),
+    ("2 Competitor 4", 60),
+    ("2 Competitor 11", 63),
+    ("3 Competitor 3", 70),
+    ("3 Competitor 0", 76),
+    ("3 Competitor 1", 83),
+    ("3 Competitor 2", 89),
+];
+
+#[test]
+fn the_example_draws_the_same_list_for_a_fixed_seed() {
+    let config = EventConfig::builder()
+        .spacing_threshold(3)
+        .min_spacing(2)
+        .build()
+        .unwrap();
+    let result = try_generate_startlist_with_rng(
+        example_windows(),
+        &config,
+        &mut StdRng::seed_from_u64(2023),
+    )
+    .unwrap();
+
+    let drawn: Vec<(&str, Minutes)> = result
+        .iter()
+        .map(|c| (c.competitor.name.as_str(), c.offset))
+        .collect();
+    assert_eq!(drawn, SNAPSHOT);
+}
@@ -0,0 +1,148 @@
+use start_list_generator::config::EventConfig;
+use start_list_generator::filter::{Comparison, Filter, StartFlag};
+use start_list_generator::output::{export_csv, export_csv_selected};
+use start_list_generator::timefmt::TimeUnit;
+use start_list_generator::{Competitor, CompetitorWithOffset, Minutes};
+
+fn config() -> EventConfig {
+    EventConfig::builder().build().unwrap()
+}
+
+fn start(
+    name: &str,
+    club: &str,
+    category: &str,
+    offset: Minutes,
+    window: usize,
+) -> CompetitorWithOffset {
+    let mut competitor = Competitor::new(name);
+    competitor.club = Some(club.to_string());
+    competitor.category = Some(category.to_string());
+    CompetitorWithOffset {
+        competitor,
+        offset,
+        window,
+    }
+}
+
+// 09:00 start; an hour in two windows
+fn list() -> Vec<CompetitorWithOffset> {
+    let mut pinned = start("Pinned", "IFK Lidingö", "M21", 40, 1);
+    pinned.competitor.fixed_offset = Some(40);
+    vec![
+        start("Anna", "OK Linné", "W21", 0, 0),
+        start("Bo", "IFK Lidingö", "M21", 10, 0),
+        start("Cecilia", "OK Linné", "W21", 20, 0),
+        start("Dan", "OK Linné", "M21", 30, 1),
+        pinned,
+        start("Eva", "IFK Lidingö", "W21", 70, 1),
+    ]
+}
+
+fn names(filter: &Filter) -> Vec<String> {
+    list()
+        .iter()
+        .filter(|c| filter.matches(c))
+        .map(|c| c.competitor.name.clone())
+        .collect()
+}
+
+#[test]
+fn and_binds_tighter_than_or() {
+    let config = config();
+    let parse = |expression| Filter::parse(expression, &config).unwrap();
+    let filter = parse("club=IFK Lidingö | club=OK Linné & time<09:15");
+    assert_eq!(
+        filter,
+        Filter::Club("IFK Lidingö".to_string())
+            .or(Filter::Club("OK Linné".to_string()).and(Filter::Time(Comparison::Before, 15)))
+    );
+    assert_eq!(names(&filter), ["Anna", "Bo", "Pinned", "Eva"]);
+    // parentheses group the other way
+    let grouped = parse("(club=IFK Lidingö | club=OK Linné) & time<09:15");
+    assert_eq!(names(&grouped), ["Anna", "Bo"]);
+
+    assert_eq!(names(&parse("window=2 & class=M21")), ["Dan", "Pinned"]);
+    assert_eq!(
+        names(&parse("flag=pinned | time>=10:00")),
+        ["Pinned", "Eva"]
+    );
+    assert_eq!(parse("flag = sweeper"), Filter::Flag(StartFlag::Sweeper));
+    assert_eq!(names(&parse("window=1,2 & time<=09:10")), ["Anna", "Bo"]);
+}
+
+#[test]
+fn positions_are_kept_unless_renumbered() {
+    let filter = Filter::parse("club=OK Linné", &config()).unwrap();
+    let positions = |renumber| {
+        export_csv_selected(
+            &list(),
+            |c| filter.matches(c),
+            renumber,
+            config().start_time,
+            TimeUnit::Minutes,
+        )
+        .lines()
+        .skip(1)
+        .map(|row| {
+            let fields: Vec<&str> = row.split(',').collect();
+            format!("{} {}", fields[0], fields[1])
+        })
+        .collect::<Vec<_>>()
+    };
+    assert_eq!(positions(false), ["1 Anna", "3 Cecilia", "4 Dan"]);
+    assert_eq!(positions(true), ["1 Anna", "2 Cecilia", "3 Dan"]);
+
+    let everyone = export_csv_selected(
+        &list(),
+        |_| true,
+        false,
+        config().start_time,
+        TimeUnit::Minutes,
+    );
+    assert_eq!(
+        everyone,
+        export_csv(&list(), config().start_time, TimeUnit::Minutes)
+    );
+}
+
+#[test]
+fn a_filter_matching_nobody_leaves_the_header() {
+    let filter = Filter::parse("club=OK Nobody", &config()).unwrap();
+    assert!(names(&filter).is_empty());
+    let csv = export_csv_selected(
+        &list(),
+        |c| filter.matches(c),
+        false,
+        config().start_time,
+        TimeUnit::Minutes,
+    );
+    assert_eq!(csv.lines().count(), 1);
+}
+
+#[test]
+fn parse_errors_point_at_the_position() {
+    let config = config();
+    let error = |expression| Filter::parse(expression, &config).unwrap_err();
+
+    let unknown = error("club=OK Linné & colour=red");
+    assert_eq!(unknown.position, 16);
+    assert!(
+        unknown.to_string().contains("unknown field 'colour'"),
+        "{}",
+        unknown
+    );
+    assert!(
+        unknown.to_string().ends_with("at position 17"),
+        "{}",
+        unknown
+    );
+
+    assert_eq!(error("club=").position, 5);
+    assert_eq!(error("time<9 o'clock").position, 5);
+    assert_eq!(error("window=0").position, 7);
+    assert_eq!(error("club<OK").position, 4);
+    assert_eq!(error("(club=OK").position, 8);
+    assert_eq!(error("club=OK) ").position, 7);
+    assert_eq!(error("& club=OK").position, 0);
+}
@@ -0,0 +1,134 @@
+use chrono::NaiveTime;
+use start_list_generator::announcer::commentary_sheet;
+use start_list_generator::config::{ConfigError, EventConfig, FinishEstimate};
+use start_list_generator::input::parse_event;
+use start_list_generator::output::{export_csv_with_finish, export_html};
+use start_list_generator::timefmt::TimeUnit;
+use start_list_generator::{Competitor, CompetitorWithOffset, Minutes};
+
+fn nine() -> NaiveTime {
+    NaiveTime::from_hms_opt(9, 0, 0).unwrap()
+}
+
+fn start(name: &str, class: Option<&str>, offset: Minutes) -> CompetitorWithOffset {
+    let mut competitor = Competitor::new(name);
+    competitor.category = class.map(str::to_string);
+    CompetitorWithOffset {
+        competitor,
+        offset,
+        window: 0,
+    }
+}
+
+fn estimates() -> Vec<FinishEstimate> {
+    let config = EventConfig::builder()
+        .finish_estimate("W21", 45, 20.0)
+        .finish_estimate("M21", 30, 10.0)
+        .build()
+        .unwrap();
+    config.finish_estimates
+}
+
+// the W21 starter goes first but the M21 course is shorter
+fn starts() -> Vec<CompetitorWithOffset> {
+    vec![
+        start("Anna Berg", Some("W21"), 0),
+        start("Bo Ek", Some("M21"), 4),
+        start("Cilla Fors", Some("W21"), 2),
+        start("Dan", Some("H12"), 6),
+        start("Eva", None, 8),
+    ]
+}
+
+#[test]
+fn the_finish_window_runs_from_the_winning_time_to_the_spread_rounded_up() {
+    let estimate = FinishEstimate {
+        class: "W21".to_string(),
+        winning_time: 45,
+        spread_pct: 20.0,
+    };
+    assert_eq!(estimate.finish_window(0), (45, 54));
+    assert_eq!(estimate.finish_window(10), (55, 64));
+    // 30 * 10% = 3, 25 * 10% = 2.5 rounds up
+    assert_eq!(estimates()[1].finish_window(4), (34, 37));
+    let short = FinishEstimate {
+        winning_time: 25,
+        spread_pct: 10.0,
+        ..estimate
+    };
+    assert_eq!(short.finish_window(0), (25, 28));
+}
+
+#[test]
+fn the_event_file_gives_estimates_per_class() {
+    let event = parse_event(
+        "[[class]]\nname = \"W21\"\nest_winning_time = 45\nspread_pct = 20\n\n\
+         [[class]]\nname = \"M21\"\nest_winning_time = 30\n\n\
+         [[window]]\nduration = 30\n",
+    )
+    .unwrap();
+    assert_eq!(event.windows.len(), 1);
+    assert_eq!(event.finish_estimates.len(), 2);
+    assert_eq!(event.finish_estimates[0], estimates()[0]);
+    assert_eq!(event.finish_estimates[1].spread_pct, 0.0);
+
+    let error = parse_event("[[class]]\nname = \"W21\"\n[[window]]\nduration = 30\n")
+        .err()
+        .unwrap();
+    assert!(error.to_string().contains("est_winning_time"), "{}", error);
+    assert!(matches!(
+        EventConfig::builder()
+            .finish_estimate("W21", 0, 10.0)
+            .build(),
+        Err(ConfigError::InvalidFinishEstimate(_))
+    ));
+    assert!(matches!(
+        EventConfig::builder()
+            .finish_estimate("W21", 45, -1.0)
+            .build(),
+        Err(ConfigError::InvalidFinishEstimate(_))
+    ));
+}
+
+#[test]
+fn classes_without_an_estimate_get_blank_finish_columns() {
+    let csv = export_csv_with_finish(
+        &starts(),
+        |_| true,
+        false,
+        nine(),
+        TimeUnit::Minutes,
+        &estimates(),
+    );
+    let lines: Vec<&str> = csv.lines().collect();
+    assert!(lines[0].ends_with(",class,expected_finish_from,expected_finish_to"));
+    assert!(lines[1].starts_with("1,Anna Berg,09:00:00,"));
+    assert!(lines[1].ends_with(",W21,09:45:00,09:54:00"));
+    assert!(lines[3].ends_with(",M21,09:34:00,09:37:00"));
+    assert!(lines[4].ends_with(",H12,,"));
+    assert!(lines[5].ends_with(",,,"));
+
+    let plain = export_csv_with_finish(&starts(), |_| true, false, nine(), TimeUnit::Minutes, &[]);
+    assert!(!plain.contains("expected_finish"));
+
+    let html = export_html(&starts(), nine(), TimeUnit::Minutes, false, &estimates());
+    assert!(html.contains("<th>Expected finish</th>"));
+    assert!(html.contains("<td>W21</td><td>09:45:00–09:54:00</td></tr>"));
+    assert!(html.contains("<td>H12</td><td></td></tr>"));
+    let html = export_html(&starts(), nine(), TimeUnit::Minutes, false, &[]);
+    assert!(!html.contains("Expected finish"));
+}
+
+#[test]
+fn the_commentary_lists_starts_by_expected_finish() {
+    let sheet = commentary_sheet(&starts(), nine(), TimeUnit::Minutes, &estimates());
+    assert_eq!(
+        sheet,
+        "09:34–09:37 Bo Ek, M21, started 09:04\n\
+         09:45–09:54 Anna Berg, W21, started 09:00\n\
+         09:47–09:56 Cilla Fors, W21, started 09:02\n\
+         No finish estimate:\n  \
+         Dan, H12, started 09:06\n  \
+         Eva, started 09:08\n"
+    );
+}
@@ -0,0 +1,88 @@
+// the seedless API is left out of no-entropy builds
+#![cfg(not(feature = "no-entropy"))]
+
+mod common;
+
+use std::collections::HashSet;
+
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+use start_list_generator::config::{DisplacementSelection, EventConfig};
+use start_list_generator::{
+    generate_startlist, generate_startlist_seeded, generate_startlist_with_rng,
+    CompetitorWithOffset, Minutes, Window,
+};
+
+use common::{config, window};
+
+#[test]
+fn places_every_competitor_exactly_once() {
+    let windows = vec![window(30, 1, 2), window(30, 2, 15), window(30, 3, 4)];
+    let result = generate_startlist(windows, &config());
+
+    assert_eq!(result.len(), 21);
+    let names: HashSet<&str> = result.iter().map(|c| c.competitor.name.as_str()).collect();
+    assert_eq!(names.len(), 21);
+}
+
+#[test]
+fn starts_keep_min_spacing_within_the_event() {
+    let windows = vec![window(30, 1, 6), window(30, 2, 8), window(30, 3, 5)];
+    let mut offsets: Vec<Minutes> = generate_startlist(windows, &config())
+        .iter()
+        .map(|c| c.offset)
+        .collect();
+    offsets.sort();
+
+    assert!(offsets[0] >= 0);
+    assert!(*offsets.last().unwrap() < 90);
+    assert!(offsets.windows(2).all(|pair| pair[1] - pair[0] >= 2));
+}
+
+#[test]
+fn empty_windows_give_an_empty_list() {
+    let windows = vec![window(30, 1, 0), window(30, 2, 0)];
+    assert!(generate_startlist(windows, &config()).is_empty());
+}
+
+fn example() -> Vec<Window> {
+    vec![window(30, 1, 2), window(30, 2, 15), window(30, 3, 4)]
+}
+
+fn draw(seed: u64, config: &EventConfig) -> Vec<CompetitorWithOffset> {
+    generate_startlist_with_rng(example(), config, &mut StdRng::seed_from_u64(seed))
+}
+
+#[test]
+fn the_same_rng_state_gives_the_same_list() {
+    let config = EventConfig::builder()
+        .displacement_selection(DisplacementSelection::Random)
+        .build()
+        .unwrap();
+    assert_eq!(
+        format!("{:?}", draw(7, &config)),
+        format!("{:?}", draw(7, &config))
+    );
+}
+
+#[test]
+fn different_seeds_give_different_shuffles() {
+    let names = |seed| -> Vec<String> {
+        draw(seed, &config())
+            .into_iter()
+            .map(|c| c.competitor.name)
+            .collect()
+    };
+    assert_ne!(names(1), names(2));
+}
+
+#[test]
+fn the_same_seed_gives_the_same_offsets() {
+    let offsets = |seed| -> Vec<(String, Minutes)> {
+        generate_startlist_seeded(example(), &config(), seed)
+            .into_iter()
+            .map(|c| (c.competitor.name, c.offset))
+            .collect()
+    };
+    assert_eq!(offsets(2023), offsets(2023));
+}
@@ -0,0 +1,120 @@
+use std::borrow::Cow;
+use std::time::Instant;
+
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+use start_list_generator::config::EventConfig;
+use start_list_generator::{
+    generate_startlist_iter, generate_startlist_seeded, Competitor, Minutes, Window,
+};
+
+fn window(duration: Minutes, number: usize, count: usize) -> Window {
+    Window::new(
+        duration,
+        (0..count).map(|i| {
+            let mut competitor = Competitor::new(format!("{} Competitor {}", number, i));
+            competitor.club = Some(format!("Club {}", i % 3));
+            competitor
+        }),
+    )
+}
+
+fn config() -> EventConfig {
+    EventConfig::builder()
+        .spacing_threshold(3)
+        .min_spacing(2)
+        .same_club_min_spacing(4)
+        .sweeper("Sweeper", 1)
+        .ceremonial_start("Mayor", 0)
+        .build()
+        .unwrap()
+}
+
+fn windows() -> Vec<Window> {
+    let mut windows = vec![window(40, 0, 18), window(20, 1, 12), window(60, 2, 6)];
+    // two entries alike in name, club and category
+    windows[2].competitors.push_back(Competitor::new("Dan"));
+    windows[2].competitors.push_back(Competitor::new("Dan"));
+    windows
+}
+
+#[test]
+fn the_iterator_gives_the_list_the_vec_api_gives_for_the_same_seed() {
+    let windows = windows();
+    for seed in 0..20 {
+        let expected = generate_startlist_seeded(windows.clone(), &config(), seed);
+        let assignments: Vec<_> =
+            generate_startlist_iter(&windows, &config(), &mut StdRng::seed_from_u64(seed))
+                .collect();
+        assert_eq!(assignments.len(), expected.len());
+        for (assignment, expected) in assignments.iter().zip(&expected) {
+            assert_eq!(assignment.competitor.name, expected.competitor.name);
+            assert_eq!(assignment.competitor.club, expected.competitor.club);
+            assert_eq!(assignment.offset, expected.offset);
+            assert_eq!(assignment.window, expected.window);
+        }
+    }
+}
+
+#[test]
+fn drawn_competitors_are_borrowed_and_the_others_owned() {
+    let windows = windows();
+    let assignments: Vec<_> =
+        generate_startlist_iter(&windows, &config(), &mut StdRng::seed_from_u64(3)).collect();
+    let arena: Vec<*const Competitor> = windows
+        .iter()
+        .flat_map(|window| window.competitors.iter())
+        .map(|competitor| competitor as *const Competitor)
+        .collect();
+    let mut borrowed: Vec<*const Competitor> = Vec::new();
+    for assignment in &assignments {
+        match &assignment.competitor {
+            Cow::Borrowed(competitor) => {
+                assert!(arena.contains(&(*competitor as *const Competitor)));
+                borrowed.push(*competitor);
+            }
+            Cow::Owned(competitor) => {
+                assert!(
+                    competitor.ceremonial || competitor.sweeper,
+                    "{}",
+                    competitor.name
+                )
+            }
+        }
+    }
+    // every entry once, both of the alike ones included
+    borrowed.sort();
+    borrowed.dedup();
+    assert_eq!(borrowed.len(), arena.len());
+    // window by window, in start order
+    assert!(assignments
+        .windows(2)
+        .all(|pair| pair[0].offset <= pair[1].offset));
+    assert!(assignments
+        .windows(2)
+        .all(|pair| pair[0].window <= pair[1].window));
+}
+
+// a timing of both APIs on a large event: cargo test -- --ignored --nocapture
+#[test]
+#[ignore]
+fn the_iterator_and_the_vec_api_on_a_large_event() {
+    let windows: Vec<Window> = (0..50).map(|i| window(600, i, 250)).collect();
+    let config = EventConfig::builder()
+        .spacing_threshold(3)
+        .min_spacing(2)
+        .build()
+        .unwrap();
+    let started = Instant::now();
+    let collected = generate_startlist_seeded(windows.clone(), &config, 1).len();
+    let vec_time = started.elapsed();
+    let started = Instant::now();
+    let iterated =
+        generate_startlist_iter(&windows, &config, &mut StdRng::seed_from_u64(1)).count();
+    let iter_time = started.elapsed();
+    assert_eq!(collected, iterated);
+    println!(
+        "vec: {:?}, iter: {:?} for {} starts",
+        vec_time, iter_time, iterated
+    );
+}
@@ -0,0 +1,351 @@
+use std::io::Read;
+use std::path::{Path, PathBuf};
+
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+use start_list_generator::config::EventConfig;
+use start_list_generator::import::{
+    ImportError, ImportOptions, ImportedEntries, Importer, ImporterRegistry,
+};
+use start_list_generator::input::{parse_event, startlist_from_csv};
+use start_list_generator::output::export_csv;
+use start_list_generator::timefmt::TimeUnit;
+use start_list_generator::{try_generate_startlist_with_rng, Competitor, Window};
+
+// a federation's `window|name|club` rows, skipping any without a name
+struct PipeImporter;
+
+impl Importer for PipeImporter {
+    fn detect(&self, path: &Path, _head: &[u8]) -> bool {
+        path.extension()
+            .is_some_and(|extension| extension == "pipe")
+    }
+
+    fn import(
+        &self,
+        reader: &mut dyn Read,
+        options: &ImportOptions,
+    ) -> Result<ImportedEntries, ImportError> {
+        let mut text = String::new();
+        reader.read_to_string(&mut text).map_err(ImportError::Io)?;
+        let mut entries = ImportedEntries::default();
+        for (i, line) in text.lines().enumerate() {
+            let fields: Vec<&str> = line.split('|').collect();
+            if fields.get(1).is_none_or(|name| name.is_empty()) {
+                entries
+                    .diagnostics
+                    .push(format!("row {} has no name", i + 1));
+                continue;
+            }
+            let window = options
+                .window_index(fields[0])
+                .ok_or_else(|| ImportError::Other(format!("no window '{}'", fields[0]).into()))?;
+            let mut competitor = Competitor::new(fields[1]);
+            competitor.club = fields.get(2).map(|club| club.to_string());
+            entries.competitors.push((window, competitor));
+        }
+        Ok(entries)
+    }
+}
+
+const EVENT: &str = r#"
+[[window]]
+name = "early"
+duration = 30
+
+[[window]]
+name = "late"
+duration = 30
+"#;
+
+// a file of its own in the temp directory, removed when dropped
+struct TempFile(PathBuf);
+
+impl TempFile {
+    fn new(name: &str, contents: &str) -> Self {
+        let path = std::env::temp_dir().join(format!("{}-{}", std::process::id(), name));
+        std::fs::write(&path, contents).unwrap();
+        TempFile(path)
+    }
+}
+
+impl Drop for TempFile {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_file(&self.0);
+    }
+}
+
+#[test]
+fn a_registered_importer_fills_the_event_windows() {
+    let event = parse_event(EVENT).unwrap();
+    let file = TempFile::new(
+        "entries.pipe",
+        "early|Anna|OK Linné\nlate|Bo|IFK\n2|Cecilia|IFK\n1||\n",
+    );
+    let mut registry = ImporterRegistry::default();
+    registry.register(PipeImporter);
+
+    let imported = registry
+        .import_path(&file.0, &event.import_options())
+        .unwrap();
+    assert_eq!(imported.diagnostics, ["row 4 has no name"]);
+    let windows = imported.into_windows(Some(event.windows)).unwrap();
+    let names: Vec<Vec<&str>> = windows
+        .iter()
+        .map(|w| w.competitors.iter().map(|c| c.name.as_str()).collect())
+        .collect();
+    assert_eq!(names, [vec!["Anna"], vec!["Bo", "Cecilia"]]);
+
+    let config = EventConfig::builder().build().unwrap();
+    let result =
+        try_generate_startlist_with_rng(windows, &config, &mut StdRng::seed_from_u64(1)).unwrap();
+    assert_eq!(result.len(), 3);
+    let anna = result.iter().find(|c| c.competitor.name == "Anna").unwrap();
+    assert_eq!(anna.competitor.club.as_deref(), Some("OK Linné"));
+}
+
+#[test]
+fn the_built_in_importers_tell_their_csvs_apart() {
+    let registry = ImporterRegistry::default();
+    let windows = TempFile::new(
+        "windows.csv",
+        "window_id,duration,competitor_name\nA,30,Anna\nB,20,Bo\n",
+    );
+    let imported = registry
+        .import_path(&windows.0, &ImportOptions::default())
+        .unwrap();
+    let windows = imported.into_windows(None).unwrap();
+    assert_eq!(
+        windows.iter().map(|w| w.duration).collect::<Vec<_>>(),
+        [30, 20]
+    );
+
+    let event = parse_event(EVENT).unwrap();
+    let entries = TempFile::new("entries.csv", "window,name\nlate,Anna\n");
+    let imported = registry
+        .import_path(&entries.0, &event.import_options())
+        .unwrap();
+    let windows = imported.into_windows(Some(event.windows)).unwrap();
+    assert_eq!(windows[1].competitors[0].name, "Anna");
+}
+
+#[test]
+fn a_file_no_importer_detects_is_an_error() {
+    let file = TempFile::new("entries.pipe", "early|Anna\n");
+    let error = ImporterRegistry::default()
+        .import_path(&file.0, &ImportOptions::default())
+        .err()
+        .unwrap();
+    assert!(matches!(error, ImportError::UnknownFormat(path) if path == file.0));
+}
+
+#[test]
+fn entries_without_windows_are_an_error() {
+    let entries = ImportedEntries {
+        competitors: vec![(0, Competitor::new("Anna"))],
+        ..ImportedEntries::default()
+    };
+    assert!(matches!(
+        entries.into_windows(None),
+        Err(ImportError::NoWindows)
+    ));
+}
+
+fn fixture(name: &str) -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("tests/fixtures")
+        .join(name)
+}
+
+#[test]
+fn a_ragged_csv_keeps_every_row_it_can_read() {
+    let imported = ImporterRegistry::default()
+        .import_path(&fixture("ragged_windows.csv"), &ImportOptions::default())
+        .unwrap();
+    let skipped_lines: Vec<&str> = imported
+        .diagnostics
+        .iter()
+        .map(|diagnostic| diagnostic.split(':').next().unwrap())
+        .collect();
+    assert_eq!(
+        skipped_lines,
+        ["line 6", "line 7", "line 8", "line 11", "line 12"]
+    );
+    assert_eq!(
+        imported.diagnostics[1],
+        "line 7: skipped 'B;abc;David': invalid duration 'abc'"
+    );
+
+    let windows = imported.into_windows(None).unwrap();
+    let entries: Vec<(&str, Option<&str>)> = windows
+        .iter()
+        .flat_map(|w| &w.competitors)
+        .map(|c| (c.name.as_str(), c.club.as_deref()))
+        .collect();
+    assert_eq!(
+        entries,
+        [
+            ("Anna", Some("OK Linné")),
+            ("Bo", None),
+            ("Cecilia", Some("OK\nRavinen")),
+            ("Frida \"Fia\" Holm", Some("IFK")),
+        ]
+    );
+    assert_eq!(
+        windows.iter().map(|w| w.duration).collect::<Vec<_>>(),
+        [30, 20]
+    );
+}
+
+#[test]
+fn more_skipped_rows_than_allowed_fail_the_import() {
+    let import = |max_skipped| {
+        let options = ImportOptions {
+            max_skipped: Some(max_skipped),
+            ..ImportOptions::default()
+        };
+        ImporterRegistry::default().import_path(&fixture("ragged_windows.csv"), &options)
+    };
+    assert!(import(5).is_ok());
+    let error = import(4).err().unwrap();
+    assert!(matches!(
+        &error,
+        ImportError::TooManySkipped { skipped, max_skipped: 4 } if skipped.len() == 5
+    ));
+    assert!(error
+        .to_string()
+        .starts_with("5 rows could not be read, more than the 4 allowed\nline 6: skipped"));
+}
+
+#[test]
+fn semicolon_entries_skip_rows_for_unknown_windows() {
+    let event = parse_event(EVENT).unwrap();
+    let file = TempFile::new(
+        "semicolon-entries.csv",
+        "window;name;club\nearly;Anna\nlate;Bo;IFK\nnowhere;Cecilia\n",
+    );
+    let imported = ImporterRegistry::default()
+        .import_path(&file.0, &event.import_options())
+        .unwrap();
+    assert_eq!(
+        imported.diagnostics,
+        ["line 4: skipped 'nowhere;Cecilia': there is no window 'nowhere'"]
+    );
+    let clubs: Vec<(usize, Option<&str>)> = imported
+        .competitors
+        .iter()
+        .map(|(window, c)| (*window, c.club.as_deref()))
+        .collect();
+    assert_eq!(clubs, [(0, None), (1, Some("IFK"))]);
+}
+
+// last year's list: clubs and classes, and a ceremonial start that was never
+// an entry
+fn last_years_list() -> String {
+    let config = EventConfig::builder()
+        .ceremonial_start("Chair", 0)
+        .build()
+        .unwrap();
+    let mut windows = parse_event(EVENT).unwrap().windows;
+    for (i, name) in [
+        "Anna", "Bo", "Cecilia", "Dan", "Eva", "Frida", "Gustav", "Hanna",
+    ]
+    .into_iter()
+    .enumerate()
+    {
+        let mut competitor = Competitor::new(name);
+        competitor.club = Some(["OK Linné", "IFK Lidingö, OL"][i % 2].to_string());
+        competitor.category = Some(["W21", "M21"][i % 2].to_string());
+        windows[i / 5].competitors.push_back(competitor);
+    }
+    let result =
+        try_generate_startlist_with_rng(windows, &config, &mut StdRng::seed_from_u64(3)).unwrap();
+    export_csv(&result, config.start_time, TimeUnit::Minutes)
+}
+
+fn entries(windows: &[Window]) -> Vec<(String, Option<String>, Option<String>)> {
+    let mut entries: Vec<_> = windows
+        .iter()
+        .flat_map(|w| &w.competitors)
+        .map(|c| (c.name.clone(), c.club.clone(), c.category.clone()))
+        .collect();
+    entries.sort();
+    entries
+}
+
+#[test]
+fn last_year_s_start_list_is_this_year_s_entries() {
+    let csv = last_years_list();
+    let last_year = startlist_from_csv(&csv, TimeUnit::Minutes).unwrap();
+    let file = TempFile::new("last-year.csv", &csv);
+    let event = parse_event(EVENT).unwrap();
+
+    let imported = ImporterRegistry::default()
+        .import_path(&file.0, &event.import_options())
+        .unwrap();
+    let windows = imported.into_windows(Some(event.windows)).unwrap();
+    // dealt out over the two equal windows, the ceremonial start left out
+    assert_eq!(
+        windows
+            .iter()
+            .map(|w| w.competitors.len())
+            .collect::<Vec<_>>(),
+        [4, 4]
+    );
+    let mut expected: Vec<_> = last_year
+        .iter()
+        .filter(|c| !c.competitor.ceremonial)
+        .map(|c| {
+            (
+                c.competitor.name.clone(),
+                c.competitor.club.clone(),
+                c.competitor.category.clone(),
+            )
+        })
+        .collect();
+    expected.sort();
+    assert_eq!(entries(&windows), expected);
+    assert_eq!(expected[1].1.as_deref(), Some("IFK Lidingö, OL"));
+
+    let config = EventConfig::builder().build().unwrap();
+    let this_year =
+        try_generate_startlist_with_rng(windows, &config, &mut StdRng::seed_from_u64(11)).unwrap();
+    assert_eq!(this_year.len(), 8);
+    assert!(this_year.iter().any(|c| {
+        last_year
+            .iter()
+            .find(|old| old.competitor.name == c.competitor.name)
+            .unwrap()
+            .offset
+            != c.offset
+    }));
+}
+
+#[test]
+fn reuse_windows_puts_everyone_back_in_their_window() {
+    let csv = last_years_list();
+    let last_year = startlist_from_csv(&csv, TimeUnit::Minutes).unwrap();
+    let file = TempFile::new("last-year-windows.csv", &csv);
+    let event = parse_event(EVENT).unwrap();
+    let options = ImportOptions {
+        reuse_windows: true,
+        ..event.import_options()
+    };
+    let imported = ImporterRegistry::default()
+        .import_path(&file.0, &options)
+        .unwrap();
+    for (window, competitor) in &imported.competitors {
+        let old = last_year
+            .iter()
+            .find(|c| c.competitor.name == competitor.name)
+            .unwrap();
+        assert_eq!(*window, old.window);
+    }
+    assert_eq!(imported.competitors.len(), 8);
+
+    // without the event's windows there is nothing to deal them out over
+    let error = ImporterRegistry::default()
+        .import_path(&file.0, &ImportOptions::default())
+        .unwrap_err();
+    assert!(matches!(error, ImportError::NoWindows));
+}
@@ -0,0 +1,168 @@
+use chrono::NaiveTime;
+use start_list_generator::input::{
+    load_windows_from_csv, parse_event, windows_from_csv, InputError,
+};
+
+const EVENT: &str = r#"
+# spring sprint
+start_time = "10:30"
+spacing_threshold = 4
+
+[[window]]
+name = "early"
+duration = 30
+competitors = [
+    "Anna Berg",  # club captain
+    "Bo \"Bosse\" Ek",
+]
+
+[[window]]
+duration = 45
+"#;
+
+#[test]
+fn reads_settings_and_windows() {
+    let event = parse_event(EVENT).unwrap();
+
+    assert_eq!(event.start_time, NaiveTime::from_hms_opt(10, 30, 0));
+    assert_eq!(event.spacing_threshold, Some(4));
+    assert_eq!(event.min_spacing, None);
+    assert_eq!(event.windows.len(), 2);
+    assert_eq!(event.windows[1].duration, 45);
+    let names: Vec<&str> = event.windows[0]
+        .competitors
+        .iter()
+        .map(|c| c.name.as_str())
+        .collect();
+    assert_eq!(names, ["Anna Berg", "Bo \"Bosse\" Ek"]);
+}
+
+#[test]
+fn entries_csv_refers_to_windows_by_name_or_number() {
+    let mut event = parse_event(EVENT).unwrap();
+    event
+        .add_entries_csv("window,name\nearly,Cecilia Dahl\n2,\"Lind, Erik\"\n")
+        .unwrap();
+
+    assert_eq!(event.windows[0].competitors.len(), 3);
+    assert_eq!(event.windows[1].competitors[0].name, "Lind, Erik");
+}
+
+#[test]
+fn entries_csv_may_give_clubs_and_the_event_their_spacing() {
+    let mut event = parse_event(&format!("same_club_min_spacing = 5\n{}", EVENT)).unwrap();
+    event
+        .add_entries_csv("window,name,club\nearly,Cecilia Dahl,OK Ravinen\n2,Erik Lind,\n")
+        .unwrap();
+
+    assert_eq!(event.same_club_min_spacing, Some(5));
+    assert_eq!(
+        event.windows[0].competitors[2].club.as_deref(),
+        Some("OK Ravinen")
+    );
+    assert_eq!(event.windows[1].competitors[0].club, None);
+}
+
+#[test]
+fn rejects_a_non_positive_duration() {
+    let error = parse_event("[[window]]\nduration = 0\n").err().unwrap();
+    assert!(matches!(
+        error,
+        InputError::NonPositiveDuration {
+            window: 0,
+            duration: 0
+        }
+    ));
+}
+
+#[test]
+fn rejects_an_empty_competitor_name() {
+    let error = parse_event("[[window]]\nduration = 30\ncompetitors = [\"Anna\", \" \"]\n")
+        .err()
+        .unwrap();
+    assert!(matches!(error, InputError::EmptyName { line: 3 }));
+}
+
+#[test]
+fn rejects_an_unknown_window_in_the_entries() {
+    let mut event = parse_event(EVENT).unwrap();
+    let error = event
+        .add_entries_csv("window,name\nlate,Frida Holm\n")
+        .err()
+        .unwrap();
+    assert!(matches!(error, InputError::UnknownWindow { line: 2, .. }));
+}
+
+#[test]
+fn windows_csv_groups_rows_in_order() {
+    let windows = windows_from_csv(
+        "window_id,duration,competitor_name\n\
+         elite,40,Anna Berg\n\
+         open,20,Cilla Dahl\n\
+         elite,40,\"Ek, Bo\"\n\
+         late,15,\n",
+    )
+    .unwrap();
+
+    let durations: Vec<_> = windows.iter().map(|w| w.duration).collect();
+    assert_eq!(durations, [40, 20, 15]);
+    let names: Vec<&str> = windows[0]
+        .competitors
+        .iter()
+        .map(|c| c.name.as_str())
+        .collect();
+    assert_eq!(names, ["Anna Berg", "Ek, Bo"]);
+    assert_eq!(windows[1].competitors.len(), 1);
+    assert!(windows[2].competitors.is_empty());
+}
+
+#[test]
+fn windows_csv_reads_an_optional_origin_column() {
+    let windows = windows_from_csv(
+        "window_id,duration,competitor_name,origin\n1,30,Anna,-1\n1,30,Bo,\n1,30,Cilla,1\n",
+    )
+    .unwrap();
+    assert_eq!(windows[0].competitors.len(), 3);
+
+    let error =
+        windows_from_csv("window_id,duration,competitor_name,origin\n1,30,Anna,1\n1,30,Bo,0\n")
+            .err()
+            .unwrap();
+    assert!(matches!(error, InputError::Syntax { line: 3, .. }));
+}
+
+#[test]
+fn windows_csv_rejects_disagreeing_durations() {
+    let error = windows_from_csv("window_id,duration,competitor_name\n1,30,Anna\n1,25,Bo\n")
+        .err()
+        .unwrap();
+    assert!(matches!(
+        error,
+        InputError::ConflictingDuration {
+            line: 3,
+            duration: 25,
+            expected: 30,
+            ..
+        }
+    ));
+    let error = windows_from_csv("window_id,competitor_name\n1,Anna\n")
+        .err()
+        .unwrap();
+    assert!(matches!(error, InputError::MissingColumn("duration")));
+}
+
+#[test]
+fn loading_a_missing_windows_csv_is_an_io_error() {
+    let error = load_windows_from_csv("no/such/windows.csv").err().unwrap();
+    assert!(matches!(error, InputError::Io(_)));
+}
+
+#[test]
+fn a_strict_read_of_a_ragged_csv_fails_at_the_first_bad_row() {
+    let text = include_str!("fixtures/ragged_windows.csv");
+    let error = windows_from_csv(text).err().unwrap();
+    assert_eq!(
+        error.to_string(),
+        "line 6: expected a competitor_name in field 3"
+    );
+}
@@ -0,0 +1,97 @@
+mod common;
+
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+use start_list_generator::config::EventConfig;
+use start_list_generator::{
+    generate_startlist_with_rng, try_generate_draw_with_rng, try_generate_startlist_with_rng,
+    StartListError, Window,
+};
+
+use common::{builder, window};
+
+fn locked(window: Window) -> Window {
+    Window {
+        locked: true,
+        ..window
+    }
+}
+
+fn config() -> EventConfig {
+    builder().min_spacing(1).build().unwrap()
+}
+
+fn drawn_numbers(windows: Vec<Window>, seed: u64) -> Vec<Vec<usize>> {
+    try_generate_draw_with_rng(windows, &config(), &mut StdRng::seed_from_u64(seed))
+        .unwrap()
+        .windows
+        .iter()
+        .map(|w| {
+            w.competitors
+                .iter()
+                .map(|c| c.name.split(' ').next().unwrap().parse().unwrap())
+                .collect()
+        })
+        .collect()
+}
+
+#[test]
+fn a_locked_dense_window_keeps_all_its_competitors() {
+    for seed in 0..5 {
+        // unlocked, the dense middle window hands competitors to both neighbours
+        let unlocked = drawn_numbers(
+            vec![window(30, 1, 2), window(10, 2, 8), window(30, 3, 2)],
+            seed,
+        );
+        assert!(unlocked[1].len() < 8, "seed {}", seed);
+
+        let drawn = drawn_numbers(
+            vec![window(30, 1, 2), locked(window(10, 2, 8)), window(30, 3, 2)],
+            seed,
+        );
+        assert_eq!(drawn[1], [2; 8], "seed {}", seed);
+        assert_eq!((drawn[0].len(), drawn[2].len()), (2, 2), "seed {}", seed);
+    }
+}
+
+#[test]
+fn a_locked_sparse_window_takes_no_strangers() {
+    for seed in 0..5 {
+        let drawn = drawn_numbers(
+            vec![window(30, 1, 1), window(10, 2, 8), locked(window(30, 3, 1))],
+            seed,
+        );
+        assert_eq!(drawn[2], [3], "seed {}", seed);
+        // the dense window balances against its other neighbour instead
+        assert!(drawn[0].contains(&2), "seed {}", seed);
+    }
+}
+
+#[test]
+fn a_locked_overfull_window_overflows_by_its_own_policy() {
+    // eight starts cannot fit ten minutes at min_spacing 2, and the window
+    // may not give any away
+    let config = EventConfig::builder()
+        .spacing_threshold(3)
+        .min_spacing(2)
+        .build()
+        .unwrap();
+    let windows = || vec![locked(window(10, 1, 8)), window(30, 2, 1)];
+
+    let result = try_generate_startlist_with_rng(windows(), &config, &mut StdRng::seed_from_u64(1));
+    assert!(matches!(
+        result,
+        Err(StartListError::WindowOverfull {
+            window: 0,
+            competitors: 8,
+            ..
+        })
+    ));
+
+    // unchecked, every member still starts in the locked window, spilling past it
+    let result = generate_startlist_with_rng(windows(), &config, &mut StdRng::seed_from_u64(1));
+    let members: Vec<_> = result.iter().filter(|c| c.window == 0).collect();
+    assert_eq!(members.len(), 8);
+    assert!(members.iter().all(|c| c.competitor.name.starts_with("1 ")));
+    assert!(members.iter().any(|c| c.offset >= 10));
+}
@@ -0,0 +1,97 @@
+// the seedless API is left out of no-entropy builds
+#![cfg(not(feature = "no-entropy"))]
+
+mod common;
+
+use start_list_generator::input::parse_event;
+use start_list_generator::{
+    reassign_offsets, spacing_violations, window_boundaries, CompetitorWithOffset, SavedDraw,
+    StartListError,
+};
+
+use common::config;
+
+// a window of eight minutes made up only of competitors moved in from one
+// neighbour: four of them at spacing_threshold 3 would need ten minutes
+fn event(moved_in: &str) -> String {
+    format!(
+        r#"
+[[window]]
+duration = 20
+competitors = ["A1", "A2", "A3"]
+
+[[window]]
+duration = 8
+{} = 4
+competitors = ["M1", "M2", "M3", "M4"]
+
+[[window]]
+duration = 20
+competitors = ["C1", "C2", "C3"]
+"#,
+        moved_in
+    )
+}
+
+fn reassign(text: &str) -> Result<Vec<CompetitorWithOffset>, StartListError> {
+    let saved = SavedDraw {
+        windows: parse_event(text).unwrap().windows,
+        witnesses: Vec::new(),
+        tie_breaks: Vec::new(),
+        displacement_selection: None,
+        thresholds: Vec::new(),
+        diagnostics: Vec::new(),
+        revision: 0,
+        log: Vec::new(),
+    };
+    reassign_offsets(&saved, &config())
+}
+
+#[test]
+fn a_moved_in_block_longer_than_its_window_is_squeezed_into_it() {
+    for moved_in in ["from_earlier", "from_later"] {
+        let text = event(moved_in);
+        let boundaries = window_boundaries(&parse_event(&text).unwrap().windows);
+        let result = reassign(&text).unwrap();
+
+        let names: Vec<&str> = result.iter().map(|c| c.competitor.name.as_str()).collect();
+        assert_eq!(
+            names,
+            ["A1", "A2", "A3", "M1", "M2", "M3", "M4", "C1", "C2", "C3"],
+            "{}",
+            moved_in
+        );
+        // in clock order as well as list order, min_spacing apart throughout
+        assert!(result
+            .windows(2)
+            .all(|pair| pair[0].offset < pair[1].offset));
+        assert!(
+            spacing_violations(&result, &[2, 2, 2]).is_empty(),
+            "{}",
+            moved_in
+        );
+        for c in &result {
+            let (start, end) = boundaries[c.window];
+            assert!(
+                (start..end).contains(&c.offset),
+                "{} at {}",
+                c.competitor.name,
+                c.offset
+            );
+        }
+    }
+}
+
+#[test]
+fn a_moved_in_block_that_cannot_fit_at_min_spacing_is_an_error() {
+    let text = event("from_earlier").replace("duration = 8", "duration = 6");
+    assert!(matches!(
+        reassign(&text),
+        Err(StartListError::WindowOverfull {
+            window: 1,
+            competitors: 4,
+            capacity: 3,
+            ..
+        })
+    ));
+}
@@ -0,0 +1,79 @@
+#![cfg(feature = "no-entropy")]
+
+use std::process::Command;
+
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+use start_list_generator::config::EventConfig;
+use start_list_generator::{try_generate_draw_with_rng, Competitor, Window};
+
+fn windows() -> Vec<Window> {
+    // equally unbalanced pairs, for a tie to break
+    [(10, 2), (10, 6), (10, 2)]
+        .into_iter()
+        .enumerate()
+        .map(|(number, (duration, count))| {
+            Window::new(
+                duration,
+                (0..count).map(move |i| Competitor::new(format!("{} Competitor {}", number, i))),
+            )
+        })
+        .collect()
+}
+
+fn binary() -> Command {
+    Command::new(env!("CARGO_BIN_EXE_start-list-generator"))
+}
+
+#[test]
+fn a_random_tie_break_falls_back_to_the_earlier_pair() {
+    let config = EventConfig::builder()
+        .min_spacing(1)
+        .random_tie_break(true)
+        .build()
+        .unwrap();
+    assert!(!config.random_tie_break);
+    for seed in 0..10 {
+        let saved =
+            try_generate_draw_with_rng(windows(), &config, &mut StdRng::seed_from_u64(seed))
+                .unwrap();
+        assert!(saved.tie_breaks.is_empty());
+    }
+}
+
+#[test]
+fn two_runs_with_the_same_seed_are_identical() {
+    let run = || {
+        binary()
+            .args(["--demo", "medium", "--seed", "7", "--format", "csv"])
+            .output()
+            .unwrap()
+    };
+    let (first, second) = (run(), run());
+    assert!(first.status.success());
+    assert_eq!(first.stdout, second.stdout);
+}
+
+#[test]
+fn a_run_without_a_seed_fails() {
+    let output = binary().args(["--demo", "small"]).output().unwrap();
+    assert_eq!(output.status.code(), Some(2));
+    assert!(String::from_utf8_lossy(&output.stderr).contains("pass --seed N"));
+}
+
+#[test]
+fn the_version_names_the_feature() {
+    let output = binary().arg("--version").output().unwrap();
+    assert!(String::from_utf8_lossy(&output.stdout).ends_with("(features: no-entropy)\n"));
+}
+
+#[test]
+fn a_locked_window_is_marked_in_the_listing() {
+    let output = binary()
+        .args(["--lock", "2", "--seed", "1"])
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    let listing = String::from_utf8_lossy(&output.stdout);
+    assert!(listing.contains("\nWindow 1: 09:00–09:30\nWindow 2: 09:30–10:00 (locked)\nWindow 3: "));
+}
@@ -0,0 +1,161 @@
+use chrono::{NaiveDate, NaiveTime};
+use start_list_generator::cards::{start_cards_html, CardOptions};
+use start_list_generator::config::EventConfig;
+use start_list_generator::input::startlist_from_csv;
+use start_list_generator::output::{export_csv, export_html, export_iof_xml, verification_code};
+use start_list_generator::timefmt::TimeUnit;
+use start_list_generator::{
+    generate_startlist_seeded, spacing_violations, Competitor, CompetitorWithOffset, Window,
+};
+
+fn start_list() -> Vec<CompetitorWithOffset> {
+    let config = EventConfig::builder()
+        .spacing_threshold(3)
+        .min_spacing(2)
+        .build()
+        .unwrap();
+    let names = ["Anna Berg", "Bo \"Bosse\" Ek", "Ek, Cilla", "Dan"];
+    let windows = vec![
+        Window::new(20, names[..2].iter().map(|&name| Competitor::new(name))),
+        Window::new(20, names[2..].iter().map(|&name| Competitor::new(name))),
+    ];
+    generate_startlist_seeded(windows, &config, 7)
+}
+
+fn nine() -> NaiveTime {
+    NaiveTime::from_hms_opt(9, 0, 0).unwrap()
+}
+
+#[test]
+fn csv_round_trips_names_and_times() {
+    let result = start_list();
+    let csv = export_csv(&result, nine(), TimeUnit::Minutes);
+    assert!(
+        csv.starts_with("position,name,start_time,offset_minutes,window_index,flag,club,class\n")
+    );
+
+    let read = startlist_from_csv(&csv, TimeUnit::Minutes).unwrap();
+    assert_eq!(read.len(), result.len());
+    for original in &result {
+        let copy = read
+            .iter()
+            .find(|c| c.competitor.name == original.competitor.name)
+            .unwrap();
+        assert_eq!(copy.offset, original.offset);
+        assert_eq!(copy.window, original.window);
+    }
+    assert_eq!(export_csv(&read, nine(), TimeUnit::Minutes), csv);
+}
+
+#[test]
+fn iof_xml_has_one_person_start_per_competitor() {
+    let result = start_list();
+    let date = NaiveDate::from_ymd_opt(2024, 5, 18).unwrap();
+    let xml = export_iof_xml(&result, nine(), date, TimeUnit::Minutes);
+
+    assert!(xml.contains("iofVersion=\"3.0\""));
+    assert_eq!(xml.matches("<PersonStart>").count(), result.len());
+    assert!(xml.contains("<Family>Ek</Family>\n          <Given>Bo &quot;Bosse&quot;</Given>"));
+    let first = result.iter().map(|c| c.offset).min().unwrap();
+    assert!(xml.contains(&format!(
+        "<StartTime>2024-05-18T09:{:02}:00</StartTime>\n        <BibNumber>1</BibNumber>",
+        first
+    )));
+    let bibs: Vec<usize> = (1..=result.len())
+        .filter(|bib| xml.contains(&format!("<BibNumber>{}</BibNumber>", bib)))
+        .collect();
+    assert_eq!(bibs.len(), result.len());
+}
+
+// the text between `before` and the next `after`
+fn between<'a>(text: &'a str, before: &str, after: &str) -> &'a str {
+    let start = text.find(before).unwrap() + before.len();
+    &text[start..start + text[start..].find(after).unwrap()]
+}
+
+#[test]
+fn every_format_carries_the_same_verification_code() {
+    let result = start_list();
+    let date = NaiveDate::from_ymd_opt(2024, 5, 18).unwrap();
+    let xml = export_iof_xml(&result, nine(), date, TimeUnit::Minutes);
+    let html = start_cards_html(&result, nine(), &CardOptions::default());
+    let csv = export_csv(&result, nine(), TimeUnit::Minutes);
+
+    let code = verification_code(&result);
+    assert_eq!(between(&xml, "<Id type=\"verification-code\">", "<"), code);
+    assert_eq!(
+        between(&html, "name=\"verification-code\" content=\"", "\""),
+        code
+    );
+    let read = startlist_from_csv(&csv, TimeUnit::Minutes).unwrap();
+    assert_eq!(verification_code(&read), code);
+
+    let mut moved = result.clone();
+    moved[0].offset += 1;
+    assert_ne!(verification_code(&moved), code);
+}
+
+#[test]
+fn ceremonial_starts_are_cleared_flagged_and_left_out_of_the_iof_export() {
+    let config = EventConfig::builder()
+        .spacing_threshold(3)
+        .min_spacing(2)
+        .ceremonial_start("Chair", 0)
+        .sweeper("Sweeper", 1)
+        .build()
+        .unwrap();
+    let windows = vec![
+        Window::new(20, (0..5).map(|i| Competitor::new(format!("A{}", i)))),
+        Window::new(20, (0..5).map(|i| Competitor::new(format!("B{}", i)))),
+    ];
+    let result = generate_startlist_seeded(windows, &config, 7);
+    // nobody starts within min_spacing of the chair
+    assert!(result
+        .iter()
+        .filter(|c| !c.competitor.ceremonial)
+        .all(|c| c.offset >= 2));
+    assert!(spacing_violations(&result, &[2, 2]).is_empty());
+
+    let date = NaiveDate::from_ymd_opt(2024, 5, 18).unwrap();
+    let xml = export_iof_xml(&result, nine(), date, TimeUnit::Minutes);
+    assert_eq!(xml.matches("<PersonStart>").count(), 10);
+    assert!(!xml.contains("Chair") && !xml.contains("Sweeper"));
+    assert!(xml.contains("<BibNumber>1</BibNumber>") && !xml.contains("<BibNumber>11</BibNumber>"));
+
+    let csv = export_csv(&result, nine(), TimeUnit::Minutes);
+    assert!(csv.contains("\n,Chair,09:00:00,0,0,ceremonial,,\n"));
+    assert!(csv
+        .lines()
+        .any(|line| line.starts_with(",Sweeper,") && line.ends_with(",sweeper,,")));
+    assert!(csv.contains("\n1,"));
+    let read = startlist_from_csv(&csv, TimeUnit::Minutes).unwrap();
+    assert!(read.iter().any(|c| c.competitor.ceremonial));
+    assert!(read.iter().any(|c| c.competitor.sweeper));
+    assert_eq!(export_csv(&read, nine(), TimeUnit::Minutes), csv);
+}
+
+#[test]
+fn the_html_list_has_every_start_in_its_markup_with_or_without_the_script() {
+    let mut result = start_list();
+    result[0].competitor.club = Some("OK Linné".to_string());
+    let searchable = export_html(&result, nine(), TimeUnit::Minutes, true, &[]);
+    let plain = export_html(&result, nine(), TimeUnit::Minutes, false, &[]);
+
+    assert_eq!(searchable.matches("<script>").count(), 1);
+    assert!(searchable.contains("normalize('NFD')"));
+    assert!(!plain.contains("<script"));
+    assert!(!plain.contains("<input"));
+    for html in [&searchable, &plain] {
+        let body = between(html, "<tbody>", "</tbody>");
+        assert_eq!(body.matches("<tr ").count(), result.len());
+        for name in ["Anna Berg", "Bo &quot;Bosse&quot; Ek", "Ek, Cilla", "Dan"] {
+            assert!(body.contains(&format!("<td>{}</td>", name)), "{}", name);
+        }
+        assert!(body.contains("<td>OK Linné</td>"));
+        assert!(html.contains("position: sticky"));
+        assert!(html.contains(&verification_code(&result)));
+        assert!(html.ends_with("</body>\n</html>\n"));
+    }
+    // the script only adds to the page
+    assert!(searchable.starts_with(&plain[..plain.find("</body>").unwrap()]));
+}
@@ -0,0 +1,213 @@
+mod common;
+
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+use start_list_generator::input::parse_event;
+use start_list_generator::output::export_draw;
+use start_list_generator::{
+    exclusive_radius_violations, spacing_violations, try_generate_draw_with_rng,
+    try_generate_startlist_with_rng, Competitor, Minutes, StartListError, Window,
+};
+
+use common::config;
+
+fn runners(window: usize, count: usize) -> impl Iterator<Item = Competitor> {
+    (0..count).map(move |i| Competitor::new(format!("{} Runner {}", window, i)))
+}
+
+fn pinned(name: &str, offset: Minutes) -> Competitor {
+    let mut competitor = Competitor::new(name);
+    competitor.fixed_offset = Some(offset);
+    competitor
+}
+
+fn exclusive(name: &str, offset: Minutes, radius: Minutes) -> Competitor {
+    let mut competitor = pinned(name, offset);
+    competitor.exclusive_radius = radius;
+    competitor
+}
+
+#[test]
+fn a_pinned_competitor_starts_exactly_on_time_and_stays_in_their_window() {
+    for seed in 0..20 {
+        // the crowded first window gives competitors to the second
+        let windows = vec![
+            Window::new(20, runners(1, 9).chain([pinned("Official", 7)])),
+            Window::new(30, runners(2, 3)),
+        ];
+        let result =
+            try_generate_startlist_with_rng(windows, &config(), &mut StdRng::seed_from_u64(seed))
+                .unwrap();
+
+        assert_eq!(result.len(), 13);
+        let official = result
+            .iter()
+            .find(|c| c.competitor.name == "Official")
+            .unwrap();
+        assert_eq!((official.offset, official.window), (7, 0));
+        assert!(
+            spacing_violations(&result, &[2, 2]).is_empty(),
+            "seed {}",
+            seed
+        );
+    }
+}
+
+#[test]
+fn colliding_pinned_starts_are_an_error() {
+    let windows = vec![
+        Window::new(20, runners(1, 3).chain([pinned("Anna", 10)])),
+        Window::new(20, runners(2, 3).chain([pinned("Bo", 11)])),
+    ];
+    let error = try_generate_startlist_with_rng(windows, &config(), &mut StdRng::seed_from_u64(1))
+        .unwrap_err();
+    assert!(matches!(
+        error,
+        StartListError::PinnedCollision {
+            ref first,
+            ref second,
+            offsets: (10, 11),
+            required_gap: 2,
+        } if first == "Anna" && second == "Bo"
+    ));
+}
+
+#[test]
+fn a_saved_draw_keeps_its_pinned_starts() {
+    let windows = vec![Window::new(
+        20,
+        runners(1, 4).chain([pinned("Official", 12)]),
+    )];
+    let saved =
+        try_generate_draw_with_rng(windows, &config(), &mut StdRng::seed_from_u64(2)).unwrap();
+    let text = export_draw(&saved, &config());
+    assert!(text.contains("pinned = [\"Official@12\"]"));
+
+    let read = parse_event(&text).unwrap();
+    let official = read.windows[0]
+        .competitors
+        .iter()
+        .find(|c| c.name == "Official")
+        .unwrap();
+    assert_eq!(official.fixed_offset, Some(12));
+    assert_eq!(read.windows[0].competitors.len(), 5);
+}
+
+#[test]
+fn a_full_window_fits_its_starts_around_a_pinned_one() {
+    for seed in 0..20 {
+        let mut window = Window::new(20, runners(1, 9).chain([pinned("Official", 10)]));
+        window.locked = true;
+        let result = try_generate_startlist_with_rng(
+            vec![window],
+            &config(),
+            &mut StdRng::seed_from_u64(seed),
+        )
+        .unwrap();
+
+        assert!(
+            result.iter().all(|c| (0..20).contains(&c.offset)),
+            "seed {}",
+            seed
+        );
+        assert!(
+            spacing_violations(&result, &[2]).is_empty(),
+            "seed {}",
+            seed
+        );
+        assert_eq!(result[5].competitor.name, "Official");
+        assert_eq!(result[5].offset, 10);
+    }
+}
+
+#[test]
+fn two_close_exclusive_slots_keep_everyone_else_out_of_their_radius() {
+    for seed in 0..20 {
+        let windows = vec![
+            Window::new(
+                30,
+                runners(1, 6).chain([exclusive("Favourite", 10, 3), exclusive("Rival", 16, 2)]),
+            ),
+            Window::new(20, runners(2, 5)),
+        ];
+        let result =
+            try_generate_startlist_with_rng(windows, &config(), &mut StdRng::seed_from_u64(seed))
+                .unwrap();
+        assert_eq!(result.len(), 13);
+        assert!(
+            result
+                .iter()
+                .filter(|c| c.competitor.fixed_offset.is_none())
+                .all(|c| !(7..=13).contains(&c.offset) && !(14..=18).contains(&c.offset)),
+            "seed {}",
+            seed
+        );
+        assert!(exclusive_radius_violations(&result).is_empty());
+        assert!(spacing_violations(&result, &[2, 2]).is_empty());
+    }
+}
+
+#[test]
+fn overlapping_exclusive_radii_are_an_error() {
+    let windows = vec![Window::new(
+        30,
+        runners(1, 4).chain([exclusive("Favourite", 10, 3), exclusive("Rival", 13, 1)]),
+    )];
+    let error = try_generate_startlist_with_rng(windows, &config(), &mut StdRng::seed_from_u64(1))
+        .unwrap_err();
+    assert!(matches!(
+        error,
+        StartListError::PinnedCollision {
+            offsets: (10, 13),
+            required_gap: 4,
+            ..
+        }
+    ));
+}
+
+#[test]
+fn an_exclusive_radius_takes_its_minutes_out_of_the_window() {
+    // minutes 7 to 13 hold only the favourite, leaving room for seven more
+    let window = |count| {
+        let mut window = Window::new(20, runners(1, count).chain([exclusive("Favourite", 10, 3)]));
+        window.locked = true;
+        vec![window]
+    };
+    let result =
+        try_generate_startlist_with_rng(window(7), &config(), &mut StdRng::seed_from_u64(1))
+            .unwrap();
+    assert!(exclusive_radius_violations(&result).is_empty());
+    let error =
+        try_generate_startlist_with_rng(window(8), &config(), &mut StdRng::seed_from_u64(1))
+            .unwrap_err();
+    assert!(matches!(
+        error,
+        StartListError::WindowOverfull {
+            competitors: 9,
+            capacity: 8,
+            ..
+        }
+    ));
+}
+
+#[test]
+fn a_saved_draw_keeps_the_exclusive_radius() {
+    let windows = vec![Window::new(
+        20,
+        runners(1, 4).chain([exclusive("Favourite", 10, 3)]),
+    )];
+    let saved =
+        try_generate_draw_with_rng(windows, &config(), &mut StdRng::seed_from_u64(2)).unwrap();
+    let text = export_draw(&saved, &config());
+    assert!(text.contains("pinned = [\"Favourite@10~3\"]"));
+    let read = parse_event(&text).unwrap();
+    let favourite = read.windows[0]
+        .competitors
+        .iter()
+        .find(|c| c.name == "Favourite")
+        .unwrap();
+    assert_eq!(
+        (favourite.fixed_offset, favourite.exclusive_radius),
+        (Some(10), 3)
+    );
+}
@@ -0,0 +1,128 @@
+mod common;
+
+use chrono::{NaiveDate, NaiveTime, TimeZone, Utc};
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+use start_list_generator::allocation::allocation_csv;
+use start_list_generator::compare::compare_configs;
+use start_list_generator::input::{startlist_from_csv, windows_from_csv};
+use start_list_generator::output::{export_csv, export_html, export_iof_xml};
+use start_list_generator::provenance::{Embedding, Provenance, ProvenanceError, ALGORITHM_VERSION};
+use start_list_generator::timefmt::TimeUnit;
+use start_list_generator::{
+    generate_startlist_seeded, try_generate_draw_with_rng, Competitor, CompetitorWithOffset, Window,
+};
+
+use common::config;
+
+fn windows() -> Vec<Window> {
+    let names = ["Anna Berg", "Bo Ek", "Cilla Fors", "Dan"];
+    vec![
+        Window::new(20, names[..2].iter().map(|&name| Competitor::new(name))),
+        Window::new(20, names[2..].iter().map(|&name| Competitor::new(name))),
+    ]
+}
+
+fn start_list() -> Vec<CompetitorWithOffset> {
+    generate_startlist_seeded(windows(), &config(), 7)
+}
+
+fn nine() -> NaiveTime {
+    NaiveTime::from_hms_opt(9, 0, 0).unwrap()
+}
+
+fn full() -> Provenance {
+    let mut provenance = Provenance::new(Some(7), &[b"start_time = \"09:00\"\n", b"window,name\n"]);
+    provenance.generated_at = Some(Utc.with_ymd_and_hms(2024, 5, 18, 7, 30, 0).unwrap());
+    provenance.host = Some("start-laptop".to_string());
+    provenance.user = Some("Åsa \"timing\" <& co>".to_string());
+    provenance
+}
+
+#[test]
+fn a_new_provenance_names_this_build_and_hashes_the_scenario() {
+    let provenance = Provenance::new(Some(7), &[b"a", b"b"]);
+    assert_eq!(provenance.crate_version, env!("CARGO_PKG_VERSION"));
+    assert_eq!(provenance.algorithm_version, ALGORITHM_VERSION);
+    assert_eq!(provenance.scenario_hash.as_ref().unwrap().len(), 16);
+    // the same bytes split over the files another way are another scenario
+    assert_ne!(
+        provenance.scenario_hash,
+        Provenance::new(Some(7), &[b"ab", b""]).scenario_hash
+    );
+    assert_eq!(Provenance::new(None, &[]).scenario_hash, None);
+    let keys: Vec<&str> = provenance.fields().iter().map(|(key, _)| *key).collect();
+    assert_eq!(
+        keys,
+        [
+            "crate_version",
+            "algorithm_version",
+            "seed",
+            "scenario_hash"
+        ]
+    );
+}
+
+#[test]
+fn provenance_round_trips_through_a_csv_that_still_reads_back() {
+    let result = start_list();
+    let csv = full().embed(
+        &export_csv(&result, nine(), TimeUnit::Minutes),
+        Embedding::Comments,
+    );
+    assert!(csv.starts_with("# provenance.crate_version: "));
+    assert_eq!(Provenance::extract(&csv).unwrap(), full());
+    assert_eq!(
+        startlist_from_csv(&csv, TimeUnit::Minutes).unwrap().len(),
+        result.len()
+    );
+
+    let saved =
+        try_generate_draw_with_rng(windows(), &config(), &mut StdRng::seed_from_u64(1)).unwrap();
+    let allocation = full().embed(&allocation_csv(&saved, &config()), Embedding::Comments);
+    assert_eq!(windows_from_csv(&allocation).unwrap().len(), 2);
+}
+
+#[test]
+fn provenance_round_trips_through_json_html_and_xml() {
+    let report = compare_configs(&windows(), &config(), &config(), 7).unwrap();
+    let json = full().embed(&report.to_json(), Embedding::Json);
+    assert!(json.starts_with("{\n  \"provenance\": {\"crate_version\": "));
+    assert!(json.contains("\"seed\": 7, \"scenario_hash\": "));
+    assert_eq!(Provenance::extract(&json).unwrap(), full());
+    let empty = Provenance::new(None, &[]).embed("{}", Embedding::Json);
+    assert!(empty.ends_with("\"algorithm_version\": 1}}"), "{}", empty);
+
+    let result = start_list();
+    let html = full().embed(
+        &export_html(&result, nine(), TimeUnit::Minutes, true, &[]),
+        Embedding::Html,
+    );
+    assert!(html.contains("<head>\n<meta name=\"provenance.crate_version\" content=\""));
+    assert!(html.contains("content=\"Åsa &quot;timing&quot; &lt;&amp; co&gt;\""));
+    assert_eq!(Provenance::extract(&html).unwrap(), full());
+
+    let date = NaiveDate::from_ymd_opt(2024, 5, 18).unwrap();
+    let xml = full().embed(
+        &export_iof_xml(&result, nine(), date, TimeUnit::Minutes),
+        Embedding::Xml,
+    );
+    assert!(xml.starts_with("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n<StartList "));
+    assert!(xml.contains(" provenance:seed=\"7\""));
+    assert_eq!(xml.matches("<StartList ").count(), 1);
+    assert_eq!(Provenance::extract(&xml).unwrap(), full());
+}
+
+#[test]
+fn an_artifact_without_provenance_says_so() {
+    let csv = export_csv(&start_list(), nine(), TimeUnit::Minutes);
+    assert_eq!(Provenance::extract(&csv), Err(ProvenanceError::Missing));
+    let tampered = "# provenance.crate_version: 0.1.0\n# provenance.algorithm_version: one\n";
+    assert!(matches!(
+        Provenance::extract(tampered),
+        Err(ProvenanceError::Invalid {
+            key: "algorithm_version",
+            ..
+        })
+    ));
+}
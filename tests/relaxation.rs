@@ -0,0 +1,147 @@
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+use start_list_generator::config::{
+    ConfigError, ConstraintKind, EventConfig, EventConfigBuilder, OffsetPattern, RollingCap,
+};
+use start_list_generator::{
+    try_generate_startlist_relaxed_with_rng, try_generate_startlist_with_rng, Competitor,
+    RelaxedStartList, StartListError, Window,
+};
+
+// five starts two minutes apart fill the ten minutes exactly
+fn windows() -> Vec<Window> {
+    vec![Window::new(
+        10,
+        (0..5).map(|i| Competitor::new(format!("Competitor {}", i))),
+    )]
+}
+
+fn builder() -> EventConfigBuilder {
+    EventConfig::builder().spacing_threshold(2).min_spacing(2)
+}
+
+fn relaxed(config: &EventConfig) -> Result<RelaxedStartList, StartListError> {
+    try_generate_startlist_relaxed_with_rng(windows(), config, &mut StdRng::seed_from_u64(3))
+}
+
+#[test]
+fn a_draw_that_fits_drops_nothing() {
+    let config = builder()
+        .relaxation_order(vec![ConstraintKind::BlockedIntervals])
+        .build()
+        .unwrap();
+    let result = relaxed(&config).unwrap();
+    assert!(result.relaxations_applied.is_empty());
+
+    let plain =
+        try_generate_startlist_with_rng(windows(), &config, &mut StdRng::seed_from_u64(3)).unwrap();
+    let offsets: Vec<_> = result.startlist.iter().map(|c| c.offset).collect();
+    assert_eq!(offsets, plain.iter().map(|c| c.offset).collect::<Vec<_>>());
+}
+
+#[test]
+fn only_the_constraint_in_the_way_is_dropped() {
+    // the cap allows every start; the blocked interval leaves room for three
+    let config = builder()
+        .rolling_cap(RollingCap {
+            starts: 5,
+            minutes: 10,
+        })
+        .blocked_interval(0, 4)
+        .relaxation_order(vec![
+            ConstraintKind::RollingCap,
+            ConstraintKind::BlockedIntervals,
+        ])
+        .build()
+        .unwrap();
+    let result = relaxed(&config).unwrap();
+    assert_eq!(
+        result.relaxations_applied,
+        [ConstraintKind::BlockedIntervals]
+    );
+    assert_eq!(result.startlist.len(), 5);
+    assert_eq!(result.startlist[0].offset, 0);
+}
+
+#[test]
+fn constraints_in_the_way_together_are_all_dropped_in_order() {
+    let config = builder()
+        .rolling_cap(RollingCap {
+            starts: 1,
+            minutes: 3,
+        })
+        .blocked_interval(0, 4)
+        .relaxation_order(vec![
+            ConstraintKind::BlockedIntervals,
+            ConstraintKind::RollingCap,
+        ])
+        .build()
+        .unwrap();
+    let result = relaxed(&config).unwrap();
+    assert_eq!(
+        result.relaxations_applied,
+        [ConstraintKind::BlockedIntervals, ConstraintKind::RollingCap]
+    );
+    assert!(result.startlist.iter().all(|c| c.offset < 10));
+}
+
+#[test]
+fn too_few_allowed_offsets_are_relaxed_too() {
+    // every third minute gives four slots for five competitors
+    let config = builder()
+        .allowed_offsets(OffsetPattern::Every { n: 3, phase: 0 })
+        .relaxation_order(vec![ConstraintKind::AllowedOffsets])
+        .build()
+        .unwrap();
+    let result = relaxed(&config).unwrap();
+    assert_eq!(result.relaxations_applied, [ConstraintKind::AllowedOffsets]);
+    assert_eq!(result.startlist.len(), 5);
+}
+
+#[test]
+fn without_a_relaxation_that_fits_the_error_is_the_unrelaxed_one() {
+    let config = builder()
+        .allowed_offsets(OffsetPattern::Every { n: 3, phase: 0 })
+        .rolling_cap(RollingCap {
+            starts: 5,
+            minutes: 10,
+        })
+        .relaxation_order(vec![ConstraintKind::RollingCap])
+        .build()
+        .unwrap();
+    assert!(matches!(
+        relaxed(&config),
+        Err(StartListError::Config(
+            ConfigError::TooFewAllowedOffsets { .. }
+        ))
+    ));
+
+    let config = builder()
+        .blocked_interval(0, 4)
+        .rolling_cap(RollingCap {
+            starts: 5,
+            minutes: 10,
+        })
+        .build()
+        .unwrap();
+    assert!(matches!(
+        relaxed(&config),
+        Err(StartListError::WindowOverrun { window: 0, .. })
+    ));
+}
+
+#[test]
+fn repeats_in_the_order_are_ignored() {
+    let config = builder()
+        .relaxation_order(vec![
+            ConstraintKind::RollingCap,
+            ConstraintKind::BlockedIntervals,
+            ConstraintKind::RollingCap,
+        ])
+        .build()
+        .unwrap();
+    assert_eq!(
+        config.relaxation_order,
+        [ConstraintKind::RollingCap, ConstraintKind::BlockedIntervals]
+    );
+}
@@ -0,0 +1,172 @@
+mod common;
+
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+use start_list_generator::input::parse_event;
+use start_list_generator::output::export_draw;
+use start_list_generator::sync::{rolling_assign, DrawOperation, SyncError};
+use start_list_generator::{
+    reassign_offsets_with_rng, spacing_violations, try_generate_draw_with_rng, Competitor, Minutes,
+    SavedDraw, Window,
+};
+
+use common::config;
+
+// an open registration: windows nobody has entered yet
+fn empty_draw(durations: &[Minutes]) -> SavedDraw {
+    let windows = durations.iter().map(|&d| Window::new(d, [])).collect();
+    try_generate_draw_with_rng(windows, &config(), &mut StdRng::seed_from_u64(0)).unwrap()
+}
+
+fn batch(prefix: &str, window: usize, count: usize) -> Vec<(usize, Competitor)> {
+    (0..count)
+        .map(|i| (window, Competitor::new(format!("{} {}", prefix, i))))
+        .collect()
+}
+
+fn offsets(operations: &[DrawOperation]) -> Vec<Minutes> {
+    operations
+        .iter()
+        .map(|op| match op {
+            DrawOperation::Assigned { offset, .. } => *offset,
+            _ => panic!("not an assignment: {}", op),
+        })
+        .collect()
+}
+
+#[test]
+fn three_batches_keep_min_spacing_and_log_the_shrinking_pool() {
+    let mut saved = empty_draw(&[30, 30]);
+    let mut rng = StdRng::seed_from_u64(7);
+    for (revision, (prefix, window)) in [("First", 0), ("Second", 1), ("Third", 0)]
+        .into_iter()
+        .enumerate()
+    {
+        rolling_assign(&mut saved, batch(prefix, window, 4), &config(), &mut rng).unwrap();
+        assert_eq!(saved.revision, revision as u32 + 1);
+
+        let result = reassign_offsets_with_rng(&saved, &config(), &mut rng).unwrap();
+        assert_eq!(result.len(), 4 * (revision + 1));
+        assert!(spacing_violations(&result, &[2, 2]).is_empty());
+    }
+    // each window has 15 slots two minutes apart; every entry takes one
+    let pools: Vec<usize> = saved
+        .log
+        .iter()
+        .map(|(_, op)| match op {
+            DrawOperation::Assigned { pool, .. } => *pool,
+            _ => panic!("not an assignment: {}", op),
+        })
+        .collect();
+    assert_eq!(pools, [15, 14, 13, 12, 15, 14, 13, 12, 11, 10, 9, 8]);
+
+    let read = parse_event(&export_draw(&saved, &config())).unwrap();
+    assert_eq!(read.log, saved.log);
+}
+
+#[test]
+fn every_free_slot_is_equally_likely() {
+    let mut counts = [0usize; 15];
+    for seed in 0..3000 {
+        let mut saved = empty_draw(&[30]);
+        let operations = rolling_assign(
+            &mut saved,
+            batch("Entry", 0, 1),
+            &config(),
+            &mut StdRng::seed_from_u64(seed),
+        )
+        .unwrap();
+        let offset = offsets(&operations)[0];
+        assert_eq!(offset % 2, 0);
+        counts[offset as usize / 2] += 1;
+    }
+    // 200 expected each
+    for count in counts {
+        assert!((140..=260).contains(&count), "{:?}", counts);
+    }
+}
+
+#[test]
+fn later_batches_get_no_worse_times_than_earlier_ones() {
+    let mut sums = [0isize; 3];
+    let seeds = 2000;
+    for seed in 0..seeds {
+        let mut saved = empty_draw(&[30]);
+        let mut rng = StdRng::seed_from_u64(seed);
+        for (i, sum) in sums.iter_mut().enumerate() {
+            let operations = rolling_assign(
+                &mut saved,
+                batch(&format!("Batch {}", i), 0, 4),
+                &config(),
+                &mut rng,
+            )
+            .unwrap();
+            *sum += offsets(&operations).iter().sum::<Minutes>();
+        }
+    }
+    // the slots average 14 minutes in
+    for sum in sums {
+        let mean = sum as f64 / (4 * seeds) as f64;
+        assert!((mean - 14.0).abs() < 0.5, "{:?}", sums);
+    }
+}
+
+#[test]
+fn flexible_entries_may_take_an_unlocked_neighbour_s_slots() {
+    let mut windows = [0usize; 2];
+    for seed in 0..200 {
+        let mut saved = empty_draw(&[20, 20]);
+        let mut entry = Competitor::new("Flexible");
+        entry.flexible = true;
+        let operations = rolling_assign(
+            &mut saved,
+            vec![(0, entry)],
+            &config(),
+            &mut StdRng::seed_from_u64(seed),
+        )
+        .unwrap();
+        match &operations[0] {
+            DrawOperation::Assigned { window, pool, .. } => {
+                assert_eq!(*pool, 20);
+                windows[*window] += 1;
+            }
+            op => panic!("not an assignment: {}", op),
+        }
+    }
+    assert!(windows[0] > 50 && windows[1] > 50, "{:?}", windows);
+
+    let mut saved = empty_draw(&[20, 20]);
+    saved.windows[1].locked = true;
+    let mut entry = Competitor::new("Flexible");
+    entry.flexible = true;
+    let operations = rolling_assign(
+        &mut saved,
+        vec![(0, entry)],
+        &config(),
+        &mut StdRng::seed_from_u64(1),
+    )
+    .unwrap();
+    assert!(matches!(
+        operations[0],
+        DrawOperation::Assigned {
+            window: 0,
+            pool: 10,
+            ..
+        }
+    ));
+}
+
+#[test]
+fn a_batch_that_does_not_fit_leaves_the_draw_alone() {
+    let mut saved = empty_draw(&[10]);
+    let error = rolling_assign(
+        &mut saved,
+        batch("Entry", 0, 6),
+        &config(),
+        &mut StdRng::seed_from_u64(1),
+    )
+    .unwrap_err();
+    assert!(matches!(error, SyncError::NoFreeSlot(name) if name == "Entry 5"));
+    assert!(saved.windows[0].competitors.is_empty());
+    assert_eq!(saved.revision, 0);
+}
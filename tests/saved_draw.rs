@@ -0,0 +1,243 @@
+// the seedless API is left out of no-entropy builds
+#![cfg(not(feature = "no-entropy"))]
+
+mod common;
+
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+use start_list_generator::config::EventConfig;
+use start_list_generator::input::parse_event;
+use start_list_generator::output::export_draw;
+use start_list_generator::{
+    reassign_offsets, reassign_offsets_with_rng, try_generate_draw_with_rng,
+    try_generate_startlist_with_rng, Competitor, CompetitorWithOffset, Minutes, SavedDraw,
+    StartListError, Window,
+};
+
+use common::{builder, window};
+
+fn windows() -> Vec<Window> {
+    vec![window(30, 1, 2), window(30, 2, 12), window(30, 3, 4)]
+}
+
+fn config(min_spacing: Minutes) -> EventConfig {
+    builder().min_spacing(min_spacing).build().unwrap()
+}
+
+fn names(result: &[CompetitorWithOffset]) -> Vec<&str> {
+    result.iter().map(|c| c.competitor.name.as_str()).collect()
+}
+
+#[test]
+fn a_draw_and_its_offsets_make_the_start_list() {
+    let mut rng = StdRng::seed_from_u64(5);
+    let whole = try_generate_startlist_with_rng(windows(), &config(2), &mut rng).unwrap();
+
+    let mut rng = StdRng::seed_from_u64(5);
+    let saved = try_generate_draw_with_rng(windows(), &config(2), &mut rng).unwrap();
+    let split = reassign_offsets_with_rng(&saved, &config(2), &mut rng).unwrap();
+    assert_eq!(names(&split), names(&whole));
+    for (a, b) in split.iter().zip(&whole) {
+        assert_eq!(a.offset, b.offset);
+    }
+}
+
+#[test]
+fn retiming_keeps_the_order_of_starts() {
+    let saved =
+        try_generate_draw_with_rng(windows(), &config(2), &mut StdRng::seed_from_u64(9)).unwrap();
+    let before = reassign_offsets(&saved, &config(2)).unwrap();
+    let after = reassign_offsets(&saved, &config(3)).unwrap();
+
+    assert_eq!(names(&after), names(&before));
+    for pair in after.windows(2) {
+        assert!(pair[1].offset - pair[0].offset >= 3);
+    }
+}
+
+#[test]
+fn a_saved_draw_reads_back_from_its_file() {
+    let saved =
+        try_generate_draw_with_rng(windows(), &config(2), &mut StdRng::seed_from_u64(9)).unwrap();
+    let text = export_draw(&saved, &config(2));
+    let read = SavedDraw {
+        windows: parse_event(&text).unwrap().windows,
+        witnesses: Vec::new(),
+        tie_breaks: Vec::new(),
+        displacement_selection: None,
+        thresholds: Vec::new(),
+        diagnostics: Vec::new(),
+        revision: 0,
+        log: Vec::new(),
+    };
+
+    let mut rng = StdRng::seed_from_u64(1);
+    let original = reassign_offsets_with_rng(&saved, &config(2), &mut rng).unwrap();
+    let mut rng = StdRng::seed_from_u64(1);
+    let reread = reassign_offsets_with_rng(&read, &config(2), &mut rng).unwrap();
+    assert_eq!(names(&reread), names(&original));
+    for (a, b) in reread.iter().zip(&original) {
+        assert_eq!((a.offset, a.window), (b.offset, b.window));
+    }
+}
+
+#[test]
+fn a_window_the_new_interval_cannot_fit_is_an_error() {
+    let saved =
+        try_generate_draw_with_rng(windows(), &config(2), &mut StdRng::seed_from_u64(9)).unwrap();
+    let config = EventConfig::builder()
+        .spacing_threshold(15)
+        .min_spacing(15)
+        .build()
+        .unwrap();
+    let result = reassign_offsets(&saved, &config);
+    assert!(matches!(result, Err(StartListError::WindowOverfull { .. })));
+}
+
+const MIXED_ORIGINS: &str = r#"
+[[window]]
+duration = 30
+from_earlier = 2
+from_later = 2
+competitors = ["Anna", "Bo", "Cecilia", "David", "Erik", "Frida"]
+"#;
+
+#[test]
+fn moved_in_blocks_at_both_ends_are_all_placed() {
+    let saved = SavedDraw {
+        windows: parse_event(MIXED_ORIGINS).unwrap().windows,
+        witnesses: Vec::new(),
+        tie_breaks: Vec::new(),
+        displacement_selection: None,
+        thresholds: Vec::new(),
+        diagnostics: Vec::new(),
+        revision: 0,
+        log: Vec::new(),
+    };
+    let result = reassign_offsets(&saved, &config(2)).unwrap();
+    assert_eq!(
+        names(&result),
+        ["Anna", "Bo", "Cecilia", "David", "Erik", "Frida"]
+    );
+}
+
+#[test]
+fn moved_in_competitors_out_of_order_are_an_error() {
+    let mut windows = parse_event(MIXED_ORIGINS).unwrap().windows;
+    // one moved in from the next window now sits among the window's own
+    let frida = windows[0].competitors.pop_back().unwrap();
+    windows[0].competitors.insert(3, frida);
+    let saved = SavedDraw {
+        windows,
+        witnesses: Vec::new(),
+        tie_breaks: Vec::new(),
+        displacement_selection: None,
+        thresholds: Vec::new(),
+        diagnostics: Vec::new(),
+        revision: 0,
+        log: Vec::new(),
+    };
+    let result = reassign_offsets(&saved, &config(2));
+    assert!(matches!(
+        result,
+        Err(StartListError::PlacementMismatch {
+            window: Some(0),
+            entered: 6,
+            ..
+        })
+    ));
+}
+
+#[test]
+fn a_moved_in_competitor_among_the_windows_own_is_an_error() {
+    // Bo came from the window before, but now starts after two of the window's own
+    let mut windows = parse_event(MIXED_ORIGINS).unwrap().windows;
+    let bo = windows[0].competitors.remove(1).unwrap();
+    windows[0].competitors.insert(3, bo);
+    let saved = SavedDraw {
+        windows,
+        witnesses: Vec::new(),
+        tie_breaks: Vec::new(),
+        displacement_selection: None,
+        thresholds: Vec::new(),
+        diagnostics: Vec::new(),
+        revision: 0,
+        log: Vec::new(),
+    };
+    let result = reassign_offsets(&saved, &config(2));
+    assert!(matches!(
+        result,
+        Err(StartListError::PlacementMismatch {
+            window: Some(0),
+            entered: 6,
+            ..
+        })
+    ));
+}
+
+#[test]
+fn a_saved_draw_records_the_spacings_it_was_drawn_with() {
+    let config = EventConfig::builder()
+        .spacing_threshold(4)
+        .min_spacing(3)
+        .build()
+        .unwrap();
+    let saved =
+        try_generate_draw_with_rng(windows(), &config, &mut StdRng::seed_from_u64(9)).unwrap();
+    let read = parse_event(&export_draw(&saved, &config)).unwrap();
+    assert_eq!(
+        (read.spacing_threshold, read.min_spacing),
+        (Some(4), Some(3))
+    );
+}
+
+#[test]
+fn a_saved_draw_keeps_every_window_and_competitor_setting() {
+    let mut first = window(30, 1, 4);
+    first.locked = true;
+    first.spacing_multiplier = 1.5;
+    first.expected_duration = Some(40);
+    for (i, competitor) in first.competitors.iter_mut().enumerate() {
+        competitor.club = (i % 2 == 0).then(|| format!("Club \"{}\"", i));
+        competitor.category = Some(if i < 2 { "H21" } else { "D21" }.to_string());
+    }
+    let mut pinned = Competitor::new("Pinned");
+    pinned.fixed_offset = Some(10);
+    pinned.club = Some("Pin OK".to_string());
+    first.competitors.push_back(pinned);
+    let windows = vec![first, window(30, 2, 3)];
+
+    let saved =
+        try_generate_draw_with_rng(windows, &config(2), &mut StdRng::seed_from_u64(4)).unwrap();
+    let read = parse_event(&export_draw(&saved, &config(2)))
+        .unwrap()
+        .windows;
+
+    for (original, reread) in saved.windows.iter().zip(&read) {
+        assert_eq!(original.locked, reread.locked);
+        assert_eq!(original.spacing_multiplier, reread.spacing_multiplier);
+        assert_eq!(original.expected_duration, reread.expected_duration);
+        let mut settings: Vec<_> = original
+            .competitors
+            .iter()
+            .map(|c| (&c.name, &c.club, &c.category, c.fixed_offset))
+            .collect();
+        let mut reread: Vec<_> = reread
+            .competitors
+            .iter()
+            .map(|c| (&c.name, &c.club, &c.category, c.fixed_offset))
+            .collect();
+        settings.sort();
+        reread.sort();
+        assert_eq!(reread, settings);
+    }
+}
+
+#[test]
+fn a_club_list_of_the_wrong_length_is_an_error() {
+    let text = "[[window]]\nduration = 30\ncompetitors = [\"Anna\", \"Bo\"]\nclubs = [\"OK\"]\n";
+    let error = parse_event(text).err().unwrap();
+    assert!(error
+        .to_string()
+        .contains("'clubs' has 1 entries for 2 competitors"));
+}
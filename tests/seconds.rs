@@ -0,0 +1,81 @@
+use chrono::NaiveTime;
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+use start_list_generator::config::EventConfig;
+use start_list_generator::timefmt::{
+    format_clock, format_offset, offset_of, ClockStyle, Resolution, TimeUnit,
+};
+use start_list_generator::{try_generate_startlist_with_rng, Competitor, Window};
+
+fn seconds_config(spacing_threshold: isize, min_spacing: isize) -> EventConfig {
+    EventConfig::builder()
+        .time_unit(TimeUnit::Seconds)
+        .spacing_threshold(spacing_threshold)
+        .min_spacing(min_spacing)
+        .build()
+        .unwrap()
+}
+
+fn runners(count: usize) -> impl Iterator<Item = Competitor> {
+    (0..count).map(|i| Competitor::new(format!("Runner {}", i)))
+}
+
+#[test]
+fn starts_45_seconds_apart_fit_a_15_minute_window() {
+    let windows = vec![Window::new(15 * 60, runners(20))];
+    let mut rng = StdRng::seed_from_u64(4);
+    let result =
+        try_generate_startlist_with_rng(windows, &seconds_config(45, 45), &mut rng).unwrap();
+
+    assert_eq!(result.len(), 20);
+    for pair in result.windows(2) {
+        assert!(pair[1].offset - pair[0].offset >= 45);
+    }
+    assert!(result.iter().all(|c| (0..15 * 60).contains(&c.offset)));
+}
+
+#[test]
+fn leftover_seconds_are_spread_without_drifting_out_of_the_window() {
+    for seed in 0..20 {
+        let windows = vec![
+            Window::new(10 * 60, runners(7)),
+            Window::new(5 * 60, runners(4)),
+        ];
+        let mut rng = StdRng::seed_from_u64(seed);
+        let result =
+            try_generate_startlist_with_rng(windows, &seconds_config(90, 30), &mut rng).unwrap();
+
+        for pair in result.windows(2) {
+            assert!(pair[1].offset - pair[0].offset >= 30);
+        }
+        for c in &result {
+            let window = if c.offset < 10 * 60 { 0 } else { 1 };
+            assert_eq!(c.window, window, "{} at {}", c.competitor.name, c.offset);
+        }
+        assert!(result.last().unwrap().offset < 15 * 60);
+    }
+}
+
+#[test]
+fn clock_times_show_seconds_when_they_are_not_zero() {
+    let nine = NaiveTime::from_hms_opt(9, 0, 0).unwrap();
+    let seconds = ClockStyle {
+        unit: TimeUnit::Seconds,
+        ..ClockStyle::default()
+    };
+    assert_eq!(format_clock(nine, 90, seconds), "09:01:30");
+    assert_eq!(format_clock(nine, 120, seconds), "09:02");
+    assert_eq!(format_clock(nine, 2, ClockStyle::default()), "09:02");
+    assert_eq!(
+        format_offset(-30, TimeUnit::Seconds, Resolution::Minutes),
+        "-0:00:30"
+    );
+    assert_eq!(
+        format_offset(61, TimeUnit::Minutes, Resolution::Minutes),
+        "1:01"
+    );
+
+    let later = NaiveTime::from_hms_opt(9, 1, 30).unwrap();
+    assert_eq!(offset_of(nine, later, TimeUnit::Seconds), 90);
+    assert_eq!(offset_of(nine, later, TimeUnit::Minutes), 1);
+}
@@ -0,0 +1,56 @@
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+use start_list_generator::config::EventConfig;
+use start_list_generator::output::export_witness;
+use start_list_generator::{try_generate_draw_with_rng, Competitor, Diagnostic, Window};
+
+fn windows() -> Vec<Window> {
+    vec![Window::new(
+        60,
+        (0..10).map(|i| Competitor::new(format!("Competitor {}", i))),
+    )]
+}
+
+#[test]
+fn a_draw_close_to_the_entry_order_raises_the_alarm() {
+    let config = EventConfig::builder().build().unwrap();
+    // this seed swaps only a few neighbours of the ten entries
+    let saved =
+        try_generate_draw_with_rng(windows(), &config, &mut StdRng::seed_from_u64(877)).unwrap();
+
+    assert!(matches!(
+        saved.diagnostics[..],
+        [Diagnostic::ShuffleAlarm { window: 0, correlation }] if correlation > 0.9
+    ));
+}
+
+#[test]
+fn an_ordinary_draw_raises_no_alarm() {
+    let config = EventConfig::builder().build().unwrap();
+    let saved =
+        try_generate_draw_with_rng(windows(), &config, &mut StdRng::seed_from_u64(3)).unwrap();
+
+    assert!(saved.diagnostics.is_empty());
+}
+
+#[test]
+fn an_ordered_draw_keeps_the_entry_order_without_an_alarm() {
+    let config = EventConfig::builder().shuffle(false).build().unwrap();
+    let saved =
+        try_generate_draw_with_rng(windows(), &config, &mut StdRng::seed_from_u64(3)).unwrap();
+
+    let witness = &saved.witnesses[0];
+    assert_eq!(witness.drawn_order, witness.entry_order);
+    assert_eq!(witness.correlation, Some(1.0));
+    assert!(saved.diagnostics.is_empty());
+}
+
+#[test]
+fn the_witness_records_the_correlation() {
+    let config = EventConfig::builder().shuffle(false).build().unwrap();
+    let saved =
+        try_generate_draw_with_rng(windows(), &config, &mut StdRng::seed_from_u64(3)).unwrap();
+
+    let text = export_witness(&saved, Some(3));
+    assert!(text.contains("Window 1\nrank correlation with entry order: 1.00\n"));
+}
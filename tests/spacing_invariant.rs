@@ -0,0 +1,88 @@
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use start_list_generator::config::EventConfig;
+use start_list_generator::{
+    generate_startlist_with_rng, try_generate_startlist_with_rng, Competitor, CompetitorWithOffset,
+    Minutes, Window,
+};
+
+// a random event: spacing, window durations and entries, with an occasional
+// window overriding the config's spacing
+fn random_event(rng: &mut StdRng, overfull: bool) -> (Vec<Window>, EventConfig) {
+    let min_spacing = rng.gen_range(1..=3);
+    let spacing_threshold = min_spacing + rng.gen_range(0..=3);
+    let config = EventConfig::builder()
+        .spacing_threshold(spacing_threshold)
+        .min_spacing(min_spacing)
+        .build()
+        .unwrap();
+    let windows = (0..rng.gen_range(1..=5))
+        .map(|number| {
+            let duration: Minutes = rng.gen_range(1..=40);
+            let mut window = Window::new(duration, std::iter::empty());
+            if rng.gen_bool(0.2) {
+                let min_spacing = rng.gen_range(1..=4);
+                window.min_spacing = Some(min_spacing);
+                window.spacing_threshold = Some(min_spacing + rng.gen_range(0..=2));
+            }
+            let capacity = (duration - 1) / window.min_spacing(&config) + 1;
+            let count = rng.gen_range(0..=if overfull { 2 * capacity } else { capacity });
+            window.competitors = (0..count)
+                .map(|i| Competitor::new(format!("{} Competitor {}", number, i)))
+                .collect();
+            window
+        })
+        .collect();
+    (windows, config)
+}
+
+fn min_spacings(windows: &[Window], config: &EventConfig) -> Vec<Minutes> {
+    windows.iter().map(|w| w.min_spacing(config)).collect()
+}
+
+// offsets strictly increase in list order, each start at least the stricter
+// min_spacing of the two windows after the one before
+fn assert_spaced(result: &[CompetitorWithOffset], min_spacings: &[Minutes], seed: u64) {
+    for pair in result.windows(2) {
+        let required = min_spacings[pair[0].window].max(min_spacings[pair[1].window]);
+        assert!(
+            pair[1].offset - pair[0].offset >= required,
+            "seed {}: {} at {} then {} at {}",
+            seed,
+            pair[0].competitor.name,
+            pair[0].offset,
+            pair[1].competitor.name,
+            pair[1].offset
+        );
+    }
+}
+
+#[test]
+fn feasible_events_give_spaced_increasing_offsets() {
+    for seed in 0..2000 {
+        let mut rng = StdRng::seed_from_u64(seed);
+        let (windows, config) = random_event(&mut rng, false);
+        let min_spacings = min_spacings(&windows, &config);
+        let count: usize = windows.iter().map(|w| w.competitors.len()).sum();
+        let Ok(result) = try_generate_startlist_with_rng(windows, &config, &mut rng) else {
+            continue;
+        };
+
+        assert_eq!(result.len(), count, "seed {}", seed);
+        assert_spaced(&result, &min_spacings, seed);
+    }
+}
+
+#[test]
+fn overfull_events_still_give_spaced_increasing_offsets() {
+    for seed in 0..2000 {
+        let mut rng = StdRng::seed_from_u64(seed);
+        let (windows, config) = random_event(&mut rng, true);
+        let min_spacings = min_spacings(&windows, &config);
+        let count: usize = windows.iter().map(|w| w.competitors.len()).sum();
+        let result = generate_startlist_with_rng(windows, &config, &mut rng);
+
+        assert_eq!(result.len(), count, "seed {}", seed);
+        assert_spaced(&result, &min_spacings, seed);
+    }
+}
@@ -0,0 +1,215 @@
+mod common;
+
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+use start_list_generator::config::EventConfig;
+
+use common::{builder, window};
+#[cfg(not(feature = "no-entropy"))]
+use start_list_generator::output::export_witness;
+#[cfg(not(feature = "no-entropy"))]
+use start_list_generator::TieBreak;
+use start_list_generator::{
+    simulate_move, try_generate_draw_with_rng, try_generate_startlist_with_rng, Minutes, Window,
+};
+
+fn config(spacing_threshold: Minutes) -> EventConfig {
+    builder()
+        .spacing_threshold(spacing_threshold)
+        .min_spacing(1)
+        .build()
+        .unwrap()
+}
+
+fn drawn(windows: Vec<Window>, config: &EventConfig) -> Vec<Vec<String>> {
+    try_generate_draw_with_rng(windows, config, &mut StdRng::seed_from_u64(1))
+        .unwrap()
+        .windows
+        .into_iter()
+        .map(|w| w.competitors.into_iter().map(|c| c.name).collect())
+        .collect()
+}
+
+fn from_window(names: &[String], number: usize) -> usize {
+    let prefix = format!("{} ", number);
+    names
+        .iter()
+        .filter(|name| name.starts_with(&prefix))
+        .count()
+}
+
+#[test]
+fn equally_unbalanced_pairs_move_into_the_earlier_window() {
+    // the middle window is as much too dense against either neighbour
+    let drawn = drawn(tied_windows(), &config(4));
+
+    assert_eq!(from_window(&drawn[0], 2), 1);
+    assert_eq!(from_window(&drawn[2], 2), 0);
+    assert_eq!(drawn[1].len(), 7);
+}
+
+fn tied_windows() -> Vec<Window> {
+    vec![window(30, 1, 2), window(30, 2, 8), window(30, 3, 2)]
+}
+
+// a no-entropy build never breaks a tie at random
+#[cfg(not(feature = "no-entropy"))]
+fn random_tie_break() -> EventConfig {
+    EventConfig::builder()
+        .spacing_threshold(4)
+        .min_spacing(1)
+        .random_tie_break(true)
+        .build()
+        .unwrap()
+}
+
+#[test]
+#[cfg(not(feature = "no-entropy"))]
+fn a_random_tie_break_is_drawn_from_the_rng_and_recorded() {
+    let counts = |seed| {
+        let saved = try_generate_draw_with_rng(
+            tied_windows(),
+            &random_tie_break(),
+            &mut StdRng::seed_from_u64(seed),
+        )
+        .unwrap();
+        let counts: Vec<usize> = saved.windows.iter().map(|w| w.competitors.len()).collect();
+        (saved.tie_breaks, counts)
+    };
+
+    assert_eq!(
+        counts(0),
+        (
+            vec![TieBreak {
+                pairs: vec![0, 1],
+                chosen: 1
+            }],
+            vec![2, 7, 3]
+        )
+    );
+    assert_eq!(
+        counts(1),
+        (
+            vec![TieBreak {
+                pairs: vec![0, 1],
+                chosen: 0
+            }],
+            vec![3, 7, 2]
+        )
+    );
+    assert_eq!(counts(0), counts(0));
+}
+
+#[test]
+fn the_earlier_pair_wins_without_a_random_tie_break() {
+    for seed in 0..5 {
+        let saved = try_generate_draw_with_rng(
+            tied_windows(),
+            &config(4),
+            &mut StdRng::seed_from_u64(seed),
+        )
+        .unwrap();
+        assert!(saved.tie_breaks.is_empty());
+        assert_eq!(saved.windows[0].competitors.len(), 3, "seed {}", seed);
+    }
+}
+
+#[test]
+#[cfg(not(feature = "no-entropy"))]
+fn the_witness_lists_the_tie_breaks() {
+    let saved = try_generate_draw_with_rng(
+        tied_windows(),
+        &random_tie_break(),
+        &mut StdRng::seed_from_u64(0),
+    )
+    .unwrap();
+
+    let text = export_witness(&saved, Some(0));
+    assert!(text.ends_with("\nTie-breaks\nwindows 1/2, 2/3 tied, drew 2/3\n"));
+}
+
+#[test]
+fn two_empty_windows_are_left_alone() {
+    let windows = || vec![window(30, 1, 0), window(30, 2, 0)];
+    assert_eq!(
+        drawn(windows(), &config(3)),
+        [Vec::<String>::new(), Vec::new()]
+    );
+    let result =
+        try_generate_startlist_with_rng(windows(), &config(3), &mut StdRng::seed_from_u64(1));
+    assert!(result.unwrap().is_empty());
+}
+
+#[test]
+fn an_empty_window_next_to_a_full_one_takes_competitors_only_one_way() {
+    for (windows, full) in [
+        (vec![window(30, 1, 0), window(10, 2, 8)], 1),
+        (vec![window(10, 1, 8), window(30, 2, 0)], 0),
+    ] {
+        let result = try_generate_startlist_with_rng(
+            windows.clone(),
+            &config(3),
+            &mut StdRng::seed_from_u64(1),
+        );
+        assert_eq!(result.unwrap().len(), 8);
+        let drawn = drawn(windows, &config(3));
+        assert_eq!(drawn[0].len() + drawn[1].len(), 8);
+        // the empty window only ever receives
+        assert_eq!(
+            from_window(&drawn[1 - full], full + 1),
+            drawn[1 - full].len()
+        );
+        assert!(!drawn[1 - full].is_empty());
+    }
+}
+
+fn adaptive(spacing_threshold: Minutes) -> EventConfig {
+    EventConfig::builder()
+        .spacing_threshold(spacing_threshold)
+        .min_spacing(1)
+        .adaptive_threshold(true)
+        .build()
+        .unwrap()
+}
+
+#[test]
+fn windows_both_above_the_threshold_move_only_in_adaptive_mode() {
+    // ten minutes between starts next to four, both above a threshold of 3
+    let windows = || vec![window(30, 1, 3), window(20, 2, 5)];
+
+    let fixed = drawn(windows(), &config(3));
+    assert_eq!((fixed[0].len(), fixed[1].len()), (3, 5));
+
+    let balanced = drawn(windows(), &adaptive(3));
+    assert_eq!(from_window(&balanced[0], 2), balanced[0].len() - 3);
+    assert!(balanced[0].len() > 3);
+    assert_eq!(balanced[0].len() + balanced[1].len(), 8);
+}
+
+#[test]
+fn adaptive_mode_leaves_balanced_windows_alone() {
+    let windows = vec![window(30, 1, 5), window(30, 2, 5)];
+    let drawn = drawn(windows, &adaptive(3));
+    assert_eq!(
+        (from_window(&drawn[0], 1), from_window(&drawn[1], 2)),
+        (5, 5)
+    );
+}
+
+#[test]
+fn a_simulated_move_reports_the_spacing_without_moving_anyone() {
+    let windows = vec![window(30, 1, 10), window(30, 2, 5)];
+    let stats = simulate_move(&windows, "1 Competitor 3", 1);
+    assert_eq!(stats.spacings, [30.0 / 9.0, 5.0]);
+    assert!((stats.max_diff - (5.0 - 30.0 / 9.0)).abs() < 1e-9);
+    assert_eq!(windows[0].competitors.len(), 10);
+
+    // moving within a window, or nobody, changes nothing
+    let unmoved = simulate_move(&windows, "1 Competitor 3", 0);
+    assert_eq!(unmoved.spacings, [3.0, 6.0]);
+    assert_eq!(simulate_move(&windows, "Nobody", 1), unmoved);
+
+    let mut locked = windows.clone();
+    locked[1].locked = true;
+    assert_eq!(simulate_move(&locked, "1 Competitor 3", 1).max_diff, 0.0);
+}
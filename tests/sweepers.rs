@@ -0,0 +1,79 @@
+mod common;
+
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+use start_list_generator::config::EventConfig;
+use start_list_generator::{try_generate_startlist_with_rng, CompetitorWithOffset, Minutes};
+
+use common::{builder, window};
+
+fn config(sweeper_window: usize) -> EventConfig {
+    builder()
+        .sweeper("Sweeper", sweeper_window)
+        .build()
+        .unwrap()
+}
+
+// the sweeper starts after everyone else in its window, and in it
+fn assert_sweeper_last(result: &[CompetitorWithOffset], window: usize, window_end: Minutes) {
+    let sweeper = result.iter().find(|c| c.competitor.sweeper).unwrap();
+    assert_eq!(sweeper.window, window);
+    assert!(sweeper.offset < window_end, "{}", sweeper.offset);
+    for other in result
+        .iter()
+        .filter(|c| c.window == window && !c.competitor.sweeper)
+    {
+        assert!(
+            other.offset < sweeper.offset,
+            "{} at {} is not before the sweeper at {}",
+            other.competitor.name,
+            other.offset,
+            sweeper.offset
+        );
+    }
+}
+
+#[test]
+fn a_sweeper_starts_last_in_its_window() {
+    for seed in 0..10 {
+        let windows = vec![window(30, 1, 6), window(30, 2, 6), window(30, 3, 6)];
+        let result =
+            try_generate_startlist_with_rng(windows, &config(1), &mut StdRng::seed_from_u64(seed))
+                .unwrap();
+        assert_eq!(result.len(), 19);
+        assert_sweeper_last(&result, 1, 60);
+    }
+}
+
+#[test]
+fn a_sweeper_starts_after_competitors_moved_in_from_the_next_window() {
+    // the dense second window hands competitors to the end of the first
+    for seed in 0..10 {
+        let windows = vec![window(30, 1, 2), window(10, 2, 8)];
+        let result =
+            try_generate_startlist_with_rng(windows, &config(0), &mut StdRng::seed_from_u64(seed))
+                .unwrap();
+        let moved_in = result
+            .iter()
+            .filter(|c| c.window == 0 && c.competitor.name.starts_with("2 "))
+            .count();
+        assert!(moved_in > 0, "seed {}", seed);
+        assert_sweeper_last(&result, 0, 30);
+    }
+}
+
+#[test]
+fn a_sweeper_starts_after_competitors_moved_in_from_the_previous_window() {
+    for seed in 0..10 {
+        let windows = vec![window(10, 1, 8), window(30, 2, 2)];
+        let result =
+            try_generate_startlist_with_rng(windows, &config(1), &mut StdRng::seed_from_u64(seed))
+                .unwrap();
+        let moved_in = result
+            .iter()
+            .filter(|c| c.window == 1 && c.competitor.name.starts_with("1 "))
+            .count();
+        assert!(moved_in > 0, "seed {}", seed);
+        assert_sweeper_last(&result, 1, 40);
+    }
+}
@@ -0,0 +1,261 @@
+mod common;
+
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+use start_list_generator::input::parse_event;
+use start_list_generator::output::export_draw;
+use start_list_generator::sync::{sync_with_rng, DrawOperation, SyncError, SyncPolicy};
+use start_list_generator::{
+    reassign_offsets_with_rng, try_generate_draw_with_rng, Competitor, SavedDraw, StartListError,
+    Window,
+};
+
+use common::{config, window};
+
+fn windows() -> Vec<Window> {
+    vec![window(30, 1, 5), window(30, 2, 8), window(30, 3, 5)]
+}
+
+fn draw() -> SavedDraw {
+    try_generate_draw_with_rng(windows(), &config(), &mut StdRng::seed_from_u64(3)).unwrap()
+}
+
+// the entries the draw was made from
+fn entries() -> Vec<(usize, Competitor)> {
+    windows()
+        .into_iter()
+        .enumerate()
+        .flat_map(|(i, window)| window.competitors.into_iter().map(move |c| (i, c)))
+        .collect()
+}
+
+// every window's starts in order, leaving out `except`
+fn order<'a>(saved: &'a SavedDraw, except: &[&str]) -> Vec<Vec<&'a str>> {
+    saved
+        .windows
+        .iter()
+        .map(|window| {
+            window
+                .competitors
+                .iter()
+                .map(|c| c.name.as_str())
+                .filter(|name| !except.contains(name))
+                .collect()
+        })
+        .collect()
+}
+
+#[test]
+fn unchanged_entries_leave_the_draw_and_its_revision_alone() {
+    let mut saved = draw();
+    let before = saved.clone();
+    let operations = sync_with_rng(
+        &mut saved,
+        &entries(),
+        SyncPolicy::Error,
+        &config(),
+        &mut StdRng::seed_from_u64(1),
+    )
+    .unwrap();
+    assert!(operations.is_empty());
+    assert_eq!(saved.revision, 0);
+    assert_eq!(order(&saved, &[]), order(&before, &[]));
+}
+
+#[test]
+fn late_entries_are_drawn_in_and_nobody_else_moves() {
+    let mut saved = draw();
+    let before = saved.clone();
+    let mut updated = entries();
+    updated.push((0, Competitor::new("Late Anna")));
+    updated.push((2, Competitor::new("Late Bo")));
+
+    let operations = sync_with_rng(
+        &mut saved,
+        &updated,
+        SyncPolicy::Error,
+        &config(),
+        &mut StdRng::seed_from_u64(1),
+    )
+    .unwrap();
+    assert_eq!(
+        operations,
+        [
+            DrawOperation::Added {
+                name: "Late Anna".to_string(),
+                window: 0
+            },
+            DrawOperation::Added {
+                name: "Late Bo".to_string(),
+                window: 2
+            },
+        ]
+    );
+    assert_eq!(saved.revision, 1);
+    assert_eq!(
+        saved.log,
+        operations
+            .iter()
+            .map(|op| (1, op.clone()))
+            .collect::<Vec<_>>()
+    );
+    assert!(saved.windows[0]
+        .competitors
+        .iter()
+        .any(|c| c.name == "Late Anna"));
+    assert!(saved.windows[2]
+        .competitors
+        .iter()
+        .any(|c| c.name == "Late Bo"));
+    assert_eq!(
+        order(&saved, &["Late Anna", "Late Bo"]),
+        order(&before, &[])
+    );
+    assert_eq!(
+        reassign_offsets_with_rng(&saved, &config(), &mut StdRng::seed_from_u64(1))
+            .unwrap()
+            .len(),
+        20
+    );
+}
+
+#[test]
+fn withdrawn_entries_leave_the_draw() {
+    let mut saved = draw();
+    let before = saved.clone();
+    let updated: Vec<_> = entries()
+        .into_iter()
+        .filter(|(_, c)| c.name != "1 Competitor 2" && c.name != "2 Competitor 7")
+        .collect();
+
+    let operations = sync_with_rng(
+        &mut saved,
+        &updated,
+        SyncPolicy::Error,
+        &config(),
+        &mut StdRng::seed_from_u64(1),
+    )
+    .unwrap();
+    assert_eq!(operations.len(), 2);
+    assert!(operations.contains(&DrawOperation::Removed {
+        name: "1 Competitor 2".to_string(),
+        window: 0
+    }));
+    assert!(operations.contains(&DrawOperation::Removed {
+        name: "2 Competitor 7".to_string(),
+        window: 1
+    }));
+    assert_eq!(
+        order(&saved, &[]),
+        order(&before, &["1 Competitor 2", "2 Competitor 7"])
+    );
+}
+
+#[test]
+fn a_mixed_update_removes_adds_and_moves_between_windows() {
+    let mut saved = draw();
+    let before = saved.clone();
+    let mut updated: Vec<_> = entries()
+        .into_iter()
+        .filter(|(_, c)| c.name != "2 Competitor 0")
+        .map(|(window, c)| {
+            if c.name == "3 Competitor 4" {
+                (0, c)
+            } else {
+                (window, c)
+            }
+        })
+        .collect();
+    updated.push((1, Competitor::new("Late Cecilia")));
+
+    let operations = sync_with_rng(
+        &mut saved,
+        &updated,
+        SyncPolicy::Error,
+        &config(),
+        &mut StdRng::seed_from_u64(1),
+    )
+    .unwrap();
+    assert_eq!(operations.len(), 3);
+    assert!(operations.contains(&DrawOperation::Removed {
+        name: "2 Competitor 0".to_string(),
+        window: 1
+    }));
+    assert!(operations.contains(&DrawOperation::Moved {
+        name: "3 Competitor 4".to_string(),
+        from: 2,
+        to: 0
+    }));
+    assert!(operations.contains(&DrawOperation::Added {
+        name: "Late Cecilia".to_string(),
+        window: 1
+    }));
+    assert!(saved.windows[0]
+        .competitors
+        .iter()
+        .any(|c| c.name == "3 Competitor 4"));
+    let changed = ["2 Competitor 0", "3 Competitor 4", "Late Cecilia"];
+    assert_eq!(order(&saved, &changed), order(&before, &changed));
+}
+
+#[test]
+fn an_oversubscribed_window_is_an_error_or_redrawn() {
+    let mut updated = entries();
+    updated.extend((0..12).map(|i| (1, Competitor::new(format!("Late {}", i)))));
+
+    let mut saved = draw();
+    let before = saved.clone();
+    let error = sync_with_rng(
+        &mut saved,
+        &updated,
+        SyncPolicy::Error,
+        &config(),
+        &mut StdRng::seed_from_u64(1),
+    )
+    .unwrap_err();
+    assert!(matches!(
+        error,
+        SyncError::Oversubscribed(StartListError::WindowOverfull { window: 1, .. })
+    ));
+    assert_eq!(saved.revision, 0);
+    assert_eq!(order(&saved, &[]), order(&before, &[]));
+
+    let operations = sync_with_rng(
+        &mut saved,
+        &updated,
+        SyncPolicy::Redraw,
+        &config(),
+        &mut StdRng::seed_from_u64(1),
+    )
+    .unwrap();
+    assert_eq!(
+        operations.last(),
+        Some(&DrawOperation::Redrawn { window: 1 })
+    );
+    assert_eq!(
+        reassign_offsets_with_rng(&saved, &config(), &mut StdRng::seed_from_u64(1))
+            .unwrap()
+            .len(),
+        30
+    );
+}
+
+#[test]
+fn the_revision_and_log_survive_a_saved_draw_file() {
+    let mut saved = draw();
+    let mut updated = entries();
+    updated.retain(|(_, c)| c.name != "1 Competitor 0");
+    updated.push((2, Competitor::new("Late Dan")));
+    sync_with_rng(
+        &mut saved,
+        &updated,
+        SyncPolicy::Error,
+        &config(),
+        &mut StdRng::seed_from_u64(1),
+    )
+    .unwrap();
+
+    let read = parse_event(&export_draw(&saved, &config())).unwrap();
+    assert_eq!(read.revision, 1);
+    assert_eq!(read.log, saved.log);
+}
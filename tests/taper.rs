@@ -0,0 +1,157 @@
+mod common;
+
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+use start_list_generator::config::{ConfigError, EventConfig, EventConfigBuilder};
+use start_list_generator::{
+    tapered_starts, try_generate_startlist_with_rng, CompetitorWithOffset, Minutes, StartListError,
+};
+
+use common::window;
+
+fn builder() -> EventConfigBuilder {
+    EventConfig::builder()
+        .spacing_threshold(3)
+        .min_spacing(2)
+        .taper(10, 2.0)
+}
+
+fn in_zones(offset: Minutes, zones: &[(Minutes, Minutes)]) -> bool {
+    zones
+        .iter()
+        .any(|(from, to)| (*from..*to).contains(&offset))
+}
+
+// every pair of starts touching a zone is at least 4 apart, every other pair
+// at least 2; returns the gaps of the pairs outside the zones
+fn outside_gaps(result: &[CompetitorWithOffset], zones: &[(Minutes, Minutes)]) -> Vec<Minutes> {
+    let mut outside = Vec::new();
+    for pair in result.windows(2) {
+        let gap = pair[1].offset - pair[0].offset;
+        if in_zones(pair[0].offset, zones) || in_zones(pair[1].offset, zones) {
+            assert!(
+                gap >= 4,
+                "{} and {} are {} apart",
+                pair[0].offset,
+                pair[1].offset,
+                gap
+            );
+        } else {
+            assert!(
+                gap >= 2,
+                "{} and {} are {} apart",
+                pair[0].offset,
+                pair[1].offset,
+                gap
+            );
+            outside.push(gap);
+        }
+    }
+    outside
+}
+
+#[test]
+fn starts_near_the_event_start_and_end_keep_double_spacing() {
+    let config = builder().build().unwrap();
+    assert_eq!(config.taper_zones(60), [(0, 10), (50, 60)]);
+    for seed in 0..20 {
+        let result = try_generate_startlist_with_rng(
+            vec![window(60, 1, 20)],
+            &config,
+            &mut StdRng::seed_from_u64(seed),
+        )
+        .unwrap();
+        assert_eq!(result.len(), 20);
+        assert!(result.iter().all(|c| (0..60).contains(&c.offset)));
+        // the middle of the event keeps the plain spacing
+        let outside = outside_gaps(&result, &config.taper_zones(60));
+        assert!(outside.iter().any(|&gap| gap < 4), "{:?}", outside);
+
+        let tapered = result
+            .iter()
+            .filter(|c| c.offset < 10 || c.offset >= 50)
+            .count();
+        assert!(tapered > 0);
+        assert_eq!(tapered_starts(&result, 60, &config), tapered);
+    }
+}
+
+#[test]
+fn the_capacity_check_counts_the_tapered_slots() {
+    // 30 starts fit two minutes apart, but only 24 with the zones tapered:
+    // 0, 4, 8, 12, 14, ..., 48, 52, 56
+    let config = builder().build().unwrap();
+    let result = try_generate_startlist_with_rng(
+        vec![window(60, 1, 24)],
+        &config,
+        &mut StdRng::seed_from_u64(1),
+    );
+    assert_eq!(result.unwrap().len(), 24);
+    let error = try_generate_startlist_with_rng(
+        vec![window(60, 1, 25)],
+        &config,
+        &mut StdRng::seed_from_u64(1),
+    )
+    .unwrap_err();
+    assert!(
+        matches!(
+            error,
+            StartListError::WindowOverfull {
+                competitors: 25,
+                capacity: 24,
+                ..
+            }
+        ),
+        "{}",
+        error
+    );
+
+    let untapered = EventConfig::builder()
+        .spacing_threshold(3)
+        .min_spacing(2)
+        .build()
+        .unwrap();
+    assert!(try_generate_startlist_with_rng(
+        vec![window(60, 1, 25)],
+        &untapered,
+        &mut StdRng::seed_from_u64(1),
+    )
+    .is_ok());
+}
+
+#[test]
+fn a_mid_event_break_tapers_out_before_it_and_in_after_it() {
+    let config = builder().blocked_interval(40, 50).build().unwrap();
+    let zones = config.taper_zones(90);
+    assert_eq!(zones, [(0, 10), (80, 90), (30, 40), (50, 60)]);
+    for seed in 0..20 {
+        let result = try_generate_startlist_with_rng(
+            vec![window(45, 1, 14), window(45, 2, 14)],
+            &config,
+            &mut StdRng::seed_from_u64(seed),
+        )
+        .unwrap();
+        assert_eq!(result.len(), 28);
+        assert!(result.iter().all(|c| !(40..50).contains(&c.offset)));
+        for c in &result {
+            let (start, end) = if c.window == 0 { (0, 45) } else { (45, 90) };
+            assert!((start..end).contains(&c.offset), "{:?}", c);
+        }
+        outside_gaps(&result, &zones);
+        // the break's zones have starts to taper
+        assert!(result.iter().any(|c| (30..40).contains(&c.offset)));
+        assert!(result.iter().any(|c| (50..60).contains(&c.offset)));
+    }
+}
+
+#[test]
+fn a_taper_needs_a_zone_and_a_factor_of_at_least_one() {
+    assert!(matches!(
+        builder().taper(0, 2.0).build(),
+        Err(ConfigError::InvalidTaper(_))
+    ));
+    assert!(matches!(
+        builder().taper(5, 0.5).build(),
+        Err(ConfigError::InvalidTaper(_))
+    ));
+}
@@ -0,0 +1,115 @@
+use chrono::NaiveTime;
+use start_list_generator::config::EventConfig;
+use start_list_generator::timefmt::{
+    format_clock, format_offset, parse_offset, ClockStyle, Resolution, TimeUnit,
+};
+use start_list_generator::{window_clock_ranges, Minutes, Window};
+
+// (offset, unit, offset as written, clock after 00:30)
+const TABLE: [(Minutes, TimeUnit, &str, &str); 12] = [
+    (-1, TimeUnit::Minutes, "-0:01", "00:29"),
+    (0, TimeUnit::Minutes, "0:00", "00:30"),
+    (59, TimeUnit::Minutes, "0:59", "01:29"),
+    (60, TimeUnit::Minutes, "1:00", "01:30"),
+    (1439, TimeUnit::Minutes, "23:59", "00:29 +1d"),
+    (1441, TimeUnit::Minutes, "24:01", "00:31 +1d"),
+    (-31, TimeUnit::Minutes, "-0:31", "23:59 -1d"),
+    (-1441, TimeUnit::Minutes, "-24:01", "00:29 -1d"),
+    (30, TimeUnit::Seconds, "0:00:30", "00:30:30"),
+    (-30, TimeUnit::Seconds, "-0:00:30", "00:29:30"),
+    (3601, TimeUnit::Seconds, "1:00:01", "01:30:01"),
+    (-1830, TimeUnit::Seconds, "-0:30:30", "23:59:30 -1d"),
+];
+
+#[test]
+fn offsets_and_clock_times_are_written_and_read_back() {
+    let zero = NaiveTime::from_hms_opt(0, 30, 0).unwrap();
+    for (offset, unit, written, clock) in TABLE {
+        let style = ClockStyle {
+            unit,
+            ..ClockStyle::default()
+        };
+        assert_eq!(format_offset(offset, unit, Resolution::Minutes), written);
+        assert_eq!(format_clock(zero, offset, style), clock, "{}", offset);
+        assert_eq!(parse_offset(written, unit), Ok(offset), "{}", written);
+
+        let with_seconds = format_offset(offset, unit, Resolution::Seconds);
+        assert_eq!(
+            parse_offset(&with_seconds, unit),
+            Ok(offset),
+            "{}",
+            with_seconds
+        );
+    }
+}
+
+#[test]
+fn a_plain_number_counts_the_unit() {
+    assert_eq!(parse_offset("90", TimeUnit::Minutes), Ok(90));
+    assert_eq!(parse_offset("90", TimeUnit::Seconds), Ok(90));
+    assert_eq!(parse_offset("-5", TimeUnit::Minutes), Ok(-5));
+}
+
+#[test]
+fn a_written_offset_is_converted_to_the_unit() {
+    assert_eq!(parse_offset("1:30", TimeUnit::Seconds), Ok(5400));
+    assert_eq!(parse_offset("0:02:00", TimeUnit::Minutes), Ok(2));
+}
+
+#[test]
+fn seconds_an_event_in_minutes_cannot_count_are_an_error() {
+    assert!(parse_offset("0:00:30", TimeUnit::Minutes).is_err());
+}
+
+#[test]
+fn malformed_offsets_are_errors() {
+    for value in [
+        "",
+        "-",
+        "1:5",
+        "1:60",
+        "0:00:60",
+        "1:00:5",
+        "1:00:00:00",
+        "a:00",
+        "1.5",
+    ] {
+        assert!(parse_offset(value, TimeUnit::Minutes).is_err(), "{}", value);
+    }
+}
+
+#[test]
+fn windows_are_written_as_clock_ranges() {
+    let windows = vec![
+        Window::new(30, []),
+        Window::new(45, []),
+        Window::new(20, []),
+    ];
+    let config = EventConfig::builder()
+        .start_time(NaiveTime::from_hms_opt(22, 45, 0).unwrap())
+        .build()
+        .unwrap();
+    assert_eq!(
+        window_clock_ranges(&windows, &config),
+        [
+            ("22:45".to_string(), "23:15".to_string()),
+            ("23:15".to_string(), "00:00 +1d".to_string()),
+            ("00:00 +1d".to_string(), "00:20 +1d".to_string()),
+        ]
+    );
+
+    let windows = vec![Window::new(90, []), Window::new(600, [])];
+    let config = EventConfig::builder()
+        .time_unit(TimeUnit::Seconds)
+        .spacing_threshold(60)
+        .min_spacing(30)
+        .build()
+        .unwrap();
+    assert_eq!(
+        window_clock_ranges(&windows, &config),
+        [
+            ("09:00".to_string(), "09:01:30".to_string()),
+            ("09:01:30".to_string(), "09:11:30".to_string()),
+        ]
+    );
+}
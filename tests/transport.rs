@@ -0,0 +1,67 @@
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+use start_list_generator::config::EventConfig;
+use start_list_generator::transport::transport_report;
+use start_list_generator::{
+    spacing_violations, try_generate_startlist_with_rng, Competitor, CompetitorWithOffset, Window,
+};
+
+fn window(duration: isize, names: &[&str]) -> Window {
+    let mut window = Window::new(duration, names.iter().map(|&name| Competitor::new(name)));
+    window.locked = true;
+    window
+}
+
+// shuttles arriving 10 minutes before the event start and 20 minutes after it
+fn config(repair: bool) -> EventConfig {
+    EventConfig::builder()
+        .spacing_threshold(3)
+        .min_spacing(2)
+        .shuttle(-30, 20)
+        .shuttle(0, 20)
+        .transport_margin(15)
+        .repair_transport(repair)
+        .build()
+        .unwrap()
+}
+
+fn draw(windows: Vec<Window>, config: &EventConfig) -> Vec<CompetitorWithOffset> {
+    try_generate_startlist_with_rng(windows, config, &mut StdRng::seed_from_u64(5)).unwrap()
+}
+
+#[test]
+fn the_first_starter_becomes_reachable_after_repair() {
+    let names = ["Anna", "Bo", "Cecilia", "David", "Erik"];
+    let unrepaired = draw(vec![window(60, &names)], &config(false));
+    let report = transport_report(&unrepaired, &config(false));
+    assert_eq!(report.stranded.len(), 1);
+    assert_eq!(report.stranded[0].offset, 0);
+
+    let config = config(true);
+    let repaired = draw(vec![window(60, &names)], &config);
+    let report = transport_report(&repaired, &config);
+    assert!(report.stranded.is_empty());
+    assert_eq!(repaired[0].competitor.name, unrepaired[0].competitor.name);
+    assert_eq!(repaired[0].offset, 5);
+    assert!(spacing_violations(&repaired, &[2]).is_empty());
+    assert!(repaired.iter().all(|c| (0..60).contains(&c.offset)));
+
+    let late = repaired.iter().filter(|c| c.offset >= 35).count();
+    assert_eq!(report.riders, [5 - late, late]);
+}
+
+#[test]
+fn a_window_ending_before_any_shuttle_arrives_cannot_be_repaired() {
+    let config = config(true);
+    let result = draw(
+        vec![window(4, &["Anna"]), window(56, &["Bo", "Cecilia"])],
+        &config,
+    );
+    let report = transport_report(&result, &config);
+
+    assert_eq!(report.stranded.len(), 1);
+    assert_eq!(report.stranded[0].competitor.name, "Anna");
+    // moved as late as the window allows, but no later
+    assert_eq!(report.stranded[0].offset, 3);
+    assert_eq!(report.riders.iter().sum::<usize>(), 2);
+}
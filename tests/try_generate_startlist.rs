@@ -0,0 +1,208 @@
+// the seedless API is left out of no-entropy builds
+#![cfg(not(feature = "no-entropy"))]
+
+mod common;
+
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+use start_list_generator::config::{ConfigError, EventConfig};
+use start_list_generator::{
+    try_generate_startlist, try_generate_startlist_with_rng, window_boundaries, Competitor,
+    Minutes, StartListError, Window,
+};
+
+use common::config;
+
+fn window(duration: Minutes, count: usize) -> Window {
+    Window::new(
+        duration,
+        (0..count).map(|i| Competitor::new(format!("Competitor {}", i))),
+    )
+}
+
+#[test]
+fn well_formed_windows_are_drawn() {
+    let windows = vec![window(30, 2), window(30, 15), window(30, 4)];
+    assert_eq!(
+        try_generate_startlist(windows, &config()).unwrap().len(),
+        21
+    );
+}
+
+#[test]
+fn no_windows_is_an_error() {
+    let error = try_generate_startlist(vec![], &config()).err().unwrap();
+    assert!(matches!(error, StartListError::NoWindows));
+}
+
+#[test]
+fn a_zero_duration_window_is_an_error() {
+    let windows = vec![window(30, 2), window(0, 3)];
+    let error = try_generate_startlist(windows, &config()).err().unwrap();
+    assert!(matches!(
+        error,
+        StartListError::NonPositiveDuration {
+            window: 1,
+            duration: 0
+        }
+    ));
+}
+
+#[test]
+fn a_window_too_short_for_its_competitors_is_an_error() {
+    // ten minutes fit five starts two minutes apart, and there is no
+    // neighbour to take the rest
+    let error = try_generate_startlist(vec![window(10, 8)], &config())
+        .err()
+        .unwrap();
+    assert!(matches!(
+        error,
+        StartListError::WindowOverfull {
+            window: 0,
+            competitors: 8,
+            capacity: 5,
+            ..
+        }
+    ));
+}
+
+#[test]
+fn an_overfull_window_names_who_does_not_fit() {
+    // unshuffled, the last three entered are last in draw order
+    let config = EventConfig::builder().shuffle(false).build().unwrap();
+    let error = try_generate_startlist(vec![window(10, 8)], &config)
+        .err()
+        .unwrap();
+    let StartListError::WindowOverfull { ref unplaced, .. } = error else {
+        panic!("{:?}", error);
+    };
+    assert_eq!(unplaced, &["Competitor 5", "Competitor 6", "Competitor 7"]);
+    assert!(error
+        .to_string()
+        .ends_with("; Competitor 5, Competitor 6, Competitor 7 do not fit"));
+}
+
+#[test]
+fn config_checks_against_the_windows_are_errors() {
+    let config = EventConfig::builder()
+        .sweeper("Official", 3)
+        .build()
+        .unwrap();
+    let error = try_generate_startlist(vec![window(30, 2)], &config)
+        .err()
+        .unwrap();
+    assert!(matches!(
+        error,
+        StartListError::Config(ConfigError::SweeperWindowOutOfRange { .. })
+    ));
+}
+
+#[test]
+fn a_window_relieved_by_its_neighbour_is_drawn() {
+    // the same overfull window as above fits once stabilization can move
+    // three of its competitors into a roomy neighbour
+    let windows = vec![window(10, 8), window(30, 2)];
+    let result = try_generate_startlist(windows, &config()).unwrap();
+    assert_eq!(result.len(), 10);
+    assert!(result.iter().filter(|c| c.window == 0).count() <= 5);
+}
+
+#[test]
+fn a_window_spacing_above_its_threshold_is_an_error() {
+    let mut wide = window(30, 2);
+    wide.min_spacing = Some(4);
+    let error = try_generate_startlist(vec![window(30, 2), wide], &config())
+        .err()
+        .unwrap();
+    assert!(matches!(
+        error,
+        StartListError::WindowSpacing {
+            window: 1,
+            min_spacing: 4,
+            spacing_threshold: 3
+        }
+    ));
+}
+
+#[test]
+fn a_min_spacing_above_the_threshold_is_rejected_by_the_builder() {
+    let error = EventConfig::builder()
+        .spacing_threshold(3)
+        .min_spacing(4)
+        .build()
+        .err()
+        .unwrap();
+    assert!(matches!(
+        error,
+        ConfigError::MinSpacingAboveThreshold {
+            min_spacing: 4,
+            spacing_threshold: 3
+        }
+    ));
+}
+
+#[test]
+fn a_block_pushing_starts_past_their_window_is_an_error() {
+    // minutes 20 to 34 are closed, leaving room for ten of the first
+    // window's fourteen
+    let config = EventConfig::builder()
+        .spacing_threshold(3)
+        .min_spacing(2)
+        .blocked_interval(20, 35)
+        .build()
+        .unwrap();
+    let error = try_generate_startlist(vec![window(30, 14), window(30, 14)], &config)
+        .err()
+        .unwrap();
+    let StartListError::WindowOverrun { window, names, .. } = error else {
+        panic!("{:?}", error);
+    };
+    assert_eq!((window, names.len()), (0, 4));
+}
+
+#[test]
+fn a_squeezed_moved_in_block_leaves_room_for_the_window_s_own() {
+    // the third window takes eight of the second's competitors; squeezing
+    // them up to its top block left its own starts no room
+    let windows = [(34, 14), (65, 31), (26, 8), (36, 2), (40, 20), (21, 6)]
+        .into_iter()
+        .enumerate()
+        .map(|(number, (duration, count))| {
+            Window::new(
+                duration,
+                (0..count).map(move |i| Competitor::new(format!("{}-{}", number + 1, i))),
+            )
+        })
+        .collect();
+    let config = EventConfig::builder().build().unwrap();
+    let result = try_generate_startlist_with_rng(
+        windows,
+        &config,
+        &mut StdRng::seed_from_u64(5834068306601209673),
+    )
+    .unwrap();
+    assert_eq!(result.len(), 81);
+    let bounds = window_boundaries(&[34, 65, 26, 36, 40, 21].map(|d| Window::new(d, [])));
+    for c in &result {
+        let (first, end) = bounds[c.window];
+        assert!((first..end).contains(&c.offset), "{:?}", c);
+    }
+}
+
+#[test]
+fn the_spacing_after_a_full_window_counts_against_the_next_one() {
+    // each window alone fits its starts two minutes apart, but the second's
+    // first start must keep two minutes from the first's last, at minute 16
+    let windows = vec![window(17, 9), window(49, 25)];
+    let config = EventConfig::builder().build().unwrap();
+    let error = try_generate_startlist(windows, &config).err().unwrap();
+    assert!(matches!(
+        error,
+        StartListError::WindowOverfull {
+            window: 1,
+            competitors: 25,
+            capacity: 24,
+            ..
+        }
+    ));
+}
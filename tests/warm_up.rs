@@ -0,0 +1,94 @@
+mod common;
+
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+use start_list_generator::config::EventConfig;
+use start_list_generator::{
+    spacing_violations, try_generate_startlist_with_rng, Competitor, CompetitorWithOffset, Minutes,
+    StartListError, Window,
+};
+
+use common::{builder, window};
+
+fn config(first_start_offset: Minutes) -> EventConfig {
+    builder()
+        .first_start_offset(first_start_offset)
+        .build()
+        .unwrap()
+}
+
+fn draw(
+    windows: Vec<Window>,
+    first_start_offset: Minutes,
+    seed: u64,
+) -> Result<Vec<CompetitorWithOffset>, StartListError> {
+    try_generate_startlist_with_rng(
+        windows,
+        &config(first_start_offset),
+        &mut StdRng::seed_from_u64(seed),
+    )
+}
+
+fn assert_after_buffer(result: &[CompetitorWithOffset], buffer: Minutes, end: Minutes) {
+    assert!(result.iter().all(|c| (buffer..end).contains(&c.offset)));
+    assert!(spacing_violations(result, &[2, 2]).is_empty());
+}
+
+#[test]
+fn the_first_start_waits_for_the_buffer() {
+    for seed in 0..10 {
+        let result = draw(vec![window(30, 1, 10)], 5, seed).unwrap();
+        assert_eq!(result.len(), 10);
+        assert_eq!(result[0].offset, 5);
+        assert_after_buffer(&result, 5, 30);
+    }
+}
+
+#[test]
+fn a_window_inside_the_buffer_passes_its_competitors_on() {
+    // nobody may start in the first window, so its three move to the second
+    // as a moved-in block, which starts at the end of the buffer
+    for seed in 0..10 {
+        let result = draw(vec![window(30, 1, 3), window(30, 2, 5)], 35, seed).unwrap();
+        assert_eq!(result.len(), 8);
+        assert_eq!(result[0].offset, 35);
+        assert!(result[0].competitor.name.starts_with("1 "));
+        assert_after_buffer(&result, 35, 60);
+    }
+}
+
+#[test]
+fn starts_moved_back_into_the_first_window_keep_out_of_the_buffer() {
+    for seed in 0..10 {
+        let result = draw(vec![window(30, 1, 0), window(30, 2, 15)], 25, seed).unwrap();
+        assert_eq!(result.len(), 15);
+        assert_after_buffer(&result, 25, 60);
+    }
+}
+
+#[test]
+fn the_buffer_takes_its_minutes_out_of_the_capacity() {
+    // from minute 25, a 30-minute window fits three starts two minutes apart
+    let error = draw(vec![window(30, 1, 10)], 25, 1).unwrap_err();
+    assert!(matches!(
+        error,
+        StartListError::WindowOverfull {
+            window: 0,
+            competitors: 10,
+            capacity: 3,
+            ..
+        }
+    ));
+}
+
+#[test]
+fn a_pinned_start_inside_the_buffer_is_an_error() {
+    let mut official = Competitor::new("Official");
+    official.fixed_offset = Some(3);
+    let windows = vec![Window::new(
+        30,
+        window(30, 1, 4).competitors.into_iter().chain([official]),
+    )];
+    let error = draw(windows, 5, 1).unwrap_err();
+    assert!(matches!(error, StartListError::PinnedBeforeStart(ref name) if name == "Official"));
+}
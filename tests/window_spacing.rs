@@ -0,0 +1,158 @@
+mod common;
+
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+use start_list_generator::config::{EventConfig, OffsetPattern};
+use start_list_generator::{
+    spacing_violations, try_generate_startlist_with_rng, CompetitorWithOffset, Minutes,
+    StartListError, Window,
+};
+
+use common::{config, window};
+
+fn draw(windows: Vec<Window>) -> Result<Vec<CompetitorWithOffset>, StartListError> {
+    try_generate_startlist_with_rng(windows, &config(), &mut StdRng::seed_from_u64(3))
+}
+
+fn gaps_in(result: &[CompetitorWithOffset], window: usize) -> Vec<Minutes> {
+    let offsets: Vec<Minutes> = result
+        .iter()
+        .filter(|c| c.window == window)
+        .map(|c| c.offset)
+        .collect();
+    offsets.windows(2).map(|pair| pair[1] - pair[0]).collect()
+}
+
+#[test]
+fn a_window_can_pack_tighter_than_the_config() {
+    // 15 starts do not fit 20 minutes at the config's 2-minute min_spacing
+    assert!(matches!(
+        draw(vec![window(20, 1, 15)]),
+        Err(StartListError::WindowOverfull { .. })
+    ));
+
+    let recreational = Window {
+        min_spacing: Some(1),
+        spacing_threshold: Some(1),
+        ..window(20, 1, 15)
+    };
+    let result = draw(vec![recreational]).unwrap();
+    assert_eq!(result.len(), 15);
+    assert!(gaps_in(&result, 0).iter().all(|&gap| gap >= 1));
+}
+
+#[test]
+fn a_window_can_keep_wider_gaps_than_the_config() {
+    let elite = Window {
+        min_spacing: Some(4),
+        spacing_threshold: Some(4),
+        ..window(40, 1, 8)
+    };
+    let result = draw(vec![elite, window(30, 2, 10)]).unwrap();
+    assert!(gaps_in(&result, 0).iter().all(|&gap| gap >= 4));
+    assert!(gaps_in(&result, 1).iter().all(|&gap| gap >= 2));
+}
+
+#[test]
+fn stabilization_weighs_spacing_against_each_windows_threshold() {
+    let count = |result: &[CompetitorWithOffset], window| {
+        result.iter().filter(|c| c.window == window).count()
+    };
+    // 2.5 against 5 minutes apart: the crowded window gives competitors away
+    let result = draw(vec![window(30, 1, 12), window(30, 2, 6)]).unwrap();
+    assert!(count(&result, 0) < 12);
+
+    // a window that aims for 6-minute gaps is as far below its threshold as
+    // the crowded one is below the config's, so nobody moves
+    let spacious = Window {
+        spacing_threshold: Some(6),
+        ..window(30, 2, 6)
+    };
+    let result = draw(vec![window(30, 1, 12), spacious]).unwrap();
+    assert_eq!((count(&result, 0), count(&result, 1)), (12, 6));
+}
+
+#[test]
+fn an_override_above_the_windows_threshold_is_an_error() {
+    let window = Window {
+        min_spacing: Some(4),
+        ..window(30, 1, 5)
+    };
+    assert!(matches!(
+        draw(vec![window]),
+        Err(StartListError::WindowSpacing {
+            window: 0,
+            min_spacing: 4,
+            spacing_threshold: 3,
+        })
+    ));
+}
+
+fn loose_config() -> EventConfig {
+    EventConfig::builder()
+        .spacing_threshold(3)
+        .min_spacing(1)
+        .allowed_offsets(OffsetPattern::odd())
+        .build()
+        .unwrap()
+}
+
+#[test]
+fn allowed_offsets_keep_a_windows_own_min_spacing() {
+    let elite = Window {
+        min_spacing: Some(3),
+        ..window(30, 1, 8)
+    };
+    let windows = vec![elite, window(30, 2, 6)];
+    let min_spacings = [3, 1];
+    for seed in 0..20 {
+        let result = try_generate_startlist_with_rng(
+            windows.clone(),
+            &loose_config(),
+            &mut StdRng::seed_from_u64(seed),
+        )
+        .unwrap();
+
+        assert!(result
+            .iter()
+            .all(|c| c.offset % 2 == 1 || c.competitor.ceremonial));
+        assert!(
+            spacing_violations(&result, &min_spacings).is_empty(),
+            "seed {}: {:?}",
+            seed,
+            gaps_in(&result, 0)
+        );
+    }
+}
+
+#[test]
+fn a_ceremonial_start_is_cleared_by_its_windows_min_spacing() {
+    let config = EventConfig::builder()
+        .spacing_threshold(3)
+        .min_spacing(1)
+        .ceremonial_start("Chair", 40)
+        .build()
+        .unwrap();
+    let elite = Window {
+        min_spacing: Some(3),
+        ..window(30, 2, 8)
+    };
+    let windows = vec![window(30, 1, 6), elite];
+    for seed in 0..20 {
+        let result = try_generate_startlist_with_rng(
+            windows.clone(),
+            &config,
+            &mut StdRng::seed_from_u64(seed),
+        )
+        .unwrap();
+
+        assert!(
+            result
+                .iter()
+                .filter(|c| !c.competitor.ceremonial)
+                .all(|c| (c.offset - 40).abs() >= 3),
+            "seed {}",
+            seed
+        );
+    }
+}
@@ -0,0 +1,116 @@
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+use start_list_generator::config::{DisplacementSelection, EventConfig};
+use start_list_generator::output::export_witness;
+use start_list_generator::{try_generate_draw_with_rng, Competitor, Window};
+
+fn windows() -> Vec<Window> {
+    (1..=3)
+        .map(|number| {
+            Window::new(
+                30,
+                (0..6).map(move |i| Competitor::new(format!("{} Competitor {}", number, i))),
+            )
+        })
+        .collect()
+}
+
+#[test]
+fn the_permutation_maps_the_entry_order_to_the_drawn_order() {
+    let config = EventConfig::builder().build().unwrap();
+    for seed in 0..10 {
+        let saved =
+            try_generate_draw_with_rng(windows(), &config, &mut StdRng::seed_from_u64(seed))
+                .unwrap();
+
+        assert_eq!(saved.witnesses.len(), 3);
+        for (window, witness) in windows().iter().zip(&saved.witnesses) {
+            let entered: Vec<&str> = window.competitors.iter().map(|c| c.name.as_str()).collect();
+            assert_eq!(witness.entry_order, entered);
+            let mapped: Vec<&str> = witness
+                .permutation
+                .iter()
+                .map(|&entry| witness.entry_order[entry].as_str())
+                .collect();
+            assert_eq!(witness.drawn_order, mapped, "seed {}", seed);
+        }
+    }
+}
+
+#[test]
+fn the_witness_lists_both_orders_side_by_side() {
+    let config = EventConfig::builder().build().unwrap();
+    let saved =
+        try_generate_draw_with_rng(windows(), &config, &mut StdRng::seed_from_u64(3)).unwrap();
+    let text = export_witness(&saved, Some(3));
+
+    assert!(text.starts_with("# draw witness, seed 3\n"));
+    let first = &saved.witnesses[0];
+    assert!(text.contains(&format!(
+        "1. 1 Competitor 0 | 1. {} | {}\n",
+        first.drawn_order[0],
+        first.permutation[0] + 1
+    )));
+    assert_eq!(text.matches(" | ").count(), 2 * (3 + 18));
+}
+
+#[test]
+fn the_witness_records_the_displacement_selection() {
+    let config = EventConfig::builder()
+        .displacement_selection(DisplacementSelection::Volunteer)
+        .build()
+        .unwrap();
+    let saved =
+        try_generate_draw_with_rng(windows(), &config, &mut StdRng::seed_from_u64(3)).unwrap();
+    assert_eq!(
+        saved.displacement_selection,
+        Some(DisplacementSelection::Volunteer)
+    );
+    let text = export_witness(&saved, Some(3));
+    assert!(text.starts_with(
+        "# draw witness, seed 3\ndisplacement selection: volunteer\n\
+         stabilization thresholds: 3.00\n\nWindow 1\n"
+    ));
+    let config = EventConfig::builder().build().unwrap();
+    let saved =
+        try_generate_draw_with_rng(windows(), &config, &mut StdRng::seed_from_u64(3)).unwrap();
+    assert!(export_witness(&saved, None).contains("displacement selection: deque-ends\n"));
+}
+
+#[test]
+fn the_witness_records_every_stabilization_threshold() {
+    // a crowded first window sends competitors on to the others
+    let crowded = || {
+        let mut windows = windows();
+        windows[0].duration = 10;
+        windows
+    };
+    let config = EventConfig::builder().build().unwrap();
+    let saved =
+        try_generate_draw_with_rng(crowded(), &config, &mut StdRng::seed_from_u64(3)).unwrap();
+    assert!(saved.thresholds.len() > 1);
+    assert!(saved.thresholds.iter().all(|&threshold| threshold == 3.0));
+    let text = export_witness(&saved, Some(3));
+    let thresholds = vec!["3.00"; saved.thresholds.len()].join(", ");
+    assert!(text.starts_with(&format!(
+        "# draw witness, seed 3\ndisplacement selection: deque-ends\n\
+         stabilization thresholds: {}\n\nWindow 1\n",
+        thresholds
+    )));
+
+    // adaptive, each iteration balances against the mean spacing it finds
+    let adaptive = EventConfig::builder()
+        .adaptive_threshold(true)
+        .build()
+        .unwrap();
+    let saved =
+        try_generate_draw_with_rng(crowded(), &adaptive, &mut StdRng::seed_from_u64(3)).unwrap();
+    assert!(saved.thresholds.len() > 1);
+    let (first, last) = (saved.thresholds[0], *saved.thresholds.last().unwrap());
+    assert!(
+        (first - (10.0 / 6.0 + 5.0 + 5.0) / 3.0).abs() < 1e-9,
+        "{}",
+        first
+    );
+    assert_ne!(first, last);
+}